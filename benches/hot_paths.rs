@@ -0,0 +1,174 @@
+// Copyright (c) 2025 rezk_nightky
+
+// Timings for the per-frame hot paths at a few terminal sizes. Plain
+// `harness = false` loops with no outside crates:
+//
+//     cargo bench --features bench
+//
+// Pass a substring to run only the matching benches, e.g. `-- rain`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use cosmostrix::charset::{build_chars, Charset};
+use cosmostrix::cloud::{Cloud, DrawCtx, GlitchMap};
+use cosmostrix::droplet::Droplet;
+use cosmostrix::frame::{changed_cells, Frame};
+use cosmostrix::palette::{build_palette, scheme_palette};
+use cosmostrix::runtime::{BoldMode, ColorMode, ColorScheme, ShadeCurve, ShadingMode};
+
+const SIZES: [(u16, u16); 3] = [(80, 24), (200, 60), (400, 120)];
+const TICK: Duration = Duration::from_micros(16_667);
+// Each bench runs for at least this long after a short warm-up.
+const TARGET: Duration = Duration::from_millis(500);
+
+fn bench(filter: &Option<String>, name: &str, mut f: impl FnMut()) {
+    if filter.as_deref().is_some_and(|p| !name.contains(p)) {
+        return;
+    }
+    for _ in 0..10 {
+        f();
+    }
+    let start = Instant::now();
+    let mut iters = 0u64;
+    while start.elapsed() < TARGET {
+        f();
+        iters += 1;
+    }
+    let per = start.elapsed().as_nanos() as f64 / iters as f64;
+    println!("{:<32} {:>12.0} ns/iter ({} iters)", name, per, iters);
+}
+
+fn cloud(cols: u16, lines: u16, start: Instant) -> Cloud {
+    let mut cloud = Cloud::new(
+        ColorMode::TrueColor,
+        false,
+        ShadingMode::Random,
+        BoldMode::Random,
+        false,
+        false,
+        ColorScheme::Green,
+        None,
+    );
+    cloud.set_seed(7);
+    cloud.init_chars(build_chars(Charset::DEFAULT, &[], true));
+    cloud.reset_at(cols, lines, start);
+    cloud
+}
+
+fn bench_rain(filter: &Option<String>) {
+    for (cols, lines) in SIZES {
+        let start = Instant::now();
+        let mut c = cloud(cols, lines, start);
+        let mut frame = Frame::new(cols, lines, c.palette.bg);
+        let mut t = start;
+        // Let the screen fill up before timing.
+        for _ in 0..600 {
+            t += TICK;
+            c.rain_at(&mut frame, t);
+        }
+        bench(filter, &format!("rain {}x{}", cols, lines), || {
+            t += TICK;
+            c.rain_at(&mut frame, t);
+            black_box(&frame);
+        });
+    }
+}
+
+fn bench_droplet_draw(filter: &Option<String>) {
+    for (cols, lines) in SIZES {
+        let now = Instant::now();
+        let palette = scheme_palette(ColorScheme::Green, ColorMode::TrueColor, false, None);
+        let color_map = vec![1u8; cols as usize * lines as usize];
+        let char_pool = build_chars(Charset::DEFAULT, &[], true);
+        let glitch_map = GlitchMap::default();
+        let ctx = DrawCtx {
+            lines,
+            col_base: 0,
+            full_width: false,
+            shading_distance: false,
+            shading_speed: false,
+            chars_per_sec: 8.0,
+            min_speed: 8.0,
+            shade_curve: ShadeCurve::Linear,
+            shade_len: 0,
+            head_len: 1,
+            tail_ghost: 1,
+            bg: palette.bg,
+            color_mode: ColorMode::TrueColor,
+            bold_mode: BoldMode::Random,
+            glitchy: false,
+            last_glitch_time: now,
+            next_glitch_time: now,
+            glitch_timers: &[],
+            glitch_ms: 0,
+            palette: &palette,
+            palette_t: 0.0,
+            color_map: &color_map,
+            glitch_map: &glitch_map,
+            char_pool: &char_pool,
+            column_pool: &[],
+        };
+        let mut frame = Frame::new(cols, lines, palette.bg);
+        let mut d = Droplet::new();
+        d.bound_col = cols / 2;
+        d.end_line = lines - 1;
+        d.length = lines;
+        d.char_pool_idx = 0;
+        d.head_put_line = lines - 1;
+        d.activate(now);
+        bench(
+            filter,
+            &format!("droplet draw full column {}", lines),
+            || {
+                d.draw(&ctx, &mut frame, now, Duration::ZERO, true);
+                black_box(&frame);
+            },
+        );
+    }
+}
+
+fn bench_diff(filter: &Option<String>) {
+    for (cols, lines) in SIZES {
+        let start = Instant::now();
+        let mut c = cloud(cols, lines, start);
+        let mut frame = Frame::new(cols, lines, c.palette.bg);
+        let mut t = start;
+        for _ in 0..600 {
+            t += TICK;
+            c.rain_at(&mut frame, t);
+        }
+        let last = frame.clone();
+        t += TICK;
+        c.rain_at(&mut frame, t);
+        bench(filter, &format!("diff {}x{}", cols, lines), || {
+            black_box(changed_cells(Some(&last), &frame));
+        });
+        bench(filter, &format!("diff full {}x{}", cols, lines), || {
+            black_box(changed_cells(None, &frame));
+        });
+    }
+}
+
+fn bench_palette(filter: &Option<String>) {
+    let modes = [
+        ColorMode::Color16,
+        ColorMode::Color256,
+        ColorMode::TrueColor,
+    ];
+    bench(filter, "palette all schemes", || {
+        for mode in modes {
+            for scheme in ColorScheme::BUILTIN {
+                black_box(build_palette(scheme, mode, false, None));
+            }
+        }
+    });
+}
+
+fn main() {
+    let filter = std::env::args().skip(1).find(|a| !a.starts_with('-'));
+    bench_rain(&filter);
+    bench_droplet_draw(&filter);
+    bench_diff(&filter);
+    bench_palette(&filter);
+}
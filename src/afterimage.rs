@@ -0,0 +1,132 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::time::Duration;
+
+use crate::cell::{Cell, Color};
+use crate::clock::Instant;
+use crate::frame::Frame;
+use crate::palette::{color_to_rgb, lerp_color, rgb_to_ansi256};
+use crate::runtime::ColorMode;
+
+// Dimmer colors a vacated cell steps through before it goes blank.
+const DECAY_STEPS: u32 = 3;
+
+#[derive(Clone, Copy, Debug)]
+struct Trail {
+    cell: Cell,
+    since: Instant,
+}
+
+// `--decay`: glyphs the tails leave behind, kept per cell (column by column,
+// like the glitch map) and redrawn dimmer each step until they run out.
+#[derive(Clone, Debug)]
+pub struct AfterImage {
+    decay: Duration,
+    lines: u16,
+    cells: Vec<Option<Trail>>,
+}
+
+impl AfterImage {
+    pub fn new(decay: Duration) -> Self {
+        Self {
+            decay,
+            lines: 0,
+            cells: Vec::new(),
+        }
+    }
+
+    pub fn resize(&mut self, cols: u16, lines: u16) {
+        self.lines = lines;
+        self.cells.clear();
+        self.cells.resize(cols as usize * lines as usize, None);
+    }
+
+    fn index(&self, col: u16, line: u16) -> Option<usize> {
+        (line < self.lines).then(|| col as usize * self.lines as usize + line as usize)
+    }
+
+    // Keeps what `frame` shows at the cell as it is vacated. A cell already
+    // fading keeps its own start.
+    pub fn record(&mut self, frame: &Frame, col: u16, line: u16, now: Instant) {
+        let Some(i) = self.index(col, line) else {
+            return;
+        };
+        let Some(&cell) = frame.get(col, line) else {
+            return;
+        };
+        match self.cells.get_mut(i) {
+            Some(slot @ None) if cell.ch != ' ' && !cell.is_continuation() => {
+                *slot = Some(Trail { cell, since: now })
+            }
+            _ => {}
+        }
+    }
+
+    // Drops the trails under a live droplet, which draws over them.
+    pub fn clear_span(&mut self, col: u16, from: u16, to: u16) {
+        for line in from..=to.min(self.lines.saturating_sub(1)) {
+            if let Some(slot) = self.index(col, line).and_then(|i| self.cells.get_mut(i)) {
+                *slot = None;
+            }
+        }
+    }
+
+    // Draws every trail at its current step, blanking the ones that ran out.
+    // `dimmest` stands in for blended colors below 256 colors.
+    pub fn draw(
+        &mut self,
+        frame: &mut Frame,
+        now: Instant,
+        bg: Option<Color>,
+        mode: ColorMode,
+        dimmest: Option<Color>,
+    ) {
+        let lines = self.lines.max(1) as usize;
+        let decay = self.decay.as_secs_f32().max(f32::EPSILON);
+        for (i, slot) in self.cells.iter_mut().enumerate() {
+            let Some(trail) = slot else {
+                continue;
+            };
+            let (col, line) = ((i / lines) as u16, (i % lines) as u16);
+            let age = now.saturating_duration_since(trail.since).as_secs_f32() / decay;
+            if age >= 1.0 {
+                *slot = None;
+                frame.set(col, line, Cell::blank_with_bg(bg));
+                continue;
+            }
+            let step = ((age * DECAY_STEPS as f32) as u32).min(DECAY_STEPS - 1);
+            let t = (step + 1) as f32 / (DECAY_STEPS + 1) as f32;
+            frame.set(
+                col,
+                line,
+                Cell {
+                    fg: fade(trail.cell.fg, bg, t, mode, dimmest),
+                    bg,
+                    bold: false,
+                    ..trail.cell
+                },
+            );
+        }
+    }
+}
+
+// `fg` moved `t` of the way toward the background (black when it is the
+// terminal's own).
+fn fade(
+    fg: Option<Color>,
+    bg: Option<Color>,
+    t: f32,
+    mode: ColorMode,
+    dimmest: Option<Color>,
+) -> Option<Color> {
+    let fg = fg?;
+    let to = bg.unwrap_or(Color::Rgb { r: 0, g: 0, b: 0 });
+    match mode {
+        ColorMode::TrueColor => Some(lerp_color(fg, to, t)),
+        ColorMode::Color256 => {
+            let (r, g, b) = color_to_rgb(lerp_color(fg, to, t))?;
+            Some(Color::AnsiValue(rgb_to_ansi256(r, g, b)))
+        }
+        _ => dimmest.or(Some(fg)),
+    }
+}
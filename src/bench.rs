@@ -0,0 +1,72 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::time::{Duration, Instant};
+
+use crate::clock::ManualClock;
+use crate::cloud::Cloud;
+use crate::frame::{changed_cells, Frame};
+use crate::terminal::{self, queue_cells};
+
+// `cosmostrix bench`: runs the rain off screen for `frames` frames, each
+// one `1 / fps` of simulated time later, and reports how long stepping the
+// cloud and turning its changes into terminal output took. Nothing is
+// written to the terminal, so the numbers leave out the terminal itself.
+pub fn run(mut cloud: Cloud, size: (u16, u16), frames: u32, fps: f64) {
+    let clock = ManualClock::new(Instant::now());
+    cloud.set_clock(Box::new(clock.clone()));
+    cloud.reset(size.0, size.1);
+    let mut frame = Frame::new(size.0, size.1, cloud.palette.bg);
+    let mut last: Option<Frame> = None;
+    let mut out: Vec<u8> = Vec::new();
+    let period = Duration::from_secs_f64(1.0 / fps);
+
+    let mut totals: Vec<Duration> = Vec::with_capacity(frames.min(100_000) as usize);
+    let (mut sim, mut draw) = (Duration::ZERO, Duration::ZERO);
+    let mut bytes = 0usize;
+    for _ in 0..frames {
+        if terminal::interrupted() {
+            break;
+        }
+        clock.advance(period);
+        let start = Instant::now();
+        cloud.rain(&mut frame);
+        let stepped = Instant::now();
+        let cells = changed_cells(last.as_ref(), &frame);
+        out.clear();
+        let _ = queue_cells(&mut out, &frame, &cells, (0, 0));
+        let drawn = Instant::now();
+
+        sim += stepped - start;
+        draw += drawn - stepped;
+        totals.push(drawn - start);
+        bytes += out.len();
+        match last.as_mut() {
+            Some(l) => l.clone_from(&frame),
+            None => last = Some(frame.clone()),
+        }
+    }
+
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    // Ctrl-C stops the run early; report the frames that did run.
+    let ran = totals.len();
+    let n = ran.max(1) as f64;
+    totals.sort();
+    let p99 = totals
+        .get((totals.len() * 99 / 100).min(totals.len().saturating_sub(1)))
+        .copied()
+        .unwrap_or_default();
+    let worst = totals.last().copied().unwrap_or_default();
+    let busy = (sim + draw).as_secs_f64();
+    println!(
+        "{} frames at {}x{}, {} fps of simulated time",
+        ran, size.0, size.1, fps
+    );
+    println!("step:   {:.3} ms/frame", ms(sim) / n);
+    println!("draw:   {:.3} ms/frame", ms(draw) / n);
+    println!("p99:    {:.3} ms", ms(p99));
+    println!("worst:  {:.3} ms", ms(worst));
+    println!("output: {:.0} bytes/frame", bytes as f64 / n);
+    if busy > 0.0 {
+        println!("max:    {:.0} fps", n / busy);
+    }
+}
@@ -1,6 +1,33 @@
 // Copyright (c) 2025 rezk_nightky
 
-use crossterm::style::Color;
+#[cfg(not(target_arch = "wasm32"))]
+pub use crossterm::style::Color;
+use unicode_width::UnicodeWidthChar;
+
+// Mirror of crossterm's color type for targets crossterm does not build on.
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    Reset,
+    Black,
+    DarkGrey,
+    Red,
+    DarkRed,
+    Green,
+    DarkGreen,
+    Yellow,
+    DarkYellow,
+    Blue,
+    DarkBlue,
+    Magenta,
+    DarkMagenta,
+    Cyan,
+    DarkCyan,
+    White,
+    Grey,
+    Rgb { r: u8, g: u8, b: u8 },
+    AnsiValue(u8),
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Cell {
@@ -11,15 +38,29 @@ pub struct Cell {
 }
 
 impl Cell {
-    pub fn blank() -> Self {
+    // Placeholder for the right half of a double-width glyph.
+    pub const CONTINUATION: char = '\0';
+
+    pub fn continuation(bg: Option<Color>) -> Self {
         Self {
-            ch: ' ',
+            ch: Self::CONTINUATION,
             fg: None,
-            bg: None,
+            bg,
             bold: false,
         }
     }
 
+    pub fn is_continuation(&self) -> bool {
+        self.ch == Self::CONTINUATION
+    }
+
+    pub fn width(&self) -> u16 {
+        if self.is_continuation() {
+            return 0;
+        }
+        self.ch.width().unwrap_or(1).clamp(1, 2) as u16
+    }
+
     pub fn blank_with_bg(bg: Option<Color>) -> Self {
         Self {
             ch: ' ',
@@ -2,6 +2,11 @@
 
 use std::char;
 
+use unicode_general_category::{get_general_category, GeneralCategory};
+use unicode_width::UnicodeWidthChar;
+
+use crate::error::{CosmostrixError, Result};
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Charset(u32);
 
@@ -24,36 +29,81 @@ impl Charset {
     pub const DEFAULT: Charset = Charset(0x7);
     pub const EXTENDED_DEFAULT: Charset = Charset(0xE);
 
+    pub const ASCII_SHUFFLE: [Charset; 5] = [
+        Charset::DEFAULT,
+        Charset::ENGLISH_LETTERS,
+        Charset::ENGLISH_DIGITS,
+        Charset::BINARY,
+        Charset::HEX,
+    ];
+
+    pub const UNICODE_SHUFFLE: [Charset; 9] = [
+        Charset::EXTENDED_DEFAULT,
+        Charset::KATAKANA,
+        Charset::GREEK,
+        Charset::CYRILLIC,
+        Charset::ARABIC,
+        Charset::HEBREW,
+        Charset::DEVANAGARI,
+        Charset::BRAILLE,
+        Charset::RUNIC,
+    ];
+
+    // Single-set names accepted by `--charset`, in menu order.
+    pub const NAMED: [(&'static str, Charset); 15] = [
+        ("ascii", Charset::DEFAULT),
+        ("extended", Charset::EXTENDED_DEFAULT),
+        ("english", Charset::ENGLISH_LETTERS),
+        ("digits", Charset::ENGLISH_DIGITS),
+        ("punc", Charset::ENGLISH_PUNCTUATION),
+        ("binary", Charset::BINARY),
+        ("hex", Charset::HEX),
+        ("katakana", Charset::KATAKANA),
+        ("greek", Charset::GREEK),
+        ("cyrillic", Charset::CYRILLIC),
+        ("arabic", Charset::ARABIC),
+        ("hebrew", Charset::HEBREW),
+        ("devanagari", Charset::DEVANAGARI),
+        ("braille", Charset::BRAILLE),
+        ("runic", Charset::RUNIC),
+    ];
+
+    // Other spellings `--charset` takes for some of the sets above.
+    pub const ALIASES: [(&'static str, Charset); 4] = [
+        ("dec", Charset::ENGLISH_DIGITS),
+        ("decimal", Charset::ENGLISH_DIGITS),
+        ("bin", Charset::BINARY),
+        ("hexadecimal", Charset::HEX),
+    ];
+
     pub fn contains(self, other: Charset) -> bool {
         (self.0 & other.0) != 0
     }
 
-    pub fn or(self, other: Charset) -> Charset {
-        Charset(self.0 | other.0)
+    pub fn is_ascii(self) -> bool {
+        self.0 & !0x307 == 0
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct CharRanges {
-    pub ranges: Vec<(char, char)>,
-}
-
-pub fn parse_user_hex_chars(s: &str) -> Result<Vec<char>, String> {
+pub fn parse_user_hex_chars(s: &str) -> Result<Vec<char>> {
     let mut out = Vec::new();
     for (i, part) in s.split(',').enumerate() {
         let part = part.trim();
         if part.is_empty() {
             continue;
         }
-        let v = u32::from_str_radix(part, 16)
-            .map_err(|_| format!("invalid hex char at index {}", i + 1))?;
-        let ch = char::from_u32(v).ok_or_else(|| format!("invalid unicode scalar at index {}", i + 1))?;
+        let v = u32::from_str_radix(part, 16).map_err(|_| {
+            CosmostrixError::InvalidChars(format!("invalid hex char at index {}", i + 1))
+        })?;
+        let ch = char::from_u32(v).ok_or_else(|| {
+            CosmostrixError::InvalidChars(format!("invalid unicode scalar at index {}", i + 1))
+        })?;
         out.push(ch);
     }
     Ok(out)
 }
 
-pub fn charset_from_str(spec: &str, default_to_ascii: bool) -> Result<Charset, String> {
+pub fn charset_from_str(spec: &str, default_to_ascii: bool) -> Result<Charset> {
     let spec = spec.trim().to_ascii_lowercase();
     match spec.as_str() {
         "auto" => Ok(if default_to_ascii {
@@ -61,34 +111,47 @@ pub fn charset_from_str(spec: &str, default_to_ascii: bool) -> Result<Charset, S
         } else {
             Charset::EXTENDED_DEFAULT
         }),
-        "ascii" => Ok(Charset::DEFAULT),
-        "extended" => Ok(Charset::EXTENDED_DEFAULT),
-        "english" => Ok(Charset::ENGLISH_LETTERS),
-        "digits" | "dec" | "decimal" => Ok(Charset::ENGLISH_DIGITS),
-        "punc" => Ok(Charset::ENGLISH_PUNCTUATION),
-        "bin" | "binary" => Ok(Charset::BINARY),
-        "hex" | "hexadecimal" => Ok(Charset::HEX),
-        "katakana" => Ok(Charset::KATAKANA),
-        "greek" => Ok(Charset::GREEK),
-        "cyrillic" => Ok(Charset::CYRILLIC),
-        "arabic" => Ok(Charset::ARABIC),
-        "hebrew" => Ok(Charset::HEBREW),
-        "devanagari" => Ok(Charset::DEVANAGARI),
-        "braille" => Ok(Charset::BRAILLE),
-        "runic" => Ok(Charset::RUNIC),
-        _ => Err(format!("unsupported charset: {}", spec)),
+        name => Charset::NAMED
+            .iter()
+            .chain(&Charset::ALIASES)
+            .find(|(n, _)| *n == name)
+            .map(|&(_, c)| c)
+            .ok_or(CosmostrixError::UnknownCharset(spec)),
+    }
+}
+
+// Whether `ch` puts something on screen in a cell of its own.
+pub fn is_visible(ch: char) -> bool {
+    match get_general_category(ch) {
+        GeneralCategory::Control
+        | GeneralCategory::Format
+        | GeneralCategory::NonspacingMark
+        | GeneralCategory::EnclosingMark
+        | GeneralCategory::SpaceSeparator
+        | GeneralCategory::LineSeparator
+        | GeneralCategory::ParagraphSeparator
+        | GeneralCategory::PrivateUse
+        | GeneralCategory::Surrogate
+        | GeneralCategory::Unassigned => false,
+        _ => ch.width().unwrap_or(0) > 0,
     }
 }
 
 fn push_range(out: &mut Vec<char>, start: u32, end: u32) {
     for v in start..=end {
         if let Some(ch) = char::from_u32(v) {
-            out.push(ch);
+            if is_visible(ch) {
+                out.push(ch);
+            }
         }
     }
 }
 
-pub fn build_chars(mut charset: Charset, user_ranges: &[(char, char)], default_to_ascii: bool) -> Vec<char> {
+pub fn build_chars(
+    mut charset: Charset,
+    user_ranges: &[(char, char)],
+    default_to_ascii: bool,
+) -> Vec<char> {
     if charset == Charset::NONE && user_ranges.is_empty() {
         charset = if default_to_ascii {
             Charset::DEFAULT
@@ -146,13 +209,7 @@ pub fn build_chars(mut charset: Charset, user_ranges: &[(char, char)], default_t
     }
 
     for &(a, b) in user_ranges {
-        let start = a as u32;
-        let end = b as u32;
-        for v in start..=end {
-            if let Some(ch) = char::from_u32(v) {
-                out.push(ch);
-            }
-        }
+        push_range(&mut out, a as u32, b as u32);
     }
 
     if out.is_empty() {
@@ -162,3 +219,135 @@ pub fn build_chars(mut charset: Charset, user_ranges: &[(char, char)], default_t
 
     out
 }
+
+// The encoding of a legacy (non-UTF-8) locale. Glyphs outside it would
+// come out as garbage, so the cloud keeps only the ones it can encode. Only
+// the letters the charsets use are listed; symbols and box drawing are not.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Codepage {
+    Ascii,
+    // ISO-8859-1, ISO-8859-15 and CP1252, which share every glyph the
+    // charsets use.
+    Latin,
+    Iso8859_5,
+    Cp1251,
+    Koi8R,
+    Koi8U,
+    Iso8859_7,
+    Iso8859_6,
+    Iso8859_8,
+    Cp1255,
+    EucJp,
+    ShiftJis,
+}
+
+impl Codepage {
+    // The codepage named by a locale such as `ru_RU.KOI8-R` or
+    // `el_GR.ISO-8859-7@euro`. None for UTF-8 and for locales that don't
+    // name their codeset, which are left alone.
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let (_, codeset) = locale.split_once('.')?;
+        let codeset = codeset.split('@').next().unwrap_or("");
+        let name: String = codeset
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        match name.as_str() {
+            "ansix341968" | "ascii" | "usascii" => Some(Codepage::Ascii),
+            "iso88591" | "iso885915" | "latin1" | "latin9" | "cp1252" | "windows1252" => {
+                Some(Codepage::Latin)
+            }
+            "iso88595" => Some(Codepage::Iso8859_5),
+            "cp1251" | "windows1251" => Some(Codepage::Cp1251),
+            "koi8r" => Some(Codepage::Koi8R),
+            "koi8u" => Some(Codepage::Koi8U),
+            "iso88597" | "cp1253" | "windows1253" => Some(Codepage::Iso8859_7),
+            "iso88596" | "cp1256" | "windows1256" => Some(Codepage::Iso8859_6),
+            "iso88598" => Some(Codepage::Iso8859_8),
+            "cp1255" | "windows1255" => Some(Codepage::Cp1255),
+            "eucjp" | "ujis" => Some(Codepage::EucJp),
+            "sjis" | "shiftjis" | "cp932" => Some(Codepage::ShiftJis),
+            _ => None,
+        }
+    }
+
+    pub fn can_encode(self, ch: char) -> bool {
+        let c = ch as u32;
+        if c < 0x80 {
+            return true;
+        }
+        let cyrillic_iso =
+            matches!(c, 0x401..=0x40C | 0x40E..=0x44F | 0x451..=0x45C | 0x45E | 0x45F);
+        let koi8r = matches!(c, 0x410..=0x44F | 0x401 | 0x451);
+        let greek = matches!(c, 0x391..=0x3A1 | 0x3A3..=0x3A9 | 0x3B1..=0x3C1 | 0x3C3..=0x3C9);
+        match self {
+            Codepage::Ascii => false,
+            Codepage::Latin => (0xA0..=0xFF).contains(&c),
+            Codepage::Iso8859_5 => cyrillic_iso,
+            Codepage::Cp1251 => cyrillic_iso || matches!(c, 0x490 | 0x491),
+            Codepage::Koi8R => koi8r,
+            Codepage::Koi8U => {
+                koi8r
+                    || matches!(
+                        c,
+                        0x404 | 0x406 | 0x407 | 0x454 | 0x456 | 0x457 | 0x490 | 0x491
+                    )
+            }
+            Codepage::Iso8859_7 => {
+                matches!(c, 0x384..=0x386 | 0x388..=0x38A | 0x38C | 0x38E..=0x3A1 | 0x3A3..=0x3CE)
+            }
+            Codepage::Iso8859_6 => {
+                matches!(c, 0x60C | 0x61B | 0x61F | 0x621..=0x63A | 0x640..=0x652)
+            }
+            Codepage::Iso8859_8 => matches!(c, 0x5D0..=0x5EA),
+            Codepage::Cp1255 => {
+                matches!(c, 0x5B0..=0x5B9 | 0x5BB..=0x5C3 | 0x5D0..=0x5EA | 0x5F0..=0x5F4)
+            }
+            // JIS X 0201 half-width katakana, plus the Greek and Cyrillic
+            // letters of JIS X 0208.
+            Codepage::EucJp | Codepage::ShiftJis => matches!(c, 0xFF61..=0xFF9F) || greek || koi8r,
+        }
+    }
+
+    // Keeps the glyphs this codepage can show. Greek and Cyrillic letters it
+    // can't are swapped for the Latin letters they transliterate to, so
+    // `--charset cyrillic` on a Latin-1 locale still rains letters;
+    // everything else is dropped.
+    pub fn fit(self, chars: Vec<char>) -> Vec<char> {
+        let mut out: Vec<char> = Vec::with_capacity(chars.len());
+        for ch in chars {
+            let ch = match self.can_encode(ch) {
+                true => Some(ch),
+                false => transliterate(ch).filter(|&t| self.can_encode(t)),
+            };
+            if let Some(ch) = ch.filter(|c| !out.contains(c)) {
+                out.push(ch);
+            }
+        }
+        out
+    }
+}
+
+// The Latin letter closest to a Greek or Cyrillic one.
+fn transliterate(ch: char) -> Option<char> {
+    const CYRILLIC: &[u8; 32] = b"ABVGDEZZIJKLMNOPRSTUFHCCSS'Y'EUA";
+    const GREEK: &[u8; 25] = b"ABGDEZHTIKLMNXOPR STYFXPO";
+    let c = ch as u32;
+    let (table, i, lower) = match c {
+        0x410..=0x42F => (&CYRILLIC[..], c - 0x410, false),
+        0x430..=0x44F => (&CYRILLIC[..], c - 0x430, true),
+        0x401 => return Some('E'),
+        0x451 => return Some('e'),
+        0x391..=0x3A9 => (&GREEK[..], c - 0x391, false),
+        0x3B1..=0x3C9 => (&GREEK[..], c - 0x3B1, true),
+        _ => return None,
+    };
+    let t = table[i as usize] as char;
+    match t {
+        ' ' if lower => Some('s'),
+        ' ' => None,
+        t if lower => Some(t.to_ascii_lowercase()),
+        t => Some(t),
+    }
+}
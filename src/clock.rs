@@ -0,0 +1,230 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+
+#[cfg(target_arch = "wasm32")]
+pub use self::manual::{set_now_ms, Instant};
+
+// Where a cloud reads the time. The cloud never calls `Instant::now` itself,
+// so tests, replays and slow motion can swap in their own clock.
+pub trait Clock {
+    fn now(&self) -> Instant;
+    fn box_clone(&self) -> Box<dyn Clock>;
+}
+
+impl Clone for Box<dyn Clock> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+// The system clock (or, on wasm, the time the host last passed in).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RealClock;
+
+impl Clock for RealClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn box_clone(&self) -> Box<dyn Clock> {
+        Box::new(*self)
+    }
+}
+
+// Time that only moves when `advance` is called. Clones share the same
+// time, so a test can keep one and hand the other to a cloud.
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    start: Instant,
+    micros: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    pub fn new(start: Instant) -> Self {
+        Self {
+            start,
+            micros: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn advance(&self, dt: Duration) {
+        self.micros
+            .fetch_add(dt.as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + Duration::from_micros(self.micros.load(Ordering::Relaxed))
+    }
+
+    fn box_clone(&self) -> Box<dyn Clock> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Scale {
+    factor: f64,
+    // The inner time and the scaled time at the last change of `factor`.
+    inner: Instant,
+    scaled: Instant,
+}
+
+// Runs `inner` at `factor` times its speed: 0.5 for slow motion, 2 for fast
+// forward, 0 to stand still. Clones share the factor, so one handle can
+// change the speed of every cloud using the clock.
+#[derive(Clone)]
+pub struct ScaledClock {
+    inner: Box<dyn Clock>,
+    scale: Arc<Mutex<Scale>>,
+}
+
+impl ScaledClock {
+    pub fn new(inner: Box<dyn Clock>, factor: f64) -> Self {
+        let now = inner.now();
+        Self {
+            inner,
+            scale: Arc::new(Mutex::new(Scale {
+                factor: factor.max(0.0),
+                inner: now,
+                scaled: now,
+            })),
+        }
+    }
+
+    pub fn factor(&self) -> f64 {
+        self.scale.lock().map(|s| s.factor).unwrap_or(1.0)
+    }
+
+    // Changes the speed from now on; time already passed keeps its scale.
+    pub fn set_factor(&self, factor: f64) {
+        let inner = self.inner.now();
+        let scaled = self.now();
+        if let Ok(mut s) = self.scale.lock() {
+            *s = Scale {
+                factor: factor.max(0.0),
+                inner,
+                scaled,
+            };
+        }
+    }
+}
+
+impl Clock for ScaledClock {
+    fn now(&self) -> Instant {
+        let Ok(s) = self.scale.lock() else {
+            return self.inner.now();
+        };
+        s.scaled
+            + self
+                .inner
+                .now()
+                .saturating_duration_since(s.inner)
+                .mul_f64(s.factor)
+    }
+
+    fn box_clone(&self) -> Box<dyn Clock> {
+        Box::new(self.clone())
+    }
+}
+
+// Stands still while paused and carries on from the same time afterwards,
+// so everything timed by it sees no gap. Unlike the clocks above, clones
+// keep their own pause state; they share only the inner clock.
+#[derive(Clone)]
+pub struct PausableClock {
+    inner: Box<dyn Clock>,
+    // The inner time the pause began, while paused.
+    paused_at: Option<Instant>,
+    // The total time spent paused.
+    lost: Duration,
+}
+
+impl PausableClock {
+    pub fn new(inner: Box<dyn Clock>) -> Self {
+        Self {
+            inner,
+            paused_at: None,
+            lost: Duration::ZERO,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    pub fn pause(&mut self) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(self.inner.now());
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if let Some(t) = self.paused_at.take() {
+            self.lost += self.inner.now().saturating_duration_since(t);
+        }
+    }
+}
+
+impl Clock for PausableClock {
+    fn now(&self) -> Instant {
+        let inner = self.paused_at.unwrap_or_else(|| self.inner.now());
+        inner.checked_sub(self.lost).unwrap_or(inner)
+    }
+
+    fn box_clone(&self) -> Box<dyn Clock> {
+        Box::new(self.clone())
+    }
+}
+
+// wasm32-unknown-unknown has no clock; the host advances this one each frame.
+#[cfg(target_arch = "wasm32")]
+mod manual {
+    use std::ops::{Add, AddAssign};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+
+    static NOW_US: AtomicU64 = AtomicU64::new(0);
+
+    pub fn set_now_ms(ms: f64) {
+        NOW_US.store((ms.max(0.0) * 1000.0) as u64, Ordering::Relaxed);
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Instant(Duration);
+
+    impl Instant {
+        pub fn now() -> Self {
+            Self(Duration::from_micros(NOW_US.load(Ordering::Relaxed)))
+        }
+
+        pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+            self.0.saturating_sub(earlier.0)
+        }
+
+        pub fn checked_sub(&self, d: Duration) -> Option<Instant> {
+            self.0.checked_sub(d).map(Instant)
+        }
+    }
+
+    impl Add<Duration> for Instant {
+        type Output = Instant;
+
+        fn add(self, rhs: Duration) -> Instant {
+            Instant(self.0 + rhs)
+        }
+    }
+
+    impl AddAssign<Duration> for Instant {
+        fn add_assign(&mut self, rhs: Duration) {
+            self.0 += rhs;
+        }
+    }
+}
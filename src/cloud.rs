@@ -1,30 +1,185 @@
 // Copyright (c) 2025 rezk_nightky
 
-use std::time::{Duration, Instant};
+use std::ops::Range;
+use std::time::Duration;
 
-use crossterm::style::Color;
-use rand::{distributions::Uniform, prelude::Distribution, rngs::StdRng, SeedableRng};
+use rand::{distr::Uniform, prelude::Distribution, rngs::SmallRng, RngCore, SeedableRng};
 
 use crate::{
-    cell::Cell,
+    afterimage::AfterImage,
+    cell::Color,
+    charset::{is_visible, Codepage},
+    clock::{Clock, Instant, PausableClock, RealClock},
     frame::Frame,
-    palette::{build_palette, Palette},
-    runtime::{BoldMode, ColorMode, ColorScheme, ShadingMode, UserColors},
+    heatmap::HeatMap,
+    palette::{
+        apply_light, apply_overrides, blend_palettes, dim_palette, scheme_palette, Palette,
+        PaletteSource,
+    },
+    pool::Pool,
+    runtime::{
+        BoldMode, Collision, ColorMode, ColorOverride, ColorScheme, DensityProfile, GlitchMode,
+        ShadeCurve, ShadingMode, TypingMode, UserColors, WaveStart,
+    },
 };
 
 use crate::droplet::Droplet;
+use crate::effect::{CloudState, Effect};
+
+const FADE_DURATION: Duration = Duration::from_millis(1000);
+// Time `--wave-start` takes to reach the last column.
+const WAVE_DURATION: Duration = Duration::from_millis(3000);
+// How often an animated palette source repaints the whole screen.
+const PALETTE_REDRAW: Duration = Duration::from_millis(100);
+
+// Droplets advance in fixed 60 Hz steps; rendering interpolates the heads
+// between steps. After a long stall the simulation jumps ahead instead of
+// replaying every missed step.
+const SIM_STEP: Duration = Duration::from_micros(16_667);
+const MAX_SIM_STEPS: u32 = 15;
+
+// Slowest column speed in async mode, as a share of the full speed.
+const ASYNC_MIN_SPEED: f32 = 0.3333333;
+
+// `--auto-scale` keeps speeds and lengths in proportion to the screen
+// height, clamped to this range of factors.
+const MIN_SCALE: f32 = 0.5;
+const MAX_SCALE: f32 = 4.0;
+
+pub const DEFAULT_MAX_DROPLETS: usize = 20_000;
+
+const CHANCE_CHUNK: usize = 256;
+
+// One bit per cell, indexed like `color_map`; an eighth the size of a bool per
+// cell on huge terminals.
+#[derive(Clone, Default)]
+pub struct GlitchMap {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl GlitchMap {
+    pub fn get(&self, i: usize) -> bool {
+        i < self.len && (self.words[i / 64] >> (i % 64)) & 1 == 1
+    }
+
+    fn clear(&mut self) {
+        self.words.clear();
+        self.len = 0;
+    }
+
+    fn toggle(&mut self, i: usize) {
+        if i < self.len {
+            self.words[i / 64] ^= 1 << (i % 64);
+        }
+    }
+
+    // A random index whose bit is `on`, giving up after a few misses so a
+    // map that is (nearly) all one value doesn't spin.
+    fn pick(&self, on: bool, rng: &mut SmallRng) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        (0..64)
+            .map(|_| (rng.next_u64() % self.len as u64) as usize)
+            .find(|&i| self.get(i) == on)
+    }
+
+    // Sets each of `len` bits with probability `pct`, reusing the buffer.
+    fn fill(&mut self, len: usize, pct: f32, rng: &mut SmallRng) {
+        let thresh = (pct * 65536.0) as u64;
+        self.len = len;
+        self.words.clear();
+        self.words.resize(len.div_ceil(64), 0);
+        for word in &mut self.words {
+            for quarter in 0..16 {
+                let mut bits = rng.next_u64();
+                for b in 0..4 {
+                    if bits & 0xffff < thresh {
+                        *word |= 1 << (quarter * 4 + b);
+                    }
+                    bits >>= 16;
+                }
+            }
+        }
+    }
+}
+
+// `GlitchMode::Cell`: a glitched cell's own cycle, `period_ms` long and
+// shifted by `offset_ms` so neighbours flicker out of step.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GlitchTimer {
+    offset_ms: u16,
+    period_ms: u16,
+}
+
+impl GlitchTimer {
+    fn cycle(&self, ms: u64) -> u64 {
+        (ms + self.offset_ms as u64) / self.period_ms.max(1) as u64
+    }
+
+    // How far through its current cycle the cell is, from 0 to 1.
+    fn phase(&self, ms: u64) -> f64 {
+        let p = self.period_ms.max(1) as u64;
+        ((ms + self.offset_ms as u64) % p) as f64 / p as f64
+    }
+}
+
+// Uniform [0, 1) samples drawn from the RNG a chunk at a time.
+#[derive(Clone)]
+struct Chances {
+    buf: [f32; CHANCE_CHUNK],
+    pos: usize,
+}
+
+impl Chances {
+    fn new() -> Self {
+        Self {
+            buf: [0.0; CHANCE_CHUNK],
+            pos: CHANCE_CHUNK,
+        }
+    }
+
+    fn next(&mut self, rng: &mut SmallRng) -> f32 {
+        if self.pos == CHANCE_CHUNK {
+            for v in &mut self.buf {
+                *v = (rng.next_u32() >> 8) as f32 / (1u32 << 24) as f32;
+            }
+            self.pos = 0;
+        }
+        let v = self.buf[self.pos];
+        self.pos += 1;
+        v
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CharLoc {
     Middle,
     Tail,
-    Head,
+    // Cells from the leading one (0) back, within `--head-len`.
+    Head(u16),
 }
 
+#[derive(Clone, Copy)]
 pub struct DrawCtx<'a> {
     pub lines: u16,
+    // Column drawn at x = 0 of the target frame.
+    pub col_base: u16,
     pub full_width: bool,
     pub shading_distance: bool,
+    // `ShadingMode::Speed`: droplets are shaded by where their speed falls
+    // between `min_speed` and `chars_per_sec`.
+    pub shading_speed: bool,
+    pub chars_per_sec: f32,
+    pub min_speed: f32,
+    pub shade_curve: ShadeCurve,
+    // Cells below the head that the shading spans; 0 spans the whole droplet.
+    pub shade_len: u16,
+    // Cells at the front of a droplet drawn as its head, brightest first.
+    pub head_len: u16,
+    // Cells just ahead of an erasing tail drawn in the dimmest color.
+    pub tail_ghost: u16,
     pub bg: Option<Color>,
 
     pub color_mode: ColorMode,
@@ -33,11 +188,20 @@ pub struct DrawCtx<'a> {
 
     pub last_glitch_time: Instant,
     pub next_glitch_time: Instant,
-
-    pub palette_colors: &'a [Color],
+    // Per-cell timers in `GlitchMode::Cell`, empty otherwise, read at
+    // `glitch_ms` since the cloud's glitch epoch.
+    pub glitch_timers: &'a [GlitchTimer],
+    pub glitch_ms: u64,
+
+    pub palette: &'a dyn PaletteSource,
+    // Seconds into the rain, for palette sources that change over time.
+    pub palette_t: f32,
     pub color_map: &'a [u8],
-    pub glitch_map: &'a [bool],
+    pub glitch_map: &'a GlitchMap,
     pub char_pool: &'a [char],
+    // One glyph per cell, column by column, in stable glyph mode; empty
+    // otherwise.
+    pub column_pool: &'a [char],
 }
 
 impl DrawCtx<'_> {
@@ -80,14 +244,19 @@ impl DrawCtx<'_> {
             return false;
         }
         let idx = col as usize * self.lines as usize + line as usize;
-        self.glitch_map.get(idx).copied().unwrap_or(false)
+        self.glitch_map.get(idx)
     }
 
-    pub fn get_char(&self, line: u16, char_pool_idx: u16) -> char {
+    pub fn get_char(&self, line: u16, col: u16, char_pool_idx: u16) -> char {
+        if !self.column_pool.is_empty() {
+            let idx = col as usize * self.lines as usize + line as usize;
+            return self.column_pool.get(idx).copied().unwrap_or('0');
+        }
         let idx = ((char_pool_idx as usize) + (line as usize)) % self.char_pool.len().max(1);
         self.char_pool.get(idx).copied().unwrap_or('0')
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn get_attr(
         &self,
         line: u16,
@@ -97,6 +266,7 @@ impl DrawCtx<'_> {
         now: Instant,
         head_put_line: u16,
         length: u16,
+        speed: f32,
     ) -> (Option<Color>, bool) {
         let mut bold = false;
         if self.bold_mode == BoldMode::Random {
@@ -107,31 +277,49 @@ impl DrawCtx<'_> {
         let mut color_idx = self.color_map.get(idx).copied().unwrap_or(0) as i32;
 
         if self.shading_distance {
-            let n = self.palette_colors.len().max(1) as f32;
+            let n = self.palette.steps().max(1) as f32;
             let dist = (head_put_line.saturating_sub(line)) as f32;
-            let len = length.max(1) as f32;
-            let v = (n - 1.0) - (dist / len * (n - 1.0));
+            let len = match self.shade_len {
+                0 => length.max(1),
+                l => l,
+            } as f32;
+            let v = self.shade_curve.brightness(dist / len) * (n - 1.0);
             color_idx = v.round() as i32;
+        } else if self.shading_speed && self.min_speed < self.chars_per_sec {
+            // The brightest entry stays for the heads.
+            let n = self.palette.steps().saturating_sub(1).max(1) as f32;
+            let t = (speed - self.min_speed) / (self.chars_per_sec - self.min_speed);
+            color_idx = (t.clamp(0.0, 1.0) * (n - 1.0)).round() as i32;
         }
 
-        if self.glitchy && self.glitch_map.get(idx).copied().unwrap_or(false) {
-            if self.is_bright(now) {
+        if self.glitchy && self.glitch_map.get(idx) {
+            let (bright, dim) = match self.glitch_timers.get(idx) {
+                Some(t) => {
+                    let phase = t.phase(self.glitch_ms);
+                    (phase <= 0.25, phase >= 0.75)
+                }
+                None => (self.is_bright(now), self.is_dim(now)),
+            };
+            if bright {
                 color_idx += 1;
                 bold = true;
-            } else if self.is_dim(now) {
+            } else if dim {
                 color_idx -= 1;
                 bold = false;
             }
         }
 
-        let last = self.palette_colors.len().saturating_sub(1) as i32;
+        let last = self.palette.steps().saturating_sub(1) as i32;
         match loc {
             CharLoc::Tail => {
                 color_idx = 0;
                 bold = false;
             }
-            CharLoc::Head => {
-                color_idx = last;
+            CharLoc::Head(dist) => {
+                // Steps down from the brightest entry across the head, but
+                // never below the shade the cell would have anyway.
+                let fade = dist as i32 * last / self.head_len.max(1) as i32;
+                color_idx = (last - fade).max(color_idx.clamp(0, last.max(0)));
                 bold = true;
             }
             CharLoc::Middle => {
@@ -148,7 +336,12 @@ impl DrawCtx<'_> {
         let fg = if self.color_mode == ColorMode::Mono {
             None
         } else {
-            self.palette_colors.get(color_idx as usize).copied()
+            let pos = if last > 0 {
+                color_idx as f32 / last as f32
+            } else {
+                0.0
+            };
+            self.palette.sample(pos, self.palette_t)
         };
 
         (fg, bold)
@@ -158,18 +351,28 @@ impl DrawCtx<'_> {
 #[derive(Clone, Debug)]
 struct ColumnStatus {
     max_speed_pct: f32,
+    spawn_weight: f32,
+    load: f32,
     num_droplets: u8,
     can_spawn: bool,
+    // No droplets spawn before this, so `--wave-start` can open columns in turn.
+    start_time: Option<Instant>,
+}
+
+impl ColumnStatus {
+    fn speed_pct(&self) -> f32 {
+        self.max_speed_pct * (0.25 + 0.75 * self.load)
+    }
 }
 
 #[derive(Clone, Debug)]
-struct MsgChr {
-    line: u16,
-    col: u16,
-    val: char,
-    draw: bool,
+struct PaletteFade {
+    from: Palette,
+    to: Palette,
+    start: Instant,
 }
 
+#[derive(Clone)]
 pub struct Cloud {
     pub lines: u16,
     pub cols: u16,
@@ -179,6 +382,10 @@ pub struct Cloud {
 
     pub full_width: bool,
     pub shading_distance: bool,
+    pub shade_curve: ShadeCurve,
+    pub shade_len: u16,
+    head_len: u16,
+    tail_ghost: u16,
     pub bold_mode: BoldMode,
 
     pub async_mode: bool,
@@ -193,6 +400,7 @@ pub struct Cloud {
     pub glitch_pct: f32,
     pub glitch_low_ms: u16,
     pub glitch_high_ms: u16,
+    pub glitch_mode: GlitchMode,
 
     pub short_pct: f32,
     pub die_early_pct: f32,
@@ -200,23 +408,69 @@ pub struct Cloud {
     pub linger_high_ms: u16,
 
     pub max_droplets_per_column: u8,
+    collision: Collision,
+    // Live droplets as (column, head line, tail line, index), reused by `resolve_collisions`.
+    collision_order: Vec<(u16, u16, Option<u16>, usize)>,
+    pub speed_jitter: f32,
+    pub col_stride: u16,
+    pub col_offset: u16,
+    pub typing_mode: Option<TypingMode>,
+    pub threads: usize,
 
     droplets: Vec<Droplet>,
+    // Per droplet, its head line before the last step and whether the step
+    // freed its column; None if it wasn't alive.
+    steps: Vec<Option<(u16, bool)>>,
+    pool: Pool,
+    strips: Vec<Frame>,
+    strip_bounds: Vec<u16>,
     num_droplets: usize,
+    max_droplets: usize,
+    max_active: Option<usize>,
+    spawn_rate: Option<f32>,
+    // Screen height that `--auto-scale` treats as normal, and the factor
+    // that height gives for the current one.
+    scale_lines: Option<u16>,
+    scale: f32,
+    free_droplets: Vec<usize>,
 
     chars: Vec<char>,
+    // The legacy locale encoding `init_chars` fits glyphs to, if any.
+    codepage: Option<Codepage>,
     char_pool: Vec<char>,
     glitch_pool: Vec<char>,
     glitch_pool_idx: usize,
-
-    glitch_map: Vec<bool>,
+    stable_glyphs: bool,
+    column_pool: Vec<char>,
+
+    glitch_map: GlitchMap,
+    glitch_timers: Vec<GlitchTimer>,
+    glitch_epoch: Instant,
+    prev_glitch_ms: u64,
+    // `--glitch-drift`: glitched cells moved to new spots per glitch cycle.
+    glitch_drift: u16,
+    next_drift: Instant,
     color_map: Vec<u8>,
+    // `--reshade`: how often the color map takes a random-walk step.
+    reshade: Option<Duration>,
+    next_reshade: Instant,
 
     col_stat: Vec<ColumnStatus>,
+    // Columns a host application has taken for itself; kept across resets.
+    reserved_cols: Vec<bool>,
+    density_profile: DensityProfile,
+    typing_col: u16,
+    wave_start: Option<WaveStart>,
+    effects: Vec<Box<dyn Effect>>,
+    effect_time: Instant,
+    after_image: Option<AfterImage>,
+    heat_map: Option<HeatMap>,
+
+    // Stopped while paused, so every time the cloud keeps stays valid.
+    clock: PausableClock,
+    mt: SmallRng,
+    chances: Chances,
 
-    mt: StdRng,
-
-    rand_chance: Uniform<f32>,
     rand_line: Uniform<u16>,
     rand_cpidx: Uniform<u16>,
     rand_len: Uniform<u16>,
@@ -224,24 +478,37 @@ pub struct Cloud {
     rand_glitch_ms: Uniform<u16>,
     rand_linger_ms: Uniform<u16>,
     rand_speed: Uniform<f32>,
+    rand_jitter: Uniform<f32>,
 
     last_glitch_time: Instant,
     next_glitch_time: Instant,
     last_spawn_time: Instant,
-    pause_time: Option<Instant>,
+    spawn_credit: f32,
+    next_spawn_cost: f32,
+    sim_time: Instant,
+    // The time the simulation has been advanced to.
+    now: Instant,
 
     force_draw_everything: bool,
 
     shading_mode: ShadingMode,
 
-    message: Vec<MsgChr>,
-
     user_colors: Option<UserColors>,
     color_scheme: ColorScheme,
+    fade: Option<PaletteFade>,
+    palette_source: Option<Box<dyn PaletteSource>>,
+    palette_epoch: Instant,
+    palette_redraw: Instant,
+    head_color: Option<ColorOverride>,
+    tail_color: Option<ColorOverride>,
+    light: bool,
+    // Palette brightness, 1 for full; lowered by `--sleep-after`.
+    dim: f32,
     default_background: bool,
 }
 
 impl Cloud {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         color_mode: ColorMode,
         full_width: bool,
@@ -252,16 +519,26 @@ impl Cloud {
         color_scheme: ColorScheme,
         user_colors: Option<UserColors>,
     ) -> Self {
-        let now = Instant::now();
-        let mt = StdRng::seed_from_u64(0x1234567);
+        let clock = PausableClock::new(Box::new(RealClock));
+        let now = clock.now();
+        let mt = SmallRng::seed_from_u64(0x1234567);
 
         let cloud = Self {
             lines: 25,
             cols: 80,
-            palette: build_palette(color_scheme, color_mode, default_background, user_colors.as_ref()),
+            palette: scheme_palette(
+                color_scheme,
+                color_mode,
+                default_background,
+                user_colors.as_ref(),
+            ),
             color_mode,
             full_width,
             shading_distance: matches!(shading_mode, ShadingMode::DistanceFromHead),
+            shade_curve: ShadeCurve::Linear,
+            shade_len: 0,
+            head_len: 1,
+            tail_ghost: 1,
             bold_mode,
             async_mode,
             raining: true,
@@ -273,70 +550,253 @@ impl Cloud {
             glitch_pct: 0.1,
             glitch_low_ms: 300,
             glitch_high_ms: 400,
+            glitch_mode: GlitchMode::Global,
             short_pct: 0.5,
             die_early_pct: 0.3333333,
             linger_low_ms: 1,
             linger_high_ms: 3000,
             max_droplets_per_column: 3,
+            collision: Collision::Pass,
+            collision_order: Vec::new(),
+            speed_jitter: 0.0,
+            col_stride: 1,
+            col_offset: 0,
+            typing_mode: None,
+            threads: 1,
             droplets: Vec::new(),
+            steps: Vec::new(),
+            pool: Pool::new(0),
+            strips: Vec::new(),
+            strip_bounds: Vec::new(),
             num_droplets: 0,
+            max_droplets: DEFAULT_MAX_DROPLETS,
+            max_active: None,
+            spawn_rate: None,
+            scale_lines: None,
+            scale: 1.0,
+            free_droplets: Vec::new(),
             chars: Vec::new(),
+            codepage: None,
             char_pool: Vec::new(),
             glitch_pool: Vec::new(),
             glitch_pool_idx: 0,
-            glitch_map: Vec::new(),
+            stable_glyphs: false,
+            column_pool: Vec::new(),
+            glitch_map: GlitchMap::default(),
+            glitch_timers: Vec::new(),
+            glitch_epoch: now,
+            prev_glitch_ms: 0,
+            glitch_drift: 0,
+            next_drift: now,
             color_map: Vec::new(),
+            reshade: None,
+            next_reshade: now,
             col_stat: Vec::new(),
+            reserved_cols: Vec::new(),
+            density_profile: DensityProfile::Uniform,
+            typing_col: 0,
+            wave_start: None,
+            effects: Vec::new(),
+            effect_time: now,
+            after_image: None,
+            heat_map: None,
+            clock,
             mt,
-            rand_chance: Uniform::new(0.0, 1.0),
-            rand_line: Uniform::new_inclusive(0, 23),
-            rand_cpidx: Uniform::new_inclusive(0, 2047),
-            rand_len: Uniform::new_inclusive(1, 23),
-            rand_col: Uniform::new_inclusive(0, 79),
-            rand_glitch_ms: Uniform::new_inclusive(300, 400),
-            rand_linger_ms: Uniform::new_inclusive(1, 3000),
-            rand_speed: Uniform::new_inclusive(0.3333333, 1.0),
+            chances: Chances::new(),
+            rand_line: Uniform::new_inclusive(0, 23).unwrap(),
+            rand_cpidx: Uniform::new_inclusive(0, 2047).unwrap(),
+            rand_len: Uniform::new_inclusive(1, 23).unwrap(),
+            rand_col: Uniform::new_inclusive(0, 79).unwrap(),
+            rand_glitch_ms: Uniform::new_inclusive(300, 400).unwrap(),
+            rand_linger_ms: Uniform::new_inclusive(1, 3000).unwrap(),
+            rand_speed: Uniform::new_inclusive(ASYNC_MIN_SPEED, 1.0).unwrap(),
+            rand_jitter: Uniform::new_inclusive(1.0, 1.0).unwrap(),
             last_glitch_time: now,
             next_glitch_time: now + Duration::from_millis(300),
             last_spawn_time: now,
-            pause_time: None,
+            spawn_credit: 0.0,
+            next_spawn_cost: 1.0,
+            sim_time: now,
+            now,
             force_draw_everything: false,
             shading_mode,
-            message: Vec::new(),
             user_colors,
             color_scheme,
+            fade: None,
+            palette_source: None,
+            palette_epoch: now,
+            palette_redraw: now,
+            head_color: None,
+            tail_color: None,
+            light: false,
+            dim: 1.0,
             default_background,
         };
 
         cloud
     }
 
-    pub fn set_message(&mut self, msg: &str) {
-        self.message.clear();
-        for ch in msg.chars() {
-            self.message.push(MsgChr {
-                line: 0,
-                col: 0,
-                val: ch,
-                draw: false,
-            });
+    // Replaces the clock `rain`, `reset` and the other untimed calls read.
+    // Call `reset` afterwards, since the old clock's times no longer apply.
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = PausableClock::new(clock);
+        if self.pause {
+            self.clock.pause();
         }
-        self.reset_message();
+    }
+
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    pub fn droplets(&self) -> &[Droplet] {
+        &self.droplets
+    }
+
+    // `--decay`: glyphs left behind by the tails fade out over `decay`
+    // instead of vanishing at once.
+    pub fn set_decay(&mut self, decay: Option<Duration>) {
+        self.after_image = decay.filter(|d| !d.is_zero()).map(|d| {
+            let mut a = AfterImage::new(d);
+            a.resize(self.cols, self.lines);
+            a
+        });
+        self.force_draw_everything = true;
+    }
+
+    // The `H` overlay of where droplet heads have been lately.
+    pub fn toggle_heat_map(&mut self) {
+        self.heat_map = match self.heat_map.take() {
+            Some(_) => None,
+            None => {
+                let mut h = HeatMap::default();
+                h.resize(self.cols, self.lines);
+                Some(h)
+            }
+        };
+        self.force_draw_everything = true;
+    }
+
+    pub fn add_effect(&mut self, effect: Box<dyn Effect>) {
+        self.effects.push(effect);
     }
 
     pub fn set_color_scheme(&mut self, scheme: ColorScheme) {
         self.color_scheme = scheme;
-        self.palette = build_palette(scheme, self.color_mode, self.default_background, self.user_colors.as_ref());
+        let palette = self.make_palette(scheme);
+        if self.color_mode == ColorMode::TrueColor {
+            self.fade = Some(PaletteFade {
+                from: self.palette.clone(),
+                to: palette,
+                start: self.clock.now(),
+            });
+            self.update_fade(self.clock.now());
+        } else {
+            self.palette = palette;
+        }
+        self.fill_color_map();
+        self.force_draw_everything = true;
+    }
+
+    pub fn set_color_overrides(
+        &mut self,
+        head: Option<ColorOverride>,
+        tail: Option<ColorOverride>,
+    ) {
+        self.head_color = head;
+        self.tail_color = tail;
+        self.palette = self.make_palette(self.color_scheme);
+        self.fill_color_map();
+        self.force_draw_everything = true;
+    }
+
+    pub fn set_light(&mut self, light: bool) {
+        self.light = light;
+        self.palette = self.make_palette(self.color_scheme);
         self.fill_color_map();
         self.force_draw_everything = true;
     }
 
+    // Darkens the colors toward black, from 1 (full brightness) down to 0.
+    pub fn set_dim(&mut self, level: f32) {
+        let level = level.clamp(0.0, 1.0);
+        if level == self.dim {
+            return;
+        }
+        self.dim = level;
+        let palette = self.make_palette(self.color_scheme);
+        match self.fade.as_mut() {
+            Some(fade) => fade.to = palette,
+            None => self.palette = palette,
+        }
+        self.force_draw_everything = true;
+    }
+
+    fn make_palette(&self, scheme: ColorScheme) -> Palette {
+        let mut palette = scheme_palette(
+            scheme,
+            self.color_mode,
+            self.default_background,
+            self.user_colors.as_ref(),
+        );
+        if self.light {
+            apply_light(
+                &mut palette,
+                scheme,
+                self.color_mode,
+                self.default_background,
+            );
+        }
+        apply_overrides(
+            &mut palette,
+            self.color_mode,
+            self.head_color,
+            self.tail_color,
+        );
+        dim_palette(&mut palette, self.dim, self.color_mode);
+        palette
+    }
+
+    // Colors the droplets from `source` instead of straight from the scheme;
+    // the scheme palette is still handed to it each frame, and effects and
+    // scenes keep using the scheme palette.
+    pub fn set_palette_source(&mut self, source: Option<Box<dyn PaletteSource>>) {
+        self.palette_source = source;
+        self.palette_epoch = self.clock.now();
+        self.force_draw_everything = true;
+    }
+
+    pub fn color_scheme(&self) -> ColorScheme {
+        self.color_scheme
+    }
+
+    pub fn update_fade(&mut self, now: Instant) {
+        let Some(fade) = &self.fade else {
+            return;
+        };
+        let t =
+            now.saturating_duration_since(fade.start).as_secs_f32() / FADE_DURATION.as_secs_f32();
+        if t >= 1.0 {
+            if let Some(fade) = self.fade.take() {
+                self.palette = fade.to;
+            }
+        } else {
+            self.palette = blend_palettes(&fade.from, &fade.to, t);
+        }
+        self.force_draw_everything = true;
+    }
+
     pub fn set_async(&mut self, on: bool) {
         self.async_mode = on;
         self.set_column_speeds();
         self.update_droplet_speeds();
     }
 
+    pub fn set_seed(&mut self, seed: u64) {
+        self.mt = SmallRng::seed_from_u64(seed);
+        self.chances = Chances::new();
+    }
+
     pub fn set_chars_per_sec(&mut self, cps: f32) {
         self.chars_per_sec = cps;
         self.recalc_droplets_per_sec();
@@ -344,6 +804,18 @@ impl Cloud {
         self.update_droplet_speeds();
     }
 
+    // Share of its speed a droplet may randomly lose, clamped to 0..=0.9;
+    // NaN and infinities count as no jitter.
+    pub fn set_speed_jitter(&mut self, jitter: f32) {
+        let jitter = if jitter.is_finite() {
+            jitter.clamp(0.0, 0.9)
+        } else {
+            0.0
+        };
+        self.speed_jitter = jitter;
+        self.rand_jitter = Uniform::new_inclusive(1.0 - jitter, 1.0).unwrap();
+    }
+
     pub fn set_droplet_density(&mut self, density: f32) {
         self.droplet_density = density;
         self.recalc_droplets_per_sec();
@@ -357,48 +829,241 @@ impl Cloud {
     pub fn set_glitch_times(&mut self, low_ms: u16, high_ms: u16) {
         self.glitch_low_ms = low_ms;
         self.glitch_high_ms = high_ms;
-        self.rand_glitch_ms = Uniform::new_inclusive(low_ms, high_ms);
+        self.rand_glitch_ms = Uniform::new_inclusive(low_ms, high_ms).unwrap();
+        self.fill_glitch_timers();
+    }
+
+    // `--glitch-drift`: once per glitch cycle, `cells` glitched cells stop
+    // glitching and as many others start, so the glitches wander over the
+    // screen instead of sitting in the spots `reset` picked.
+    pub fn set_glitch_drift(&mut self, cells: u16) {
+        self.glitch_drift = cells;
+    }
+
+    pub fn set_glitch_mode(&mut self, mode: GlitchMode) {
+        self.glitch_mode = mode;
+        self.fill_glitch_timers();
+    }
+
+    // Stable glyph mode: every cell of a column keeps one glyph while any
+    // droplet runs through it, and glitches recolor cells without swapping
+    // their glyphs. A column gets fresh glyphs when a droplet starts in it
+    // with no other droplet there.
+    pub fn set_stable_glyphs(&mut self, on: bool) {
+        self.stable_glyphs = on;
+        self.fill_column_pool();
+        self.force_draw_everything = true;
+    }
+
+    fn fill_column_pool(&mut self) {
+        self.column_pool.clear();
+        if !self.stable_glyphs {
+            return;
+        }
+        self.column_pool
+            .resize(self.cols as usize * self.lines as usize, '0');
+        for col in 0..self.cols {
+            self.fill_column(col);
+        }
+    }
+
+    fn fill_column(&mut self, col: u16) {
+        let lines = self.lines as usize;
+        let start = col as usize * lines;
+        let dist = Uniform::new_inclusive(0usize, self.chars.len().saturating_sub(1)).unwrap();
+        let Some(cells) = self.column_pool.get_mut(start..start + lines) else {
+            return;
+        };
+        for c in cells {
+            *c = self
+                .chars
+                .get(dist.sample(&mut self.mt))
+                .copied()
+                .unwrap_or('0');
+        }
     }
 
     pub fn set_linger_times(&mut self, low_ms: u16, high_ms: u16) {
         self.linger_low_ms = low_ms;
         self.linger_high_ms = high_ms;
-        self.rand_linger_ms = Uniform::new_inclusive(low_ms, high_ms);
+        self.rand_linger_ms = Uniform::new_inclusive(low_ms, high_ms).unwrap();
+    }
+
+    // Keeps the rain out of `cols`, for hosts that draw their own content
+    // (a status bar, a TUI panel) there after each frame. Droplets already
+    // in those columns are removed; the next `draw` repaints everything.
+    pub fn reserve_columns(&mut self, cols: Range<u16>) {
+        if cols.is_empty() {
+            return;
+        }
+        if self.reserved_cols.len() < cols.end as usize {
+            self.reserved_cols.resize(cols.end as usize, false);
+        }
+        for col in cols.clone() {
+            self.reserved_cols[col as usize] = true;
+        }
+        for i in 0..self.droplets.len() {
+            let d = &self.droplets[i];
+            if d.is_alive && !self.can_rain_in(d.bound_col) {
+                let col = d.bound_col;
+                self.droplets[i].is_alive = false;
+                self.free_droplets.push(i);
+                if let Some(cs) = self.col_stat.get_mut(col as usize) {
+                    cs.num_droplets = cs.num_droplets.saturating_sub(1);
+                    cs.can_spawn = true;
+                }
+            }
+        }
+        if let Some(a) = self.after_image.as_mut() {
+            for col in cols {
+                a.clear_span(col, 0, self.lines);
+            }
+        }
+        self.force_draw_everything = true;
+    }
+
+    // Lets the rain back into `cols`. The next `draw` repaints everything,
+    // clearing whatever the host left there.
+    pub fn release_columns(&mut self, cols: Range<u16>) {
+        for col in cols {
+            if let Some(r) = self.reserved_cols.get_mut(col as usize) {
+                *r = false;
+            }
+        }
+        self.force_draw_everything = true;
+    }
+
+    pub fn is_reserved(&self, col: u16) -> bool {
+        self.reserved_cols
+            .get(col as usize)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    // Whether a droplet bound to `col` stays clear of reserved columns,
+    // including the padding column of full-width glyphs.
+    fn can_rain_in(&self, col: u16) -> bool {
+        let padding = self.full_width && self.is_reserved(col.saturating_add(1));
+        !self.is_reserved(col) && !padding
     }
 
     pub fn set_max_droplets_per_column(&mut self, v: u8) {
         self.max_droplets_per_column = v;
     }
 
+    // `--collision`: what a droplet does on catching up with the one ahead
+    // of it in its column. Only matters with more than one droplet a column.
+    pub fn set_collision(&mut self, collision: Collision) {
+        self.collision = collision;
+    }
+
+    pub fn set_max_droplets(&mut self, v: usize) {
+        self.max_droplets = v.max(1);
+    }
+
+    // Scales speed and the shortest droplet length by the screen height over
+    // `lines`; with the spawn rate following the speed, the share of the
+    // screen covered stays the same on every size. Applied on the next reset.
+    pub fn set_auto_scale(&mut self, lines: Option<u16>) {
+        self.scale_lines = lines.filter(|&l| l > 0);
+    }
+
+    // Speed after `--auto-scale`.
+    fn scaled_cps(&self) -> f32 {
+        self.chars_per_sec * self.scale
+    }
+
+    // Caps the droplets alive at once; the rain stops spawning at the cap.
+    pub fn set_max_active(&mut self, n: Option<usize>) {
+        self.max_active = n;
+    }
+
+    // A fixed number of droplets spawned per second, instead of the rate
+    // derived from density, speed and screen size.
+    pub fn set_spawn_rate(&mut self, rate: Option<f32>) {
+        self.spawn_rate = rate;
+        self.recalc_droplets_per_sec();
+    }
+
+    // More threads than cores only adds switching, so the count is capped.
+    // The calling thread takes a strip too, leaving `threads - 1` workers.
+    pub fn set_threads(&mut self, n: usize) {
+        let cores = std::thread::available_parallelism().map_or(1, |c| c.get());
+        self.threads = n.clamp(1, cores);
+        if self.pool.workers() != self.threads - 1 {
+            self.pool = Pool::new(self.threads - 1);
+        }
+        self.force_draw_everything = true;
+    }
+
+    // Splits the columns into one strip per draw thread. Full-width strips
+    // start on even columns so a glyph and its padding stay together.
+    fn sync_strips(&mut self) {
+        self.strip_bounds.clear();
+        self.strips.clear();
+        let n = self.threads.min(self.cols as usize / 2);
+        if n < 2 {
+            return;
+        }
+        for i in 0..=n {
+            let mut start = (self.cols as usize * i / n) as u16;
+            if self.full_width && i < n {
+                start &= !1;
+            }
+            self.strip_bounds.push(start);
+        }
+        for w in self.strip_bounds.windows(2) {
+            self.strips
+                .push(Frame::new(w[1] - w[0], self.lines, self.palette.bg));
+        }
+    }
+
+    // Index of the strip in `bounds` that holds `col`.
+    fn strip_of(bounds: &[u16], col: u16) -> usize {
+        bounds
+            .partition_point(|&b| b <= col)
+            .saturating_sub(1)
+            .min(bounds.len() - 2)
+    }
+
+    // Stops the cloud's clock rather than the simulation, so droplets,
+    // spawning, glitches, effects and fades all resume where they were.
     pub fn toggle_pause(&mut self) {
         self.pause = !self.pause;
         if self.pause {
-            self.pause_time = Some(Instant::now());
-        } else if let Some(pt) = self.pause_time.take() {
-            let elapsed = Instant::now().saturating_duration_since(pt);
-            self.last_spawn_time += elapsed;
-            for d in &mut self.droplets {
-                if d.is_alive {
-                    d.increment_time(elapsed);
-                }
-            }
+            self.clock.pause();
+        } else {
+            self.clock.resume();
         }
     }
 
     pub fn reset(&mut self, cols: u16, lines: u16) {
+        self.reset_at(cols, lines, self.clock.now());
+    }
+
+    // `reset` with the simulation clock starting at `now`, for callers that
+    // drive the cloud with their own timeline.
+    pub fn reset_at(&mut self, cols: u16, lines: u16, now: Instant) {
         self.cols = cols;
         self.lines = lines;
 
-        self.num_droplets = (1.5 * self.cols as f32).round() as usize;
+        self.num_droplets = ((1.5 * self.cols as f32).round() as usize).min(self.max_droplets);
         self.droplets.clear();
         self.droplets.resize_with(self.num_droplets, Droplet::new);
+        self.free_droplets.clear();
+        self.free_droplets.extend((0..self.num_droplets).rev());
 
+        self.scale = match self.scale_lines {
+            Some(l) => (lines as f32 / l as f32).clamp(MIN_SCALE, MAX_SCALE),
+            None => 1.0,
+        };
         let max_line = lines.saturating_sub(2);
         let max_len = max_line.max(1);
-        self.rand_line = Uniform::new_inclusive(0, max_line);
-        self.rand_len = Uniform::new_inclusive(1, max_len);
-        self.rand_col = Uniform::new_inclusive(0, cols.saturating_sub(1));
-        self.rand_cpidx = Uniform::new_inclusive(0, 2047);
+        let min_len = (self.scale.round() as u16).clamp(1, max_len);
+        self.rand_line = Uniform::new_inclusive(0, max_line).unwrap();
+        self.rand_len = Uniform::new_inclusive(min_len, max_len).unwrap();
+        self.update_rand_col();
+        self.rand_cpidx = Uniform::new_inclusive(0, 2047).unwrap();
 
         self.recalc_droplets_per_sec();
 
@@ -407,29 +1072,89 @@ impl Cloud {
             cols as usize,
             ColumnStatus {
                 max_speed_pct: 1.0,
+                spawn_weight: 1.0,
+                load: 1.0,
                 num_droplets: 0,
                 can_spawn: true,
+                start_time: None,
             },
         );
+        if let Some(wave) = self.wave_start.take() {
+            let n = self.col_stat.len().max(1) as f32;
+            for (col, cs) in self.col_stat.iter_mut().enumerate() {
+                let x = (col as f32 + 0.5) / n;
+                let delay = match wave {
+                    WaveStart::Left => x,
+                    WaveStart::Center => (2.0 * x - 1.0).abs(),
+                };
+                cs.start_time = Some(now + WAVE_DURATION.mul_f32(delay));
+            }
+        }
 
         self.fill_glitch_map();
         self.fill_color_map();
+        self.fill_column_pool();
+        if let Some(a) = self.after_image.as_mut() {
+            a.resize(cols, lines);
+        }
+        if let Some(h) = self.heat_map.as_mut() {
+            h.resize(cols, lines);
+        }
+        self.set_column_weights();
         self.set_column_speeds();
         self.update_droplet_speeds();
 
-        if !self.message.is_empty() {
-            self.reset_message();
-        }
-
-        let now = Instant::now();
         self.last_glitch_time = now;
-        self.next_glitch_time = now + Duration::from_millis(self.rand_glitch_ms.sample(&mut self.mt) as u64);
+        self.next_glitch_time =
+            now + Duration::from_millis(self.rand_glitch_ms.sample(&mut self.mt) as u64);
         self.last_spawn_time = now;
+        self.spawn_credit = 0.0;
+        self.sim_time = now;
+        self.now = now;
+        self.effect_time = now;
+        self.glitch_epoch = now;
+        self.prev_glitch_ms = 0;
+        self.next_drift = self.next_glitch_time;
+        self.next_reshade = now + self.reshade.unwrap_or_default();
+        self.force_draw_everything = true;
+        log::debug!(
+            "reset to {}x{} with {} droplet slots",
+            cols,
+            lines,
+            self.num_droplets
+        );
+    }
+
+    // Runs the simulation over the `span` leading up to now, so the first
+    // frame opens on a screen already full of rain.
+    pub fn prefill(&mut self, span: Duration) {
+        let now = self.clock.now();
+        let Some(start) = now.checked_sub(span) else {
+            return;
+        };
+        self.sim_time = start;
+        self.last_spawn_time = start;
+        while now.saturating_duration_since(self.sim_time) >= SIM_STEP {
+            self.sim_time += SIM_STEP;
+            self.sim_step(self.sim_time);
+            self.glitch_step(self.sim_time);
+            self.reshade_step(self.sim_time);
+        }
+        self.now = now;
         self.force_draw_everything = true;
+        log::debug!("prefilled {:?} of rain", span);
+    }
+
+    // Applies to the glyphs passed to `init_chars` from then on.
+    pub fn set_codepage(&mut self, codepage: Option<Codepage>) {
+        self.codepage = codepage;
     }
 
     pub fn init_chars(&mut self, chars: Vec<char>) {
-        self.chars = chars;
+        self.chars = match self.codepage {
+            Some(cp) => cp.fit(chars),
+            None => chars,
+        };
         if self.chars.is_empty() {
             self.chars.push('0');
             self.chars.push('1');
@@ -439,7 +1164,7 @@ impl Cloud {
         self.glitch_pool.resize(1024, '0');
         self.glitch_pool_idx = 0;
 
-        let dist = Uniform::new_inclusive(0usize, self.chars.len().saturating_sub(1));
+        let dist = Uniform::new_inclusive(0usize, self.chars.len().saturating_sub(1)).unwrap();
         for i in 0..self.char_pool.len() {
             let idx = dist.sample(&mut self.mt);
             self.char_pool[i] = self.chars[idx];
@@ -448,10 +1173,15 @@ impl Cloud {
             let idx = dist.sample(&mut self.mt);
             self.glitch_pool[i] = self.chars[idx];
         }
+        self.fill_column_pool();
     }
 
     fn recalc_droplets_per_sec(&mut self) {
-        let droplet_seconds = (self.lines as f32) / self.chars_per_sec.max(0.001);
+        if let Some(rate) = self.spawn_rate {
+            self.droplets_per_sec = rate;
+            return;
+        }
+        let droplet_seconds = (self.lines as f32) / self.scaled_cps().max(0.001);
         self.droplets_per_sec = (self.cols as f32) * self.droplet_density / droplet_seconds;
     }
 
@@ -461,22 +1191,96 @@ impl Cloud {
             return;
         }
         let size = self.lines as usize * self.cols as usize;
-        self.glitch_map.resize(size, false);
-        for v in &mut self.glitch_map {
-            *v = self.rand_chance.sample(&mut self.mt) <= self.glitch_pct;
+        self.glitch_map.fill(size, self.glitch_pct, &mut self.mt);
+        self.fill_glitch_timers();
+    }
+
+    fn fill_glitch_timers(&mut self) {
+        self.glitch_timers.clear();
+        if self.glitch_mode != GlitchMode::Cell || !self.glitchy {
+            return;
+        }
+        let size = self.lines as usize * self.cols as usize;
+        self.glitch_timers.reserve(size);
+        for _ in 0..size {
+            let period_ms = self.rand_glitch_ms.sample(&mut self.mt);
+            let offset_ms = (self.mt.next_u32() % period_ms.max(1) as u32) as u16;
+            self.glitch_timers.push(GlitchTimer {
+                offset_ms,
+                period_ms,
+            });
         }
     }
 
+    fn chance(&mut self) -> f32 {
+        self.chances.next(&mut self.mt)
+    }
+
     fn fill_color_map(&mut self) {
         let size = self.lines as usize * self.cols as usize;
         self.color_map.resize(size, 0);
 
         let n = self.palette.colors.len().max(1);
-        let (low, high) = if n < 3 { (0, 0) } else if n == 3 { (1, 1) } else { (1, (n - 2) as u8) };
-        let dist = Uniform::new_inclusive(low, high);
+        let (low, high) = if n < 3 {
+            (0, 0)
+        } else if n == 3 {
+            (1, 1)
+        } else {
+            (1, (n - 2) as u8)
+        };
+        let span = (high - low) as u16 + 1;
+
+        for chunk in self.color_map.chunks_mut(8) {
+            let mut bits = self.mt.next_u64();
+            for v in chunk {
+                *v = low + (((bits & 0xff) as u16 * span) >> 8) as u8;
+                bits >>= 8;
+            }
+        }
+    }
+
+    // `--reshade`: every `period` each cell's shade drifts one palette step
+    // up or down (or stays), so the random shading doesn't sit still for the
+    // whole session. `None` keeps the map as `reset` filled it.
+    pub fn set_reshade(&mut self, period: Option<Duration>) {
+        self.reshade = period.filter(|p| !p.is_zero());
+        self.next_reshade = self.now + self.reshade.unwrap_or_default();
+    }
 
-        for v in &mut self.color_map {
-            *v = dist.sample(&mut self.mt);
+    fn reshade_step(&mut self, now: Instant) {
+        let Some(period) = self.reshade else {
+            return;
+        };
+        if now < self.next_reshade {
+            return;
+        }
+        self.next_reshade = now + period;
+        self.walk_color_map();
+        self.force_draw_everything = true;
+    }
+
+    // Moves each cell of the color map one step down (a quarter of them),
+    // up (another quarter) or nowhere, bouncing off the ends of the range
+    // `fill_color_map` picks from.
+    fn walk_color_map(&mut self) {
+        let n = self.palette.colors.len().max(1);
+        if n < 4 {
+            return;
+        }
+        let (low, high) = (1u8, (n - 2) as u8);
+        for chunk in self.color_map.chunks_mut(32) {
+            let mut bits = self.mt.next_u64();
+            for v in chunk {
+                *v = match bits & 3 {
+                    0 if *v > low => *v - 1,
+                    0 => *v + 1,
+                    1 if *v < high => *v + 1,
+                    1 => *v - 1,
+                    _ => *v,
+                }
+                .clamp(low, high);
+                bits >>= 2;
+            }
         }
     }
 
@@ -486,6 +1290,65 @@ impl Cloud {
         }
     }
 
+    pub fn set_col_stride(&mut self, stride: u16, offset: u16) {
+        self.col_stride = stride.max(1);
+        self.col_offset = offset % self.col_stride;
+        self.update_rand_col();
+    }
+
+    fn update_rand_col(&mut self) {
+        let slot_cols = if self.full_width {
+            self.cols.div_ceil(2)
+        } else {
+            self.cols
+        };
+        let slots = slot_cols
+            .saturating_sub(self.col_offset)
+            .div_ceil(self.col_stride);
+        self.rand_col = Uniform::new_inclusive(0, slots.saturating_sub(1)).unwrap();
+    }
+
+    // Opens the columns one after another over the first seconds after the
+    // next reset, instead of all at once.
+    pub fn set_wave_start(&mut self, wave: Option<WaveStart>) {
+        self.wave_start = wave;
+    }
+
+    pub fn set_density_profile(&mut self, profile: DensityProfile) {
+        self.density_profile = profile;
+        self.set_column_weights();
+    }
+
+    fn set_column_weights(&mut self) {
+        let cols = self.col_stat.len().max(1);
+        for (col, cs) in self.col_stat.iter_mut().enumerate() {
+            let x = (col as f32 + 0.5) / cols as f32;
+            let w = match &self.density_profile {
+                DensityProfile::Uniform => 1.0,
+                DensityProfile::Center => 1.0 - (2.0 * x - 1.0).abs(),
+                DensityProfile::Edges => (2.0 * x - 1.0).abs(),
+                DensityProfile::GradientLeft => 1.0 - x,
+                DensityProfile::Mask(mask) => {
+                    let i = ((x * mask.len() as f32) as usize).min(mask.len().saturating_sub(1));
+                    mask.get(i).copied().unwrap_or(1.0)
+                }
+            };
+            cs.spawn_weight = w.clamp(0.0, 1.0);
+        }
+    }
+
+    pub fn set_column_loads(&mut self, loads: &[f32]) {
+        if loads.is_empty() {
+            return;
+        }
+        let cols = self.col_stat.len().max(1);
+        for (col, cs) in self.col_stat.iter_mut().enumerate() {
+            let band = col * loads.len() / cols;
+            cs.load = loads[band].clamp(0.0, 1.0);
+        }
+        self.update_droplet_speeds();
+    }
+
     fn set_column_speeds(&mut self) {
         for cs in &mut self.col_stat {
             cs.max_speed_pct = if self.async_mode {
@@ -497,65 +1360,31 @@ impl Cloud {
     }
 
     fn update_droplet_speeds(&mut self) {
+        let cps = self.scaled_cps();
         for d in &mut self.droplets {
             if !d.is_alive {
                 continue;
             }
             if let Some(cs) = self.col_stat.get(d.bound_col as usize) {
-                d.chars_per_sec = cs.max_speed_pct * self.chars_per_sec;
+                d.chars_per_sec = cs.speed_pct() * d.speed_pct * cps;
             }
         }
     }
 
     fn time_for_glitch(&self, now: Instant) -> bool {
-        self.glitchy && now >= self.next_glitch_time
+        self.glitchy && self.glitch_mode == GlitchMode::Global && now >= self.next_glitch_time
     }
 
-    fn is_bright(&self, now: Instant) -> bool {
-        if now < self.last_glitch_time {
-            return false;
-        }
-        let since = now.saturating_duration_since(self.last_glitch_time).as_nanos() as f64;
-        let between = self
-            .next_glitch_time
-            .saturating_duration_since(self.last_glitch_time)
-            .as_nanos() as f64;
-        if between <= 0.0 {
-            return false;
-        }
-        (since / between) <= 0.25
-    }
-
-    fn is_dim(&self, now: Instant) -> bool {
-        if now > self.next_glitch_time {
-            return true;
-        }
-        let since = now.saturating_duration_since(self.last_glitch_time).as_nanos() as f64;
-        let between = self
-            .next_glitch_time
-            .saturating_duration_since(self.last_glitch_time)
-            .as_nanos() as f64;
-        if between <= 0.0 {
-            return true;
-        }
-        (since / between) >= 0.75
-    }
-
-    pub fn is_glitched(&self, line: u16, col: u16) -> bool {
-        if !self.glitchy {
+    pub fn is_glitched(&self, line: u16, col: u16) -> bool {
+        if !self.glitchy {
             return false;
         }
         let idx = col as usize * self.lines as usize + line as usize;
-        self.glitch_map.get(idx).copied().unwrap_or(false)
-    }
-
-    pub fn get_char(&self, line: u16, char_pool_idx: u16) -> char {
-        let idx = ((char_pool_idx as usize) + (line as usize)) % self.char_pool.len().max(1);
-        self.char_pool.get(idx).copied().unwrap_or('0')
+        self.glitch_map.get(idx)
     }
 
     fn do_glitch_span(&mut self, start_line: u16, hp: u16, col: u16, cp_idx: u16) {
-        if !self.glitchy {
+        if !self.glitchy || self.stable_glyphs {
             return;
         }
 
@@ -572,15 +1401,36 @@ impl Cloud {
         }
     }
 
+    // `GlitchMode::Cell`: swaps the glyph of every glitched cell in the span
+    // whose own timer started a new cycle since the last frame.
+    fn do_cell_glitch_span(&mut self, start_line: u16, hp: u16, col: u16, cp_idx: u16, ms: u64) {
+        if self.stable_glyphs {
+            return;
+        }
+        for line in start_line..=hp.min(self.lines.saturating_sub(1)) {
+            let idx = col as usize * self.lines as usize + line as usize;
+            let Some(t) = self.glitch_timers.get(idx) else {
+                break;
+            };
+            if !self.glitch_map.get(idx) || t.cycle(ms) == t.cycle(self.prev_glitch_ms) {
+                continue;
+            }
+            let char_idx = ((cp_idx as usize) + (line as usize)) % self.char_pool.len();
+            self.char_pool[char_idx] =
+                self.glitch_pool[self.glitch_pool_idx % self.glitch_pool.len()];
+            self.glitch_pool_idx = (self.glitch_pool_idx + 1) % self.glitch_pool.len();
+        }
+    }
+
     fn fill_droplet(&mut self, d: &mut Droplet, col: u16) {
         let mut end_line = self.lines.saturating_sub(1);
-        if self.rand_chance.sample(&mut self.mt) <= self.die_early_pct {
+        if self.chance() <= self.die_early_pct {
             end_line = self.rand_line.sample(&mut self.mt);
         }
         let cp_idx = self.rand_cpidx.sample(&mut self.mt);
 
         let mut len = self.lines;
-        if self.rand_chance.sample(&mut self.mt) <= self.short_pct {
+        if self.chance() <= self.short_pct {
             len = self.rand_len.sample(&mut self.mt);
         }
 
@@ -593,38 +1443,287 @@ impl Cloud {
         let speed = self
             .col_stat
             .get(col as usize)
-            .map(|cs| cs.max_speed_pct)
+            .map(|cs| cs.speed_pct())
             .unwrap_or(1.0)
-            * self.chars_per_sec;
+            * self.scaled_cps();
+        let speed_pct = self.rand_jitter.sample(&mut self.mt);
 
         d.bound_col = col;
         d.end_line = end_line;
         d.char_pool_idx = cp_idx;
         d.length = len;
-        d.chars_per_sec = speed;
+        d.speed_pct = speed_pct;
+        d.chars_per_sec = speed * speed_pct;
         d.time_to_linger = ttl;
         d.head_put_line = 0;
         d.head_cur_line = 0;
         d.tail_put_line = None;
         d.tail_cur_line = 0;
         d.head_stop_time = None;
+        d.head_char = None;
+    }
+
+    fn random_col(&mut self) -> u16 {
+        let slot = self.rand_col.sample(&mut self.mt);
+        let col = slot
+            .saturating_mul(self.col_stride)
+            .saturating_add(self.col_offset);
+        if self.full_width {
+            col.saturating_mul(2)
+        } else {
+            col
+        }
+    }
+
+    fn spawn_in_col(&mut self, col: u16, now: Instant) -> Option<usize> {
+        if col as usize >= self.col_stat.len() || !self.can_rain_in(col) {
+            return None;
+        }
+        let di = self.alloc_droplet()?;
+        if self.stable_glyphs && self.col_stat[col as usize].num_droplets == 0 {
+            self.fill_column(col);
+        }
+
+        let mut d = std::mem::take(&mut self.droplets[di]);
+        self.fill_droplet(&mut d, col);
+        d.activate(now);
+        self.droplets[di] = d;
+
+        self.col_stat[col as usize].can_spawn = false;
+        self.col_stat[col as usize].num_droplets += 1;
+        Some(di)
+    }
+
+    pub fn type_char(&mut self, ch: char) {
+        let Some(mode) = self.typing_mode else {
+            return;
+        };
+        if self.cols == 0 {
+            return;
+        }
+
+        let step = if self.full_width { 2 } else { 1 };
+        let col = match mode {
+            TypingMode::Random => self.random_col(),
+            TypingMode::Cursor => {
+                let col = self.typing_col;
+                self.typing_col = col.saturating_add(step);
+                if self.typing_col >= self.cols {
+                    self.typing_col = 0;
+                }
+                col
+            }
+        };
+
+        if let Some(di) = self.spawn_in_col(col, self.sim_time) {
+            self.droplets[di].head_char = Some(ch);
+        }
     }
 
+    pub fn inject_text(&mut self, text: &str) {
+        let now = self.sim_time;
+        let text: Vec<char> = text
+            .chars()
+            .filter(|&c| c == ' ' || is_visible(c))
+            .collect();
+        if text.iter().all(|c| c.is_whitespace()) {
+            for _ in 0..(self.cols / 4).max(1) {
+                let col = self.random_col();
+                self.spawn_in_col(col, now);
+            }
+            return;
+        }
+
+        let col = self.random_col();
+        let Some(di) = self.spawn_in_col(col, now) else {
+            return;
+        };
+
+        let pool_len = self.char_pool.len();
+        let cp_idx = self.droplets[di].char_pool_idx as usize;
+        let shown = text.len().min(self.lines as usize);
+        for (line, &ch) in text.iter().take(shown).enumerate() {
+            self.char_pool[(cp_idx + line) % pool_len] = ch;
+            if let Some(c) = self
+                .column_pool
+                .get_mut(col as usize * self.lines as usize + line)
+            {
+                *c = ch;
+            }
+        }
+
+        let d = &mut self.droplets[di];
+        d.end_line = (shown as u16).saturating_sub(1);
+        d.length = self.lines;
+    }
+
+    fn sim_step(&mut self, now: Instant) {
+        self.spawn_droplets(now);
+        let lines = self.lines;
+        self.steps.clear();
+        self.steps.resize(self.droplets.len(), None);
+        if self.strips.is_empty() {
+            for (d, step) in self.droplets.iter_mut().zip(&mut self.steps) {
+                if d.is_alive {
+                    *step = Some((d.head_put_line, d.advance(now, lines)));
+                }
+            }
+        } else {
+            // Droplets only move within their own column, so each strip's
+            // droplets are stepped on its own thread.
+            let mut buckets: Vec<Vec<_>> = self.strips.iter().map(|_| Vec::new()).collect();
+            for (d, step) in self.droplets.iter_mut().zip(&mut self.steps) {
+                if d.is_alive {
+                    buckets[Self::strip_of(&self.strip_bounds, d.bound_col)].push((d, step));
+                }
+            }
+            self.pool.run(buckets.iter_mut().map(|bucket| {
+                move || {
+                    for (d, step) in bucket.iter_mut() {
+                        **step = Some((d.head_put_line, d.advance(now, lines)));
+                    }
+                }
+            }));
+        }
+        for i in 0..self.droplets.len() {
+            let Some((prev_head, free_col)) = self.steps[i] else {
+                continue;
+            };
+            let d = &self.droplets[i];
+            let col = d.bound_col;
+            if let Some(h) = self.heat_map.as_mut() {
+                h.touch(col, prev_head, d.head_put_line, now);
+            }
+            if !d.is_alive {
+                let early = d.tail_put_line.unwrap_or(0) <= self.lines / 4;
+                if let Some(cs) = self.col_stat.get_mut(col as usize) {
+                    cs.num_droplets = cs.num_droplets.saturating_sub(1);
+                    if early {
+                        cs.can_spawn = true;
+                    }
+                }
+                self.free_droplets.push(i);
+            }
+            if free_col {
+                self.set_column_spawn(col, true);
+            }
+        }
+        if self.collision != Collision::Pass && self.max_droplets_per_column > 1 {
+            self.resolve_collisions();
+        }
+    }
+
+    // Finds droplets whose head has run into the visible part of the droplet
+    // ahead of them in the same column, and merges or queues them. A droplet
+    // ahead whose tail hasn't started yet still covers the top of the column,
+    // so there is nowhere to hold the one behind and it is left alone.
+    fn resolve_collisions(&mut self) {
+        let mut order = std::mem::take(&mut self.collision_order);
+        order.clear();
+        order.extend(
+            self.droplets
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| d.is_alive)
+                .map(|(i, d)| (d.bound_col, d.head_put_line, d.tail_put_line, i)),
+        );
+        // Front to back within each column; of two heads on the same line the
+        // one whose tail is further down was there first.
+        order.sort_unstable_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(b.2.cmp(&a.2)));
+
+        for pair in order.windows(2) {
+            let ((col, _, _, front), (rear_col, _, _, rear)) = (pair[0], pair[1]);
+            if col != rear_col || !self.droplets[front].is_alive {
+                continue;
+            }
+            let Some(tp) = self.droplets[front].tail_put_line else {
+                continue;
+            };
+            if self.droplets[rear].head_put_line <= tp {
+                continue;
+            }
+            match self.collision {
+                Collision::Merge => self.merge_droplets(front, rear),
+                Collision::Queue => self.droplets[rear].hold_behind(tp),
+                Collision::Pass => {}
+            }
+        }
+        self.collision_order = order;
+    }
+
+    // The rear droplet takes the front one's head, glyphs and timing and
+    // keeps its own tail; the front one's slot is freed.
+    fn merge_droplets(&mut self, front: usize, rear: usize) {
+        let f = std::mem::take(&mut self.droplets[front]);
+        let r = &mut self.droplets[rear];
+        let top = r.tail_put_line.map_or(0, |t| t + 1);
+        r.length = r.length.max(f.head_put_line.saturating_sub(top) + 1);
+        r.head_put_line = f.head_put_line;
+        r.end_line = f.end_line;
+        r.char_pool_idx = f.char_pool_idx;
+        r.chars_per_sec = f.chars_per_sec;
+        r.speed_pct = f.speed_pct;
+        r.advance_remainder = f.advance_remainder;
+        r.is_head_crawling = f.is_head_crawling;
+        r.is_tail_crawling = f.is_tail_crawling;
+        r.head_stop_time = f.head_stop_time;
+        r.time_to_linger = f.time_to_linger;
+        r.head_char = f.head_char;
+
+        if let Some(cs) = self.col_stat.get_mut(f.bound_col as usize) {
+            cs.num_droplets = cs.num_droplets.saturating_sub(1);
+        }
+        self.free_droplets.push(front);
+        log::trace!("merged droplets in column {}", f.bound_col);
+    }
+
+    // Reuses a dead slot, growing the pool up to `max_droplets` when none is free.
+    fn alloc_droplet(&mut self) -> Option<usize> {
+        if let Some(di) = self.free_droplets.pop() {
+            return Some(di);
+        }
+        if self.droplets.len() >= self.max_droplets {
+            return None;
+        }
+        self.droplets.push(Droplet::new());
+        Some(self.droplets.len() - 1)
+    }
+
+    // Spawns arrive as a Poisson process: credit builds up at
+    // `droplets_per_sec` and each spawn costs an exponentially distributed
+    // amount of it, so even low rates spawn steadily instead of in bursts.
     fn spawn_droplets(&mut self, now: Instant) {
         let elapsed = now.saturating_duration_since(self.last_spawn_time);
-        let elapsed_sec = elapsed.as_secs_f32();
-        let to_spawn = ((elapsed_sec * self.droplets_per_sec) as usize).min(self.num_droplets);
+        self.last_spawn_time = now;
+        self.spawn_credit += elapsed.as_secs_f32() * self.droplets_per_sec;
+
+        let mut to_spawn = 0usize;
+        while self.spawn_credit >= self.next_spawn_cost && to_spawn < self.max_droplets {
+            self.spawn_credit -= self.next_spawn_cost;
+            self.next_spawn_cost = -(1.0 - self.chance()).ln();
+            to_spawn += 1;
+        }
+        if to_spawn == self.max_droplets {
+            self.spawn_credit = 0.0;
+        }
         if to_spawn == 0 {
             return;
         }
 
-        let mut idx = 0usize;
         let mut spawned = 0usize;
 
         for _ in 0..to_spawn {
-            let mut col = self.rand_col.sample(&mut self.mt);
+            let active = self.droplets.len() - self.free_droplets.len();
+            if self.max_active.is_some_and(|max| active >= max) {
+                break;
+            }
+
+            let slot = self.rand_col.sample(&mut self.mt);
+            let mut col = slot
+                .saturating_mul(self.col_stride)
+                .saturating_add(self.col_offset);
             if self.full_width {
-                col &= 0xFFFE;
+                col = col.saturating_mul(2);
             }
 
             if col as usize >= self.col_stat.len() {
@@ -632,24 +1731,27 @@ impl Cloud {
             }
 
             if !self.col_stat[col as usize].can_spawn
+                || !self.can_rain_in(col)
+                || self.col_stat[col as usize]
+                    .start_time
+                    .is_some_and(|t| now < t)
                 || self.col_stat[col as usize].num_droplets >= self.max_droplets_per_column
             {
                 continue;
             }
 
-            let mut found = None;
-            while idx < self.droplets.len() {
-                if !self.droplets[idx].is_alive {
-                    found = Some(idx);
-                    break;
-                }
-                idx += 1;
+            let weight = self.col_stat[col as usize].spawn_weight
+                * (0.1 + 0.9 * self.col_stat[col as usize].load);
+            if weight < 1.0 && self.chance() >= weight {
+                continue;
             }
-            let Some(di) = found else {
+
+            let Some(di) = self.alloc_droplet() else {
+                log::debug!("droplet pool full at {}", self.max_droplets);
                 break;
             };
 
-            let mut d = std::mem::replace(&mut self.droplets[di], Droplet::new());
+            let mut d = std::mem::take(&mut self.droplets[di]);
             self.fill_droplet(&mut d, col);
             d.activate(now);
             self.droplets[di] = d;
@@ -661,7 +1763,13 @@ impl Cloud {
         }
 
         if spawned > 0 {
-            self.last_spawn_time = now;
+            log::trace!(
+                "spawned {} of {} droplets, {} of {} slots free",
+                spawned,
+                to_spawn,
+                self.free_droplets.len(),
+                self.droplets.len()
+            );
         }
     }
 
@@ -669,194 +1777,166 @@ impl Cloud {
         self.force_draw_everything = true;
     }
 
+    pub fn shading_mode(&self) -> ShadingMode {
+        self.shading_mode
+    }
+
     pub fn set_shading_mode(&mut self, sm: ShadingMode) {
         self.shading_mode = sm;
         self.shading_distance = matches!(sm, ShadingMode::DistanceFromHead);
         self.force_draw_everything = true;
     }
 
-    pub fn get_attr(
-        &self,
-        line: u16,
-        col: u16,
-        val: char,
-        loc: CharLoc,
-        now: Instant,
-        head_put_line: u16,
-        length: u16,
-    ) -> (Option<Color>, bool) {
-        let mut bold = false;
-        if self.bold_mode == BoldMode::Random {
-            bold = (((line as u32) ^ (val as u32)) % 2) == 1;
-        }
-
-        let idx = col as usize * self.lines as usize + line as usize;
-        let mut color_idx = self.color_map.get(idx).copied().unwrap_or(0) as i32;
-
-        if self.shading_distance {
-            let n = self.palette.colors.len().max(1) as f32;
-            let dist = (head_put_line.saturating_sub(line)) as f32;
-            let len = length.max(1) as f32;
-            let v = (n - 1.0) - (dist / len * (n - 1.0));
-            color_idx = v.round() as i32;
-        }
-
-        if self.glitchy && self.glitch_map.get(idx).copied().unwrap_or(false) {
-            if self.is_bright(now) {
-                color_idx += 1;
-                bold = true;
-            } else if self.is_dim(now) {
-                color_idx -= 1;
-                bold = false;
-            }
-        }
+    pub fn set_shade_curve(&mut self, curve: ShadeCurve, len: u16) {
+        self.shade_curve = curve;
+        self.shade_len = len;
+        self.force_draw_everything = true;
+    }
 
-        let last = self.palette.colors.len().saturating_sub(1) as i32;
-        match loc {
-            CharLoc::Tail => {
-                color_idx = 0;
-                bold = false;
-            }
-            CharLoc::Head => {
-                color_idx = last;
-                bold = true;
-            }
-            CharLoc::Middle => {
-                color_idx = color_idx.clamp(0, last.max(0));
-            }
-        }
+    // `--head-len`: draws the first `len` cells of each droplet as its head,
+    // dimming towards the back, for comet-like streaks.
+    pub fn set_head_len(&mut self, len: u16) {
+        self.head_len = len.max(1);
+        self.force_draw_everything = true;
+    }
 
-        match self.bold_mode {
-            BoldMode::Off => bold = false,
-            BoldMode::All => bold = true,
-            BoldMode::Random => {}
-        }
+    // `--tail-ghost`: the `len` cells an erasing tail reaches next fade to
+    // the dimmest color first, softening its edge; 0 cuts the tail off hard.
+    pub fn set_tail_ghost(&mut self, len: u16) {
+        self.tail_ghost = len;
+        self.force_draw_everything = true;
+    }
 
-        let fg = if self.color_mode == ColorMode::Mono {
-            None
-        } else {
-            self.palette.colors.get(color_idx as usize).copied()
-        };
+    pub fn set_bold_mode(&mut self, mode: BoldMode) {
+        self.bold_mode = mode;
+        self.force_draw_everything = true;
+    }
 
-        (fg, bold)
+    pub fn rain(&mut self, frame: &mut Frame) {
+        self.rain_at(frame, self.clock.now());
     }
 
-    fn reset_message(&mut self) {
-        if self.message.is_empty() {
+    // Advances the simulation to `now` and draws it into `frame`.
+    pub fn rain_at(&mut self, frame: &mut Frame, now: Instant) {
+        if self.pause {
             return;
         }
+        self.advance_to(now);
+        self.draw(frame);
+    }
 
-        let first_col = self.cols / 4;
-        let last_col = (3 * self.cols) / 4;
-        let chars_per_col = last_col.saturating_sub(first_col) + 1;
-        let msg_lines = (self.message.len() as u16 / chars_per_col).saturating_add(1);
-        let first_line = self.lines / 2 - msg_lines / 2;
-
-        let mut remaining = self.message.len() as u16;
-        let mut line = first_line;
-        let mut col = first_col;
-        if remaining < chars_per_col {
-            col += (chars_per_col - remaining) / 2;
+    // Advances the simulation by `dt` on the cloud's own timeline, without
+    // drawing, so replays and tests can run frame-accurately with `draw`.
+    pub fn step(&mut self, dt: Duration) {
+        if self.pause {
+            return;
         }
+        self.advance_to(self.now + dt);
+    }
 
-        for mc in &mut self.message {
-            mc.draw = false;
-            if line < self.lines {
-                mc.line = line;
-                mc.col = col;
-            } else {
-                mc.line = u16::MAX;
-                mc.col = u16::MAX;
-            }
-
-            if col == last_col {
-                line = line.saturating_add(1);
-                col = first_col;
-                if remaining < chars_per_col {
-                    col += (chars_per_col - remaining) / 2;
-                }
-            } else {
-                col = col.saturating_add(1);
+    fn advance_to(&mut self, now: Instant) {
+        self.now = now;
+        self.update_fade(now);
+
+        let mut steps = 0;
+        while now.saturating_duration_since(self.sim_time) >= SIM_STEP {
+            if steps == MAX_SIM_STEPS {
+                log::debug!(
+                    "simulation {:?} behind, skipping ahead",
+                    now.saturating_duration_since(self.sim_time)
+                );
+                self.sim_time = now;
+                break;
             }
-            remaining = remaining.saturating_sub(1);
+            self.sim_time += SIM_STEP;
+            self.sim_step(self.sim_time);
+            self.glitch_step(self.sim_time);
+            self.reshade_step(self.sim_time);
+            steps += 1;
         }
     }
 
-    fn calc_message(&mut self, frame: &Frame) {
-        for mc in &mut self.message {
-            if mc.line == u16::MAX || mc.col == u16::MAX {
-                break;
-            }
-            if let Some(c) = frame.get(mc.col, mc.line) {
-                if c.ch != ' ' {
-                    mc.draw = true;
+    // Swaps the glyphs of glitched cells under live droplets when a glitch
+    // cycle starts: all at once in `GlitchMode::Global`, per cell otherwise.
+    fn glitch_step(&mut self, now: Instant) {
+        if !self.glitchy {
+            return;
+        }
+        let cell_ms = now.saturating_duration_since(self.glitch_epoch).as_millis() as u64;
+        let global = self.time_for_glitch(now);
+        let cells = !self.glitch_timers.is_empty();
+        if global || cells {
+            for i in 0..self.droplets.len() {
+                let d = &self.droplets[i];
+                if !d.is_alive {
+                    continue;
+                }
+                let start_line = d.tail_put_line.map(|v| v + 1).unwrap_or(0);
+                let (hp, col, cp_idx) = (d.head_put_line, d.bound_col, d.char_pool_idx);
+                if global {
+                    self.do_glitch_span(start_line, hp, col, cp_idx);
+                } else {
+                    self.do_cell_glitch_span(start_line, hp, col, cp_idx, cell_ms);
                 }
             }
         }
+        self.prev_glitch_ms = cell_ms;
+        if global {
+            self.last_glitch_time = now;
+            let ms = self.rand_glitch_ms.sample(&mut self.mt) as u64;
+            self.next_glitch_time = self.last_glitch_time + Duration::from_millis(ms);
+            log::trace!("glitch cycle, next in {} ms", ms);
+        }
+        if self.glitch_drift > 0 && now >= self.next_drift {
+            self.drift_glitch_map();
+            // Cell mode has no shared cycle; drift as often as the slowest cell glitches.
+            self.next_drift = match global {
+                true => self.next_glitch_time,
+                false => now + Duration::from_millis(self.glitch_high_ms as u64),
+            };
+        }
     }
 
-    fn draw_message(&self, frame: &mut Frame) {
-        let bg = self.palette.bg;
-        for mc in &self.message {
-            if !mc.draw {
-                continue;
-            }
-            if mc.line == u16::MAX || mc.col == u16::MAX {
-                continue;
-            }
-            frame.set(
-                mc.col,
-                mc.line,
-                Cell {
-                    ch: mc.val,
-                    fg: if self.color_mode == ColorMode::Mono {
-                        None
-                    } else {
-                        self.palette.colors.last().copied()
-                    },
-                    bg,
-                    bold: self.bold_mode != BoldMode::Off,
-                },
-            );
+    fn drift_glitch_map(&mut self) {
+        for _ in 0..self.glitch_drift {
+            let Some(from) = self.glitch_map.pick(true, &mut self.mt) else {
+                return;
+            };
+            let Some(to) = self.glitch_map.pick(false, &mut self.mt) else {
+                return;
+            };
+            self.glitch_map.toggle(from);
+            self.glitch_map.toggle(to);
         }
     }
 
-    pub fn rain(&mut self, frame: &mut Frame) {
-        if self.pause {
-            return;
+    // Draws the droplets and effects as they stand at the cloud's current time.
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let now = self.now;
+        if let Some(src) = self.palette_source.as_mut() {
+            src.set_base(&self.palette);
+            if src.is_animated()
+                && now.saturating_duration_since(self.palette_redraw) >= PALETTE_REDRAW
+            {
+                self.palette_redraw = now;
+                self.force_draw_everything = true;
+            }
         }
-
-        let now = Instant::now();
-        self.spawn_droplets(now);
-
         if self.force_draw_everything {
             frame.clear_with_bg(self.palette.bg);
+            self.sync_strips();
         }
-
-        let time_for_glitch = self.time_for_glitch(now);
-
-        // Update pass (mut self)
-        for i in 0..self.droplets.len() {
-            if !self.droplets[i].is_alive {
-                continue;
-            }
-
-            let (col, start_line, hp, cp_idx, free_col) = {
-                let d = &mut self.droplets[i];
-                let free_col = d.advance(now, self.lines);
-                let col = d.bound_col;
-                let start_line = d.tail_put_line.map(|v| v + 1).unwrap_or(0);
-                let hp = d.head_put_line;
-                let cp_idx = d.char_pool_idx;
-                (col, start_line, hp, cp_idx, free_col)
-            };
-
-            if free_col {
-                self.set_column_spawn(col, true);
-            }
-
-            if time_for_glitch {
-                self.do_glitch_span(start_line, hp, col, cp_idx);
+        let lead = now.saturating_duration_since(self.sim_time);
+        let glitch_ms = now.saturating_duration_since(self.glitch_epoch).as_millis() as u64;
+
+        // Keep what the tails are about to blank, before they do.
+        if let Some(a) = self.after_image.as_mut() {
+            for d in self.droplets.iter().filter(|d| d.is_alive) {
+                if let Some(tp) = d.tail_put_line {
+                    for line in d.tail_cur_line..=tp {
+                        a.record(frame, d.bound_col, line, now);
+                    }
+                }
             }
         }
 
@@ -864,45 +1944,104 @@ impl Cloud {
         let draw_everything = self.force_draw_everything;
         let ctx = DrawCtx {
             lines: self.lines,
+            col_base: 0,
             full_width: self.full_width,
             shading_distance: self.shading_distance,
+            shading_speed: self.shading_mode == ShadingMode::Speed,
+            chars_per_sec: self.scaled_cps(),
+            min_speed: self.scaled_cps()
+                * (1.0 - self.speed_jitter)
+                * if self.async_mode {
+                    ASYNC_MIN_SPEED
+                } else {
+                    1.0
+                },
+            shade_curve: self.shade_curve,
+            shade_len: self.shade_len,
+            head_len: self.head_len,
+            tail_ghost: self.tail_ghost,
             bg: self.palette.bg,
             color_mode: self.color_mode,
             bold_mode: self.bold_mode,
             glitchy: self.glitchy,
             last_glitch_time: self.last_glitch_time,
             next_glitch_time: self.next_glitch_time,
-            palette_colors: &self.palette.colors,
+            glitch_timers: &self.glitch_timers,
+            glitch_ms,
+            palette: self.palette_source.as_deref().unwrap_or(&self.palette),
+            palette_t: now
+                .saturating_duration_since(self.palette_epoch)
+                .as_secs_f32(),
             color_map: &self.color_map,
             glitch_map: &self.glitch_map,
+            column_pool: &self.column_pool,
             char_pool: &self.char_pool,
         };
 
-        for d in &mut self.droplets {
-            if !d.is_alive {
-                continue;
+        if self.strips.is_empty() {
+            for d in &mut self.droplets {
+                if !d.is_alive {
+                    continue;
+                }
+                d.draw(&ctx, frame, now, lead, draw_everything);
             }
-            d.draw(&ctx, frame, now, draw_everything);
-
-            if !d.is_alive {
-                if let Some(cs) = self.col_stat.get_mut(d.bound_col as usize) {
-                    cs.num_droplets = cs.num_droplets.saturating_sub(1);
-                    if d.tail_put_line.unwrap_or(0) <= self.lines / 4 {
-                        cs.can_spawn = true;
+        } else {
+            // Columns are independent, so each strip is drawn on its own thread
+            // and then copied into place.
+            let bounds = &self.strip_bounds;
+            let mut buckets: Vec<Vec<&mut Droplet>> =
+                self.strips.iter().map(|_| Vec::new()).collect();
+            for d in self.droplets.iter_mut().filter(|d| d.is_alive) {
+                buckets[Self::strip_of(bounds, d.bound_col)].push(d);
+            }
+            let jobs = buckets.iter_mut().zip(self.strips.iter_mut()).zip(bounds);
+            self.pool.run(jobs.map(|((bucket, strip), &col_base)| {
+                let ctx = DrawCtx { col_base, ..ctx };
+                move || {
+                    for d in bucket.iter_mut() {
+                        d.draw(&ctx, strip, now, lead, draw_everything);
                     }
                 }
+            }));
+            for (strip, &x) in self.strips.iter().zip(bounds) {
+                frame.blit(strip, x, 0);
             }
         }
 
-        if !self.message.is_empty() {
-            self.calc_message(frame);
-            self.draw_message(frame);
+        if let Some(a) = self.after_image.as_mut() {
+            for d in self.droplets.iter().filter(|d| d.is_alive) {
+                let from = d.tail_put_line.map(|v| v + 1).unwrap_or(0);
+                a.clear_span(d.bound_col, from, d.head_line_after(lead));
+            }
+            a.draw(
+                frame,
+                now,
+                self.palette.bg,
+                self.color_mode,
+                self.palette.colors.first().copied(),
+            );
+        }
+        if let Some(h) = self.heat_map.as_mut() {
+            h.draw(frame, now, self.palette.bg, self.color_mode);
         }
 
-        if time_for_glitch {
-            self.last_glitch_time = now;
-            let ms = self.rand_glitch_ms.sample(&mut self.mt) as u64;
-            self.next_glitch_time = self.last_glitch_time + Duration::from_millis(ms);
+        if !self.effects.is_empty() {
+            let dt = now.saturating_duration_since(self.effect_time);
+            self.effect_time = now;
+            let state = CloudState {
+                cols: self.cols,
+                lines: self.lines,
+                full_width: self.full_width,
+                color_mode: self.color_mode,
+                bold_mode: self.bold_mode,
+                chars_per_sec: self.scaled_cps(),
+                palette: &self.palette,
+                droplets: &self.droplets,
+            };
+            for e in &mut self.effects {
+                e.update(&state, dt);
+                e.draw(frame);
+            }
         }
 
         self.force_draw_everything = false;
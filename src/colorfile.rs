@@ -0,0 +1,242 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::configfile;
+use crate::palette::rgb_to_ansi256;
+use crate::runtime::{UserColor, UserColors};
+use crate::terminal;
+use crate::x11colors;
+
+pub const VERSION_KEY: &str = "cosmostrix_colorfile_version";
+
+const BUILTIN: &[(&str, &str)] = &[
+    (
+        "phosphor",
+        "cosmostrix_colorfile_version 2\nbg: black\nhead: #d8ffd8\n#002b00\n#005f00\n#00a000\n#00e000\n#60ff60\n",
+    ),
+    (
+        "amber",
+        "cosmostrix_colorfile_version 2\nbg: black\nhead: #fff2c0\n#3a2000\n#7a4500\n#b86e00\n#ff9f00\n#ffc040\n",
+    ),
+    (
+        "ice",
+        "cosmostrix_colorfile_version 2\nbg: #000814\nhead: white\n#01304a\n#035a80\n#0a8fc0\n#4cc9f0\n#a0e8ff\n",
+    ),
+    (
+        "synthwave",
+        "cosmostrix_colorfile_version 2\nbg: #120024\nhead: #fff0ff\n#3d0066\n#7a00b8\n#c400ff\n#ff2fd0\n#ff9df0\n",
+    ),
+    (
+        "blood",
+        "cosmostrix_colorfile_version 2\nbg: black\nhead: #ffd0d0\n#2a0000\n#5c0000\n#990000\n#d40000\n#ff3030\n",
+    ),
+];
+
+pub fn builtin_names() -> impl Iterator<Item = &'static str> {
+    BUILTIN.iter().map(|(n, _)| *n)
+}
+
+fn user_colors_dir() -> Option<PathBuf> {
+    Some(configfile::config_dir()?.join("colors"))
+}
+
+// Names of the color files in ~/.config/cosmostrix/colors/, sorted.
+pub fn user_names() -> Vec<String> {
+    let Some(entries) = user_colors_dir().and_then(|d| fs::read_dir(d).ok()) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| {
+            let path = e.path();
+            let ext = path.extension().and_then(|x| x.to_str());
+            match ext {
+                None | Some("txt") => path.file_stem().map(|s| s.to_string_lossy().into_owned()),
+                Some(_) => None,
+            }
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+// Accepts a path, a name in ~/.config/cosmostrix/colors/, or a built-in name.
+pub fn load_user_colors(spec: &Path) -> Result<UserColors, String> {
+    if spec.is_file() {
+        return parse_user_colors(spec);
+    }
+
+    let is_bare = spec.components().count() == 1 && spec.extension().is_none();
+    let name = spec.to_string_lossy();
+    if is_bare {
+        if let Some(dir) = user_colors_dir() {
+            for candidate in [dir.join(&*name), dir.join(format!("{}.txt", name))] {
+                if candidate.is_file() {
+                    return parse_user_colors(&candidate);
+                }
+            }
+        }
+
+        let key = name.to_ascii_lowercase();
+        if let Some((_, content)) = BUILTIN.iter().find(|(n, _)| *n == key) {
+            return parse_user_colors_str(content);
+        }
+    }
+
+    Err(format!(
+        "color file not found: {} (built-in: {})",
+        name,
+        builtin_names().collect::<Vec<_>>().join(", ")
+    ))
+}
+
+// Droplet ramp taken from the terminal's own ANSI theme, darkest first.
+const TERMINAL_RAMP: [u8; 5] = [8, 2, 10, 7, 15];
+
+pub fn terminal_user_colors() -> UserColors {
+    let rgb = terminal::query_palette(&TERMINAL_RAMP);
+    let colors = TERMINAL_RAMP
+        .iter()
+        .zip(rgb)
+        .map(|(&index, rgb)| match rgb {
+            Some((r, g, b)) => UserColor {
+                index,
+                ..from_rgb(r, g, b)
+            },
+            None => UserColor {
+                index,
+                rgb_1000: None,
+            },
+        })
+        .collect();
+    let bg = terminal::query_background().map(|(r, g, b)| UserColor {
+        index: 0,
+        ..from_rgb(r, g, b)
+    });
+    UserColors {
+        colors,
+        bg,
+        head: None,
+    }
+}
+
+fn from_rgb(r: u8, g: u8, b: u8) -> UserColor {
+    let to_1000 = |v: u8| (v as u32 * 1000 / 255) as u16;
+    UserColor {
+        index: rgb_to_ansi256(r, g, b),
+        rgb_1000: Some((to_1000(r), to_1000(g), to_1000(b))),
+    }
+}
+
+fn parse_hex(s: &str) -> Option<UserColor> {
+    let hex = s.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let v = u32::from_str_radix(hex, 16).ok()?;
+    Some(from_rgb((v >> 16) as u8, (v >> 8) as u8, v as u8))
+}
+
+fn is_hex_line(line: &str) -> bool {
+    let word = line
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .next()
+        .unwrap_or("");
+    parse_hex(word).is_some()
+}
+
+pub fn parse_color(s: &str) -> Result<UserColor, String> {
+    let s = s.trim();
+    if let Some(c) = parse_hex(s) {
+        return Ok(c);
+    }
+
+    let parts: Vec<&str> = s.split(',').map(|p| p.trim()).collect();
+    if let Ok(idx) = parts[0].parse::<u16>() {
+        let rgb_1000 = if parts.len() >= 4 {
+            let r: u16 = parts[1].parse().map_err(|_| "invalid r".to_string())?;
+            let g: u16 = parts[2].parse().map_err(|_| "invalid g".to_string())?;
+            let b: u16 = parts[3].parse().map_err(|_| "invalid b".to_string())?;
+            Some((r, g, b))
+        } else {
+            None
+        };
+        return Ok(UserColor {
+            index: idx.min(255) as u8,
+            rgb_1000,
+        });
+    }
+
+    x11colors::lookup(s)
+        .map(|(r, g, b)| from_rgb(r, g, b))
+        .ok_or_else(|| format!("invalid color: {}", s))
+}
+
+pub fn parse_user_colors(path: &Path) -> Result<UserColors, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse_user_colors_str(&content)
+}
+
+pub fn parse_user_colors_str(content: &str) -> Result<UserColors, String> {
+    let mut version = 1u32;
+    let mut colors: Vec<UserColor> = Vec::new();
+    let mut bg: Option<UserColor> = None;
+    let mut head: Option<UserColor> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let first = line.chars().next().unwrap_or(' ');
+        if (first == '#' && !is_hex_line(line))
+            || first == ';'
+            || first == '/'
+            || first == '*'
+            || first == '@'
+        {
+            continue;
+        }
+        if line.contains("neo_color_version") {
+            continue;
+        }
+        if let Some(v) = line.strip_prefix(VERSION_KEY) {
+            version = v
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid {}", VERSION_KEY))?;
+            if version > 2 {
+                return Err(format!("unsupported color file version: {}", version));
+            }
+            continue;
+        }
+
+        if let Some((key, val)) = line.split_once([':', '=']) {
+            match key.trim().to_ascii_lowercase().as_str() {
+                "bg" | "background" => bg = Some(parse_color(val)?),
+                "head" => head = Some(parse_color(val)?),
+                k => return Err(format!("unknown color file annotation: {}", k)),
+            }
+            continue;
+        }
+
+        colors.push(parse_color(line)?);
+    }
+
+    if version < 2 && bg.is_none() {
+        if colors.len() < 2 {
+            return Err("color file must contain at least two colors".to_string());
+        }
+        bg = Some(colors.remove(0));
+    }
+
+    if colors.is_empty() && head.is_none() {
+        return Err("color file must contain at least one droplet color".to_string());
+    }
+
+    Ok(UserColors { colors, bg, head })
+}
@@ -0,0 +1,339 @@
+// Copyright (c) 2025 rezk_nightky
+
+use clap::{Arg, Command, CommandFactory};
+
+use crate::config::Args;
+use crate::validate::{choices, hint};
+
+const NAME: &str = "cosmostrix";
+
+enum Value {
+    Flag,
+    Choices(Vec<&'static str>),
+    File,
+    Any,
+}
+
+struct Opt {
+    long: String,
+    short: Option<char>,
+    help: String,
+    value: Value,
+    optional: bool,
+}
+
+fn value_of(a: &Arg, name: &str) -> Value {
+    let c = choices(name);
+    let path = a
+        .get_value_names()
+        .into_iter()
+        .flatten()
+        .any(|n| matches!(n.as_str(), "FILE" | "PATH" | "FILE|NAME"));
+    if !c.is_empty() {
+        Value::Choices(c)
+    } else if path {
+        Value::File
+    } else {
+        Value::Any
+    }
+}
+
+fn help_of(a: &Arg) -> String {
+    a.get_help().map(|h| h.to_string()).unwrap_or_default()
+}
+
+fn opts(cmd: &Command) -> Vec<Opt> {
+    cmd.get_arguments()
+        .filter(|a| !a.is_hide_set() && !a.is_positional())
+        .filter_map(|a| {
+            let long = a.get_long()?;
+            Some(Opt {
+                long: long.to_string(),
+                short: a.get_short(),
+                help: help_of(a),
+                value: match a.get_action().takes_values() {
+                    false => Value::Flag,
+                    true => value_of(a, long),
+                },
+                optional: a.get_num_args().is_some_and(|n| n.min_values() == 0),
+            })
+        })
+        .collect()
+}
+
+// The first positional argument of a command (`record FILE`,
+// `completions SHELL`), if it has one.
+fn positional(cmd: &Command) -> Option<Value> {
+    let a = cmd.get_positionals().next()?;
+    Some(value_of(a, &a.get_id().as_str().replace('_', "-")))
+}
+
+fn subcommands(cmd: &Command) -> Vec<&Command> {
+    cmd.get_subcommands().filter(|c| !c.is_hide_set()).collect()
+}
+
+// `cosmostrix completions SHELL`: a completion script built from the same
+// option table as the parser, so new flags show up without editing it.
+pub fn script(shell: &str) -> Result<String, String> {
+    let mut cmd = Args::command();
+    cmd.build();
+    match shell.trim().to_ascii_lowercase().as_str() {
+        "bash" => Ok(bash(&cmd)),
+        "zsh" => Ok(zsh(&cmd)),
+        "fish" => Ok(fish(&cmd)),
+        _ => Err(hint(
+            format!("expected bash, zsh or fish, got: {}", shell),
+            shell,
+            "shell",
+        )),
+    }
+}
+
+fn words(opts: &[Opt]) -> Vec<String> {
+    let mut out = Vec::new();
+    for o in opts {
+        out.push(format!("--{}", o.long));
+        if let Some(s) = o.short {
+            out.push(format!("-{}", s));
+        }
+    }
+    out
+}
+
+fn bash_values(value: &Value) -> String {
+    match value {
+        Value::Choices(c) => format!("COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))", c.join(" ")),
+        Value::File => "COMPREPLY=($(compgen -f -- \"$cur\"))".to_string(),
+        Value::Flag | Value::Any => "COMPREPLY=()".to_string(),
+    }
+}
+
+fn bash(cmd: &Command) -> String {
+    let subs = subcommands(cmd);
+    let names: Vec<&str> = subs.iter().map(|c| c.get_name()).collect();
+    let top = opts(cmd);
+
+    let mut out = format!("# bash completion for {}\n\n_{}() {{\n", NAME, NAME);
+    out.push_str("    local cur prev cmd w opts\n");
+    out.push_str("    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+    out.push_str("    prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+    out.push_str("    cmd=\"\"\n");
+    out.push_str("    for w in \"${COMP_WORDS[@]:1:COMP_CWORD-1}\"; do\n");
+    out.push_str(&format!(
+        "        case \"$w\" in\n            {}) cmd=\"$w\" ;;\n        esac\n    done\n\n",
+        names.join("|")
+    ));
+
+    out.push_str("    case \"$prev\" in\n");
+    let all: Vec<Opt> = top
+        .into_iter()
+        .chain(subs.iter().flat_map(|c| opts(c)))
+        .collect();
+    let mut seen: Vec<String> = Vec::new();
+    for o in all.iter().filter(|o| !matches!(o.value, Value::Flag)) {
+        let mut pats = vec![format!("--{}", o.long)];
+        pats.extend(o.short.map(|s| format!("-{}", s)));
+        pats.retain(|p| !seen.contains(p));
+        if pats.is_empty() {
+            continue;
+        }
+        seen.extend(pats.iter().cloned());
+        out.push_str(&format!(
+            "        {}) {}; return ;;\n",
+            pats.join("|"),
+            bash_values(&o.value)
+        ));
+    }
+    out.push_str("    esac\n\n");
+
+    out.push_str("    case \"$cmd\" in\n");
+    out.push_str(&format!(
+        "        \"\") opts=\"{} {}\" ;;\n",
+        words(&opts(cmd)).join(" "),
+        names.join(" ")
+    ));
+    out.push_str(&format!(
+        "        run) opts=\"{}\" ;;\n",
+        words(&opts(cmd)).join(" ")
+    ));
+    for sub in subs.iter().filter(|c| c.get_name() != "run") {
+        out.push_str(&format!("        {})\n", sub.get_name()));
+        if let Some(v) = positional(sub).filter(|v| !matches!(v, Value::Any)) {
+            out.push_str(&format!(
+                "            if [[ \"$cur\" != -* ]]; then {}; return; fi\n",
+                bash_values(&v)
+            ));
+        }
+        out.push_str(&format!(
+            "            opts=\"{}\" ;;\n",
+            words(&opts(sub)).join(" ")
+        ));
+    }
+    out.push_str("    esac\n");
+    out.push_str("    COMPREPLY=($(compgen -W \"$opts\" -- \"$cur\"))\n}\n\n");
+    out.push_str(&format!("complete -F _{} {}\n", NAME, NAME));
+    out
+}
+
+fn zsh_quote(s: &str) -> String {
+    s.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+fn zsh_specs(opts: &[Opt], indent: &str) -> String {
+    let mut out = String::new();
+    for o in opts {
+        let value = match &o.value {
+            Value::Flag => String::new(),
+            Value::Choices(c) => format!(":{}:({})", o.long, c.join(" ")),
+            Value::File => format!(":{}:_files", o.long),
+            Value::Any => format!(":{}:", o.long),
+        };
+        let value = match o.optional {
+            true if !value.is_empty() => format!(":{}", value),
+            _ => value,
+        };
+        let eq = match (&o.value, o.optional) {
+            (Value::Flag, _) => "",
+            (_, true) => "=-",
+            (_, false) => "=",
+        };
+        let names = match o.short {
+            Some(s) => format!(
+                "'(-{s} --{l})'{{-{s},--{l}{eq}}}",
+                s = s,
+                l = o.long,
+                eq = eq
+            ),
+            None => format!("'--{}{}'", o.long, eq),
+        };
+        out.push_str(&format!(
+            "{}{}'[{}]{}' \\\n",
+            indent,
+            names,
+            zsh_quote(&o.help),
+            value
+        ));
+    }
+    out
+}
+
+fn zsh(cmd: &Command) -> String {
+    let subs = subcommands(cmd);
+    let mut out = format!(
+        "#compdef {}\n\n_{}() {{\n    local line state\n\n",
+        NAME, NAME
+    );
+    out.push_str("    _arguments -C -s \\\n");
+    out.push_str(&zsh_specs(&opts(cmd), "        "));
+    out.push_str("        '1: :->cmds' \\\n        '*:: :->args'\n\n");
+    out.push_str("    case $state in\n        cmds)\n            _values 'command' \\\n");
+    for sub in &subs {
+        let about = sub.get_about().map(|a| a.to_string()).unwrap_or_default();
+        out.push_str(&format!(
+            "                '{}[{}]' \\\n",
+            sub.get_name(),
+            zsh_quote(&about)
+        ));
+    }
+    out.push_str("            ;;\n        args)\n            case $line[1] in\n");
+    for sub in &subs {
+        let specs = if sub.get_name() == "run" {
+            opts(cmd)
+        } else {
+            opts(sub)
+        };
+        let pos = match positional(sub) {
+            Some(Value::Choices(c)) => {
+                format!("                        '1:value:({})'\n", c.join(" "))
+            }
+            Some(Value::File) => "                        '1:file:_files'\n".to_string(),
+            _ => String::new(),
+        };
+        out.push_str(&format!(
+            "                {})\n                    _arguments -s \\\n",
+            sub.get_name()
+        ));
+        out.push_str(&zsh_specs(&specs, "                        "));
+        out.push_str(&pos);
+        if pos.is_empty() {
+            out.push_str("                        && return\n");
+        }
+        out.push_str("                    ;;\n");
+    }
+    out.push_str("            esac\n            ;;\n    esac\n}\n\n");
+    out.push_str(&format!("_{} \"$@\"\n", NAME));
+    out
+}
+
+fn fish_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn fish_lines(out: &mut String, cond: Option<&str>, opts: &[Opt]) {
+    for o in opts {
+        let mut line = format!("complete -c {}", NAME);
+        if let Some(cond) = cond {
+            line.push_str(&format!(" -n {}", fish_quote(cond)));
+        }
+        if let Some(s) = o.short {
+            line.push_str(&format!(" -s {}", s));
+        }
+        line.push_str(&format!(" -l {}", o.long));
+        match &o.value {
+            Value::Flag => {}
+            Value::Choices(c) => line.push_str(&format!(" -x -a {}", fish_quote(&c.join(" ")))),
+            Value::File => line.push_str(" -r -F"),
+            Value::Any => line.push_str(" -x"),
+        }
+        if !o.help.is_empty() {
+            line.push_str(&format!(" -d {}", fish_quote(&o.help)));
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+}
+
+fn fish(cmd: &Command) -> String {
+    let subs = subcommands(cmd);
+    let mut out = format!(
+        "# fish completion for {}\n\ncomplete -c {} -f\n",
+        NAME, NAME
+    );
+    for sub in &subs {
+        let about = sub.get_about().map(|a| a.to_string()).unwrap_or_default();
+        out.push_str(&format!(
+            "complete -c {} -n __fish_use_subcommand -a {} -d {}\n",
+            NAME,
+            sub.get_name(),
+            fish_quote(&about)
+        ));
+    }
+    // The top-level options are global, so they complete after any command.
+    let global = opts(cmd);
+    fish_lines(&mut out, None, &global);
+    for sub in subs.iter().filter(|c| c.get_name() != "run") {
+        let cond = format!("__fish_seen_subcommand_from {}", sub.get_name());
+        let own: Vec<Opt> = opts(sub)
+            .into_iter()
+            .filter(|o| !global.iter().any(|g| g.long == o.long))
+            .collect();
+        fish_lines(&mut out, Some(&cond), &own);
+        match positional(sub) {
+            Some(Value::Choices(c)) => out.push_str(&format!(
+                "complete -c {} -n {} -a {}\n",
+                NAME,
+                fish_quote(&cond),
+                fish_quote(&c.join(" "))
+            )),
+            Some(Value::File) => out.push_str(&format!(
+                "complete -c {} -n {} -F\n",
+                NAME,
+                fish_quote(&cond)
+            )),
+            _ => {}
+        }
+    }
+    out
+}
@@ -3,7 +3,7 @@
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 #[derive(Clone, Copy, Debug)]
 pub struct U16Range {
@@ -33,75 +33,622 @@ impl FromStr for U16Range {
     }
 }
 
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Run the rain in this terminal (the default when no command is given)
+    Run,
+    /// Time stepping and drawing off screen and print the results
+    Bench {
+        /// number of frames to run
+        #[arg(long = "frames", default_value_t = 600, value_name = "N")]
+        frames: u32,
+
+        /// screen size to run them on
+        #[arg(long = "size", default_value = "200x60", value_name = "COLSxROWS")]
+        size: String,
+    },
+    /// Record --duration seconds (default 10) of rain off screen to an asciicast v2 file
+    Record {
+        /// where to write the recording
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// screen size to record
+        #[arg(long = "size", default_value = "80x24", value_name = "COLSxROWS")]
+        size: String,
+    },
+    /// Print every resolved option as a config file
+    Export,
+    /// Print a completion script for bash, zsh or fish
+    Completions {
+        #[arg(value_name = "SHELL")]
+        shell: String,
+    },
+    /// Print the manual page (roff)
+    Man,
+    /// Run one simulation and stream it to telnet/nc clients
+    Serve {
+        /// address to listen on
+        #[arg(long = "bind", default_value = "127.0.0.1", value_name = "ADDR")]
+        bind: String,
+
+        /// port to listen on
+        #[arg(long = "port", default_value_t = 2323, value_name = "PORT")]
+        port: u16,
+    },
+    /// Print a swatch of every color scheme and color file
+    Themes,
+    /// Probe the terminal's capabilities and suggest flags
+    Doctor,
+}
+
 #[derive(Parser, Debug, Clone)]
-#[command(name = "cosmostrix")]
+#[command(name = "cosmostrix", args_override_self = true)]
 pub struct Args {
-    #[arg(short = 'a', long = "async")]
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// enable async column speeds
+    #[arg(short = 'a', long = "async", global = true)]
     pub async_mode: bool,
 
-    #[arg(short = 'b', long = "bold", default_value_t = 1)]
+    /// switch color scheme by local time, e.g. "06:00=gold,20:00=purple"
+    #[arg(long = "auto-theme", value_name = "SCHEDULE", global = true)]
+    pub auto_theme: Option<String>,
+
+    /// scale speed and droplet lengths to the terminal height (relative to 24 rows)
+    #[arg(long = "auto-scale", global = true)]
+    pub auto_scale: bool,
+
+    /// renderer: auto, cell, kitty, sixel (pixel backends need their feature)
+    #[arg(
+        long = "backend",
+        default_value = "auto",
+        value_name = "NAME",
+        global = true
+    )]
+    pub backend: String,
+
+    /// draw the ASCII/ANSI art in FILE dimly behind the rain, lit up where heads pass
+    #[arg(long = "bg-art", value_name = "FILE", global = true)]
+    pub bg_art: Option<PathBuf>,
+
+    /// with a pixel backend, show a dimmed PPM image behind the rain
+    #[arg(long = "bg-image", value_name = "FILE", global = true)]
+    pub bg_image: Option<PathBuf>,
+
+    /// 0=off, 1=random, 2=all
+    #[arg(
+        short = 'b',
+        long = "bold",
+        default_value_t = 1,
+        value_name = "NUM",
+        global = true
+    )]
     pub bold: u8,
 
-    #[arg(short = 'C', long = "colorfile")]
+    /// load user colors from a file or a named color file
+    #[arg(
+        short = 'C',
+        long = "colorfile",
+        value_name = "FILE|NAME",
+        global = true
+    )]
     pub colorfile: Option<PathBuf>,
 
-    #[arg(short = 'c', long = "color", default_value = "green")]
+    /// color scheme
+    #[arg(
+        short = 'c',
+        long = "color",
+        default_value = "green",
+        value_name = "COLOR",
+        global = true
+    )]
     pub color: String,
 
-    #[arg(short = 'D', long = "defaultbg")]
+    /// only rain in every NUM-th column
+    #[arg(
+        long = "col-stride",
+        default_value_t = 1,
+        value_name = "NUM",
+        global = true
+    )]
+    pub col_stride: u16,
+
+    /// first rain column for --col-stride
+    #[arg(
+        long = "col-offset",
+        default_value_t = 0,
+        value_name = "NUM",
+        global = true
+    )]
+    pub col_offset: u16,
+
+    /// use terminal default background color
+    #[arg(short = 'D', long = "defaultbg", global = true)]
     pub defaultbg: bool,
 
-    #[arg(short = 'd', long = "density", default_value_t = 1.0)]
+    /// cycle scenes, schemes, charsets and speeds every SECS seconds (default: 20)
+    #[arg(long = "demo", num_args = 0..=1, default_missing_value = "20", value_name = "SECS", global = true)]
+    pub demo: Option<f32>,
+
+    /// let glyphs left by the tails fade out through dimmer colors over MS milliseconds
+    #[arg(long = "decay", value_name = "MS", global = true)]
+    pub decay: Option<u64>,
+
+    /// let each cell's random shade drift a step up or down every SECS seconds
+    #[arg(long = "reshade", value_name = "SECS", global = true)]
+    pub reshade: Option<f32>,
+
+    /// droplet density
+    #[arg(
+        short = 'd',
+        long = "density",
+        default_value_t = 1.0,
+        value_name = "NUM",
+        global = true
+    )]
     pub density: f32,
 
-    #[arg(short = 'F', long = "fullwidth")]
+    /// quit after SECS seconds
+    #[arg(long = "duration", value_name = "SECS", global = true)]
+    pub duration: Option<f32>,
+
+    /// spatial spawn weights: uniform, center, edges, gradient-left, file:<MASK>
+    #[arg(
+        long = "density-profile",
+        default_value = "uniform",
+        value_name = "NAME",
+        global = true
+    )]
+    pub density_profile: String,
+
+    /// use two columns per character (double-width glyphs fill both)
+    #[arg(short = 'F', long = "fullwidth", global = true)]
     pub fullwidth: bool,
 
-    #[arg(short = 'f', long = "fps", default_value_t = 60.0)]
+    /// target FPS
+    #[arg(
+        short = 'f',
+        long = "fps",
+        default_value_t = 60.0,
+        value_name = "NUM",
+        global = true
+    )]
     pub fps: f64,
 
-    #[arg(short = 'g', long = "glitchms", default_value = "300,400")]
+    /// glitch timing range in ms
+    #[arg(
+        short = 'g',
+        long = "glitchms",
+        default_value = "300,400",
+        value_name = "LO,HI",
+        global = true
+    )]
     pub glitch_ms: U16Range,
 
-    #[arg(short = 'G', long = "glitchpct", default_value_t = 10.0)]
+    /// glitch chance percent
+    #[arg(
+        short = 'G',
+        long = "glitchpct",
+        default_value_t = 10.0,
+        value_name = "PCT",
+        global = true
+    )]
     pub glitch_pct: f32,
 
-    #[arg(short = 'l', long = "lingerms", default_value = "1,3000")]
+    /// global: all glitched cells flicker together (default); cell: each on its own timer
+    #[arg(
+        long = "glitch-mode",
+        default_value = "global",
+        value_name = "MODE",
+        global = true
+    )]
+    pub glitch_mode: String,
+
+    /// move NUM glitched cells to new spots every glitch cycle, so glitches wander over time
+    #[arg(
+        long = "glitch-drift",
+        default_value_t = 0,
+        value_name = "NUM",
+        global = true
+    )]
+    pub glitch_drift: u16,
+
+    /// give each cell of a column a fixed glyph while droplets run through it; glitches only flicker the color
+    #[arg(long = "stable-glyphs", global = true)]
+    pub stable_glyphs: bool,
+
+    /// turn the droplet colors once around the hue circle every SECS seconds (256-color and truecolor)
+    #[arg(long = "hue-cycle", value_name = "SECS", global = true)]
+    pub hue_cycle: Option<f32>,
+
+    /// half-block rendering with double vertical resolution
+    #[arg(long = "hi-res", global = true)]
+    pub hi_res: bool,
+
+    /// use light-background palettes (auto-detected by default)
+    #[arg(long = "light", conflicts_with = "dark", global = true)]
+    pub light: bool,
+
+    /// use dark-background palettes, skipping detection
+    #[arg(long = "dark", global = true)]
+    pub dark: bool,
+
+    /// wait until there has been no input for MINUTES, then run full screen until a key (repeats)
+    #[arg(long = "idle", value_name = "MINUTES", global = true)]
+    pub idle: Option<f32>,
+
+    /// rain text received over TCP/UDP (4242 for 127.0.0.1:4242, or e.g. 0.0.0.0:4242)
+    #[arg(long = "listen", value_name = "PORT|ADDR", global = true)]
+    pub listen: Option<String>,
+
+    /// phosphor glow around droplet heads (truecolor only)
+    #[arg(long = "glow", global = true)]
+    pub glow: bool,
+
+    /// override the droplet head color (#RRGGBB or 0-255)
+    #[arg(long = "head-color", value_name = "COLOR", global = true)]
+    pub head_color: Option<String>,
+
+    /// override the droplet tail color (#RRGGBB or 0-255)
+    #[arg(long = "tail-color", value_name = "COLOR", global = true)]
+    pub tail_color: Option<String>,
+
+    /// append debug logs (resizes, spawns, glitch cycles, frame times) to PATH
+    #[arg(long = "log-file", value_name = "PATH", global = true)]
+    pub log_file: Option<PathBuf>,
+
+    /// off, error, warn, info, debug or trace
+    #[arg(
+        long = "log-level",
+        default_value = "debug",
+        value_name = "LEVEL",
+        global = true
+    )]
+    pub log_level: String,
+
+    /// linger timing range in ms
+    #[arg(
+        short = 'l',
+        long = "lingerms",
+        default_value = "1,3000",
+        value_name = "LO,HI",
+        global = true
+    )]
     pub linger_ms: U16Range,
 
-    #[arg(short = 'M', long = "shadingmode", default_value_t = 0)]
+    /// 0=random, 1=distance-from-head, 2=speed: fast droplets bright, slow ones dim
+    #[arg(
+        short = 'M',
+        long = "shadingmode",
+        default_value_t = 0,
+        value_name = "NUM",
+        global = true
+    )]
     pub shading_mode: u8,
 
-    #[arg(short = 'm', long = "message")]
+    /// distance-from-head falloff: linear (default), quadratic, exp, steps:N
+    #[arg(
+        long = "shade-curve",
+        default_value = "linear",
+        value_name = "CURVE",
+        global = true
+    )]
+    pub shade_curve: String,
+
+    /// shade only the NUM cells behind the head, the rest stay dim
+    #[arg(
+        long = "shade-len",
+        default_value_t = 0,
+        value_name = "NUM",
+        global = true
+    )]
+    pub shade_len: u16,
+
+    /// draw the first NUM cells of each droplet as its head, dimming towards the back
+    #[arg(
+        long = "head-len",
+        default_value_t = 1,
+        value_name = "NUM",
+        global = true
+    )]
+    pub head_len: u16,
+
+    /// draw the NUM cells an erasing tail reaches next in the dimmest color (0: cut off hard)
+    #[arg(
+        long = "tail-ghost",
+        default_value_t = 1,
+        value_name = "NUM",
+        global = true
+    )]
+    pub tail_ghost: u16,
+
+    /// tune output for slow links such as SSH
+    #[arg(long = "low-bandwidth", num_args = 0..=1, default_missing_value = "on", default_value = "auto", value_name = "on|off|auto", global = true)]
+    pub low_bandwidth: String,
+
+    /// scroll TEXT across the screen over the rain, right to left
+    #[arg(long = "marquee", value_name = "TEXT", global = true)]
+    pub marquee: Option<String>,
+
+    /// row for --marquee, counted from 0 at the top (default: the middle row)
+    #[arg(long = "marquee-row", value_name = "ROW", global = true)]
+    pub marquee_row: Option<u16>,
+
+    /// columns per second for --marquee
+    #[arg(
+        long = "marquee-speed",
+        default_value_t = 12.0,
+        value_name = "NUM",
+        global = true
+    )]
+    pub marquee_speed: f32,
+
+    /// overlay message
+    #[arg(short = 'm', long = "message", value_name = "TEXT", global = true)]
     pub message: Option<String>,
 
-    #[arg(long = "maxdpc", default_value_t = 3)]
+    /// let the rain start revealing the message only after SECS seconds
+    #[arg(
+        long = "message-delay",
+        default_value_t = 0.0,
+        value_name = "SECS",
+        global = true
+    )]
+    pub message_delay: f32,
+
+    /// hide the message again SECS seconds after it is fully revealed, then start over (default: never)
+    #[arg(long = "message-hold", value_name = "SECS", global = true)]
+    pub message_hold: Option<f32>,
+
+    /// put the message somewhere random each cycle instead of the center
+    #[arg(long = "message-scatter", global = true)]
+    pub message_scatter: bool,
+
+    /// drive column speed/density from live system load: cpu, mem, net
+    #[arg(long = "metrics", value_name = "SOURCE", global = true)]
+    pub metrics: Option<String>,
+
+    /// spell phrases from FILE (one per line) down random columns
+    #[arg(long = "phrases", value_name = "FILE", global = true)]
+    pub phrases: Option<PathBuf>,
+
+    /// open on a screen already full of rain (runs 5-20 s of simulation first)
+    #[arg(long = "prefill", global = true)]
+    pub prefill: bool,
+
+    /// save current settings to the config file on exit
+    #[arg(long = "save-config", global = true)]
+    pub save_config: bool,
+
+    /// max droplets per column (clamped to 1..3, default: 3)
+    #[arg(
+        long = "maxdpc",
+        default_value_t = 3,
+        value_name = "NUM",
+        global = true
+    )]
     pub max_droplets_per_column: u8,
 
-    #[arg(long = "noglitch")]
+    /// when a droplet catches up with the one ahead: pass (default), merge or queue
+    #[arg(
+        long = "collision",
+        default_value = "pass",
+        value_name = "MODE",
+        global = true
+    )]
+    pub collision: String,
+
+    /// cap on live droplets; the pool grows on demand up to it
+    #[arg(long = "max-droplets", default_value_t = cosmostrix::cloud::DEFAULT_MAX_DROPLETS, value_name = "NUM", global = true)]
+    pub max_droplets: usize,
+
+    /// stop spawning while NUM droplets are alive
+    #[arg(long = "max-active", value_name = "NUM", global = true)]
+    pub max_active: Option<usize>,
+
+    /// spawn NUM droplets per second, ignoring --density, --speed and the screen size
+    #[arg(long = "spawn-rate", value_name = "NUM", global = true)]
+    pub spawn_rate: Option<f32>,
+
+    /// disable glitch
+    #[arg(long = "noglitch", global = true)]
     pub noglitch: bool,
 
-    #[arg(short = 'r', long = "rippct", default_value_t = 33.33333)]
+    /// die-early percent
+    #[arg(
+        short = 'r',
+        long = "rippct",
+        default_value_t = 33.33333,
+        value_name = "PCT",
+        global = true
+    )]
     pub rippct: f32,
 
-    #[arg(short = 'S', long = "speed", default_value_t = 8.0)]
+    /// chars per second
+    #[arg(
+        short = 'S',
+        long = "speed",
+        default_value_t = 8.0,
+        value_name = "NUM",
+        global = true
+    )]
     pub speed: f32,
 
-    #[arg(short = 's', long = "screensaver")]
+    /// share scheme changes and --shuffle timing with other instances in group NAME (Unix)
+    #[arg(long = "sync-group", value_name = "NAME", global = true)]
+    pub sync_group: Option<String>,
+
+    /// append per-frame timings to FILE (CSV, or JSON lines for .json/.jsonl) and print a summary on exit
+    #[arg(long = "telemetry", value_name = "FILE", global = true)]
+    pub telemetry: Option<PathBuf>,
+
+    /// run all simulated time at NUM times real speed, e.g. 0.5 for slow motion
+    #[arg(
+        long = "time-scale",
+        default_value_t = 1.0,
+        value_name = "NUM",
+        global = true
+    )]
+    pub time_scale: f64,
+
+    /// set the window title while running (restored on exit where supported)
+    #[arg(long = "title", value_name = "TEXT", global = true)]
+    pub title: Option<String>,
+
+    /// scroll random glyphs through the window title
+    #[arg(long = "title-rain", global = true)]
+    pub title_rain: bool,
+
+    /// step and draw column strips on up to N threads, 0 for one per core
+    #[arg(long = "threads", default_value_t = 1, value_name = "N", global = true)]
+    pub threads: usize,
+
+    /// per-droplet random slowdown percent (clamped to 0..90, default: 0)
+    #[arg(
+        long = "speed-jitter",
+        default_value_t = 0.0,
+        value_name = "PCT",
+        global = true
+    )]
+    pub speed_jitter: f32,
+
+    /// start with a scene other than the rain: starfield, snow, life, fireworks, waterfall
+    #[arg(
+        long = "scene",
+        default_value = "rain",
+        value_name = "NAME",
+        global = true
+    )]
+    pub scene: String,
+
+    /// data for the waterfall scene: random (default) or stdin
+    #[arg(
+        long = "scene-input",
+        default_value = "random",
+        value_name = "SRC",
+        global = true
+    )]
+    pub scene_input: String,
+
+    /// capture the mouse: wheel changes speed, shift+wheel changes density
+    #[arg(long = "mouse", global = true)]
+    pub mouse: bool,
+
+    /// run CMD through the shell after quitting with a key or the mouse
+    #[arg(long = "on-exit", value_name = "CMD", global = true)]
+    pub on_exit: Option<String>,
+
+    /// lock the screen after quitting with a key or the mouse (swaylock, i3lock or loginctl)
+    #[arg(long = "lock", global = true)]
+    pub lock: bool,
+
+    /// exit on first keypress
+    #[arg(short = 's', long = "screensaver", global = true)]
     pub screensaver: bool,
 
-    #[arg(long = "shortpct", default_value_t = 50.0)]
+    /// open the columns over the first 3 seconds, from the left (default) or the center
+    #[arg(long = "wave-start", num_args = 0..=1, default_missing_value = "left", value_name = "FROM", global = true)]
+    pub wave_start: Option<String>,
+
+    /// Windows screensaver mode: accept /s /p /c, exit on any key or mouse input
+    #[arg(long = "win-screensaver", global = true)]
+    pub win_screensaver: bool,
+
+    /// split the screen into independent rain panes (e.g. 2x2)
+    #[arg(long = "panes", value_name = "COLSxROWS", global = true)]
+    pub panes: Option<String>,
+
+    /// comma-separated charsets, one per pane (cycled)
+    #[arg(long = "pane-charsets", value_name = "LIST", global = true)]
+    pub pane_charsets: Option<String>,
+
+    /// comma-separated color schemes, one per pane (cycled)
+    #[arg(long = "pane-colors", value_name = "LIST", global = true)]
+    pub pane_colors: Option<String>,
+
+    /// draw box-drawing lines between panes
+    #[arg(long = "pane-separators", global = true)]
+    pub pane_separators: bool,
+
+    /// confine the rain to a rectangle of the terminal (0-based cell offsets)
+    #[arg(long = "region", value_name = "X,Y,W,H", global = true)]
+    pub region: Option<String>,
+
+    /// keep blank cells around the rain, per side or one number for all (applied inside --region)
+    #[arg(long = "margin", value_name = "T,R,B,L", global = true)]
+    pub margin: Option<String>,
+
+    /// draw a single or double line box around the rain
+    #[arg(
+        long = "border",
+        default_value = "none",
+        value_name = "STYLE",
+        global = true
+    )]
+    pub border: String,
+
+    /// fade to black over the last minute and quit after SPAN (30m, 1h30m, 90s; bare numbers are minutes)
+    #[arg(long = "sleep-after", value_name = "SPAN", global = true)]
+    pub sleep_after: Option<String>,
+
+    /// switch to a random built-in color scheme every SECS seconds
+    #[arg(long = "shuffle", value_name = "SECS", global = true)]
+    pub shuffle: Option<f32>,
+
+    /// with --shuffle, also pick a random charset
+    #[arg(long = "shuffle-charsets", global = true)]
+    pub shuffle_charsets: bool,
+
+    /// short droplet percent
+    #[arg(
+        long = "shortpct",
+        default_value_t = 50.0,
+        value_name = "PCT",
+        global = true
+    )]
     pub shortpct: f32,
 
-    #[arg(long = "charset", default_value = "auto")]
+    /// typed keys spawn droplets: random (default) or cursor
+    #[arg(long = "typing", num_args = 0..=1, default_missing_value = "random", value_name = "MODE", global = true)]
+    pub typing: Option<String>,
+
+    /// character set
+    #[arg(
+        long = "charset",
+        default_value = "auto",
+        value_name = "NAME",
+        global = true
+    )]
     pub charset: String,
 
-    #[arg(long = "chars")]
+    /// custom unicode hex ranges (pairs)
+    #[arg(long = "chars", value_name = "HEX...", global = true)]
     pub chars: Option<String>,
 
-    #[arg(long = "colormode")]
+    /// force color mode (0, 8, 16, 256, 32)
+    #[arg(long = "colormode", value_name = "MODE", global = true)]
     pub colormode: Option<u16>,
 
-    #[arg(long = "info")]
+    /// ignore NO_COLOR and detect colors as usual
+    #[arg(long = "force-color", global = true)]
+    pub force_color: bool,
+
+    #[cfg(feature = "weather")]
+    /// follow current weather (requires the `weather` feature)
+    #[arg(long = "weather", value_name = "CITY", global = true)]
+    pub weather: Option<String>,
+
+    /// print build info and exit
+    #[arg(long = "info", global = true)]
     pub info: bool,
+
+    /// print every resolved option as TOML and exit, like `export`
+    #[arg(
+        long = "dump-config",
+        visible_alias = "print-effective-settings",
+        global = true
+    )]
+    pub dump_config: bool,
 }
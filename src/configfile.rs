@@ -0,0 +1,188 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory};
+
+use crate::config::Args;
+use crate::error::CosmostrixError;
+
+pub fn config_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        Some(v) => PathBuf::from(v),
+        None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("cosmostrix"))
+}
+
+pub fn path() -> Option<PathBuf> {
+    Some(config_dir()?.join("config.toml"))
+}
+
+pub fn toml_str(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn unquote(s: &str) -> Option<String> {
+    let inner = s.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.push(chars.next()?),
+            '"' => return None,
+            _ => out.push(c),
+        }
+    }
+    Some(out)
+}
+
+// Splits a `key = value` line, ignoring blanks and `#` comments. Values are
+// TOML strings, booleans or bare numbers; tables are not supported.
+fn parse_line(line: &str) -> Result<Option<(&str, &str)>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    if line.starts_with('[') {
+        return Err("tables are not supported".to_string());
+    }
+    let (key, value) = line.split_once('=').ok_or("expected `key = value`")?;
+    let value = value.trim();
+    let value = match value.starts_with('"') {
+        true => value,
+        false => value.split('#').next().unwrap_or("").trim(),
+    };
+    Ok(Some((key.trim(), value)))
+}
+
+// Turns the config file into command-line arguments placed before the real
+// ones, so anything given on the command line still wins.
+pub fn load_args(path: &Path) -> Result<Vec<OsString>, CosmostrixError> {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(CosmostrixError::File {
+                path: path.to_path_buf(),
+                source: e,
+            })
+        }
+    };
+
+    let cmd = Args::command();
+    let mut out = Vec::new();
+    for (n, line) in content.lines().enumerate() {
+        let err = |reason: String| CosmostrixError::Config {
+            path: path.to_path_buf(),
+            line: n + 1,
+            reason,
+        };
+        let Some((key, value)) = parse_line(line).map_err(err)? else {
+            continue;
+        };
+        let Some(arg) = cmd.get_arguments().find(|a| a.get_long() == Some(key)) else {
+            return Err(err(format!("unknown setting `{}`", key)));
+        };
+
+        if arg.get_action().takes_values() {
+            let value = match value.starts_with('"') {
+                true => unquote(value).ok_or_else(|| err(format!("bad string for `{}`", key)))?,
+                false => value.to_string(),
+            };
+            out.push(OsString::from(format!("--{}", key)));
+            out.push(OsString::from(value));
+        } else {
+            match value {
+                "true" => out.push(OsString::from(format!("--{}", key))),
+                "false" => {}
+                _ => return Err(err(format!("`{}` must be true or false", key))),
+            }
+        }
+    }
+    Ok(out)
+}
+
+// Writes `entries` (values already in TOML form) into the file, replacing
+// lines for the same keys and keeping everything else.
+pub fn save(path: &Path, entries: &[(&str, String)]) -> io::Result<()> {
+    let existing = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+
+    let mut written = vec![false; entries.len()];
+    let mut out = String::new();
+    for line in existing.lines() {
+        let key = parse_line(line).ok().flatten().map(|(k, _)| k);
+        match key.and_then(|k| entries.iter().position(|(e, _)| *e == k)) {
+            Some(i) => {
+                out.push_str(&format!("{} = {}\n", entries[i].0, entries[i].1));
+                written[i] = true;
+            }
+            None => {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    for ((key, value), _) in entries.iter().zip(&written).filter(|(_, w)| !**w) {
+        out.push_str(&format!("{} = {}\n", key, value));
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, out)
+}
+
+// Renders every option as it was resolved, noting whether each value came
+// from the defaults, the config file (the first `from_file` arguments) or
+// the command line.
+pub fn dump(matches: &ArgMatches, from_file: usize) -> String {
+    let mut out = String::new();
+    if let Some(p) = path() {
+        out.push_str(&format!("# config file: {}\n", p.display()));
+    }
+    for arg in Args::command().get_arguments() {
+        let Some(long) = arg.get_long() else {
+            continue;
+        };
+        let id = arg.get_id().as_str();
+        if matches!(long, "help" | "dump-config") {
+            continue;
+        }
+
+        let source = match matches.value_source(id) {
+            None => {
+                out.push_str(&format!("# {} =\n", long));
+                continue;
+            }
+            Some(ValueSource::DefaultValue) => "default",
+            Some(_) => match matches.indices_of(id).and_then(|mut i| i.next_back()) {
+                Some(i) if i <= from_file => "config file",
+                _ => "command line",
+            },
+        };
+        let raw: Vec<String> = matches
+            .get_raw(id)
+            .into_iter()
+            .flatten()
+            .map(|v| v.to_string_lossy().into_owned())
+            .collect();
+        let value = raw.join(",");
+        let value = match arg.get_action().takes_values() {
+            false => value,
+            true if value.parse::<f64>().is_ok() => value,
+            true => toml_str(&value),
+        };
+        out.push_str(&format!("{} = {}  # {}\n", long, value, source));
+    }
+    out
+}
@@ -0,0 +1,72 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::time::{Duration, Instant};
+
+use rand::rngs::ThreadRng;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+
+use crate::charset::Charset;
+use crate::runtime::ColorScheme;
+use crate::scene::SceneKind;
+
+// How long the old picture takes to dissolve into the new one.
+pub const TRANSITION: Duration = Duration::from_millis(600);
+const SPEEDS: std::ops::RangeInclusive<f32> = 4.0..=16.0;
+
+pub struct Pick {
+    pub scene: SceneKind,
+    pub scheme: ColorScheme,
+    pub charset: Charset,
+    pub speed: f32,
+}
+
+// `--demo`: every period moves on to the next scene with a random scheme,
+// charset and speed, for displays left running unattended.
+pub struct Demo {
+    period: Duration,
+    last: Instant,
+    rng: ThreadRng,
+}
+
+impl Demo {
+    pub fn new(secs: f32) -> Self {
+        Self {
+            // Too long for a `Duration` means never moving on.
+            period: Duration::try_from_secs_f32(secs.max(1.0)).unwrap_or(Duration::MAX),
+            last: Instant::now(),
+            rng: rand::rng(),
+        }
+    }
+
+    pub fn next(&mut self, scene: SceneKind, scheme: ColorScheme, def_ascii: bool) -> Option<Pick> {
+        if self.last.elapsed() < self.period {
+            return None;
+        }
+        self.last = Instant::now();
+
+        let schemes: Vec<ColorScheme> = ColorScheme::BUILTIN
+            .iter()
+            .copied()
+            .filter(|&s| s != scheme)
+            .collect();
+        let charsets: Vec<Charset> = if def_ascii {
+            Charset::ASCII_SHUFFLE.to_vec()
+        } else {
+            [
+                Charset::ASCII_SHUFFLE.as_slice(),
+                Charset::UNICODE_SHUFFLE.as_slice(),
+            ]
+            .concat()
+        };
+        Some(Pick {
+            scene: scene.next(),
+            scheme: schemes.choose(&mut self.rng).copied().unwrap_or(scheme),
+            charset: charsets
+                .choose(&mut self.rng)
+                .copied()
+                .unwrap_or(Charset::DEFAULT),
+            speed: self.rng.random_range(SPEEDS),
+        })
+    }
+}
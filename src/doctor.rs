@@ -0,0 +1,163 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::env;
+use std::io::{stdout, Write};
+use std::time::Duration;
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::terminal::{self, query_reply};
+
+const PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+const WIDTH_SAMPLES: usize = 48;
+
+// Every probe ends with a primary device attributes request, which all
+// terminals answer, so unsupported queries fail fast instead of timing out.
+fn probe(seq: &str) -> Option<String> {
+    query_reply(&format!("{}\x1b[c", seq), PROBE_TIMEOUT, |s| {
+        s.rsplit("\x1b[?").next().is_some_and(|r| r.ends_with('c'))
+    })
+}
+
+fn supports_kitty_graphics() -> bool {
+    probe("\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\").is_some_and(|r| r.contains("_Gi=31;OK"))
+}
+
+// DECRQM for mode 2026: 1 or 2 mean the terminal knows the mode, 3 means it
+// is always on.
+fn supports_sync_output() -> bool {
+    probe("\x1b[?2026$p").is_some_and(|r| {
+        r.split("[?2026;")
+            .nth(1)
+            .and_then(|s| s.split('$').next())
+            .is_some_and(|v| matches!(v, "1" | "2" | "3"))
+    })
+}
+
+fn utf8_locale() -> Option<String> {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|k| env::var(k).ok().filter(|v| !v.is_empty()))
+        .next()
+}
+
+fn cursor_column() -> Option<u16> {
+    let reply = query_reply("\x1b[6n", PROBE_TIMEOUT, |s| s.ends_with('R'))?;
+    let params = reply.rsplit("\x1b[").next()?.strip_suffix('R')?;
+    params.split(';').nth(1)?.parse().ok()
+}
+
+// Prints sample glyphs and compares how far the cursor moves with their
+// expected width. Returns (checked, narrower, wider); a missing glyph can
+// still advance correctly, so this only catches width mismatches.
+fn check_widths(chars: &[char]) -> Option<(usize, usize, usize)> {
+    let step = (chars.len() / WIDTH_SAMPLES).max(1);
+    let mut out = stdout();
+    let (mut checked, mut narrow, mut wide) = (0, 0, 0);
+    for &ch in chars.iter().step_by(step).take(WIDTH_SAMPLES) {
+        let _ = write!(out, "\r\x1b[2K{}", ch);
+        let advance = cursor_column()?.saturating_sub(1) as usize;
+        let expected = ch.width().unwrap_or(1);
+        checked += 1;
+        if advance < expected {
+            narrow += 1;
+        } else if advance > expected {
+            wide += 1;
+        }
+    }
+    let _ = write!(out, "\r\x1b[2K");
+    let _ = out.flush();
+    Some((checked, narrow, wide))
+}
+
+fn report(name: &str, ok: bool, detail: &str) {
+    let status = if ok { "yes" } else { "no" };
+    if detail.is_empty() {
+        println!("{:<20}{}", name, status);
+    } else {
+        println!("{:<20}{:<5}{}", name, status, detail);
+    }
+}
+
+pub fn run(charset: &str, chars: &[char], slow_link: Duration) {
+    let mut suggest: Vec<String> = Vec::new();
+
+    let colorterm = env::var("COLORTERM").unwrap_or_default();
+    let term = env::var("TERM").unwrap_or_default();
+    let truecolor = matches!(
+        colorterm.to_ascii_lowercase().as_str(),
+        "truecolor" | "24bit"
+    ) || (cfg!(windows) && env::var_os("WT_SESSION").is_some());
+    let color256 = truecolor || term.contains("256color");
+    report("truecolor", truecolor, &format!("COLORTERM={}", colorterm));
+    report("256 colors", color256, &format!("TERM={}", term));
+    if !truecolor {
+        suggest.push(
+            if color256 {
+                "--colormode 256"
+            } else {
+                "--colormode 16"
+            }
+            .to_string(),
+        );
+    }
+
+    let locale = utf8_locale();
+    let utf8 = locale
+        .as_ref()
+        .is_some_and(|l| l.to_ascii_uppercase().contains("UTF"));
+    report("UTF-8 locale", utf8, locale.as_deref().unwrap_or("unset"));
+    if !utf8 {
+        suggest.push("--charset ascii".to_string());
+    }
+
+    let sixel = terminal::supports_sixel();
+    report("sixel graphics", sixel, "");
+    if sixel {
+        suggest.push("--backend sixel (build with --features sixel)".to_string());
+    }
+    let kitty = supports_kitty_graphics();
+    report("kitty graphics", kitty, "");
+    if kitty {
+        suggest.push("--backend kitty (build with --features kitty)".to_string());
+    }
+    report("synchronized output", supports_sync_output(), "");
+
+    match terminal::measure_latency() {
+        Some(rtt) => {
+            let slow = rtt > slow_link;
+            report(
+                "fast link",
+                !slow,
+                &format!("{} ms round trip", rtt.as_millis()),
+            );
+            if slow {
+                suggest.push("--low-bandwidth on".to_string());
+            }
+        }
+        None => report("fast link", false, "terminal did not answer"),
+    }
+
+    match check_widths(chars) {
+        Some((checked, narrow, wide)) => {
+            let ok = narrow == 0 && wide == 0;
+            let detail = format!(
+                "{}: {} sampled, {} too narrow, {} too wide",
+                charset, checked, narrow, wide
+            );
+            report("glyph widths", ok, &detail);
+            if wide > 0 {
+                suggest.push("--fullwidth".to_string());
+            } else if narrow > 0 && utf8 {
+                suggest.push("--charset ascii".to_string());
+            }
+        }
+        None => report("glyph widths", false, "terminal did not report the cursor"),
+    }
+
+    if suggest.is_empty() {
+        println!("\nno flags needed");
+    } else {
+        println!("\nsuggested flags: {}", suggest.join(" "));
+    }
+}
@@ -1,7 +1,10 @@
 // Copyright (c) 2025 rezk_nightky
 
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
+use unicode_width::UnicodeWidthChar;
+
+use crate::clock::Instant;
 use crate::cloud::{CharLoc, DrawCtx};
 use crate::frame::Frame;
 
@@ -22,6 +25,8 @@ pub struct Droplet {
     pub char_pool_idx: u16,
     pub length: u16,
     pub chars_per_sec: f32,
+    pub speed_pct: f32,
+    pub head_char: Option<char>,
 
     pub advance_remainder: f32,
 
@@ -30,6 +35,12 @@ pub struct Droplet {
     pub time_to_linger: Duration,
 }
 
+impl Default for Droplet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Droplet {
     pub fn new() -> Self {
         Self {
@@ -45,6 +56,8 @@ impl Droplet {
             char_pool_idx: u16::MAX,
             length: u16::MAX,
             chars_per_sec: 0.0,
+            speed_pct: 1.0,
+            head_char: None,
 
             advance_remainder: 0.0,
 
@@ -54,10 +67,6 @@ impl Droplet {
         }
     }
 
-    pub fn reset(&mut self) {
-        *self = Self::new();
-    }
-
     pub fn activate(&mut self, now: Instant) {
         self.is_alive = true;
         self.is_head_crawling = true;
@@ -66,15 +75,6 @@ impl Droplet {
         self.last_time = Some(now);
     }
 
-    pub fn increment_time(&mut self, delta: Duration) {
-        if let Some(t) = self.last_time.as_mut() {
-            *t += delta;
-        }
-        if let Some(t) = self.head_stop_time.as_mut() {
-            *t += delta;
-        }
-    }
-
     pub fn advance(&mut self, now: Instant, lines: u16) -> bool {
         let Some(last) = self.last_time else {
             self.last_time = Some(now);
@@ -110,7 +110,9 @@ impl Droplet {
             }
         }
 
-        if self.is_tail_crawling && (self.head_put_line >= self.length || self.head_put_line >= self.end_line) {
+        if self.is_tail_crawling
+            && (self.head_put_line >= self.length || self.head_put_line >= self.end_line)
+        {
             let next_tail = match self.tail_put_line {
                 Some(v) => v.saturating_add(chars_advanced),
                 None => chars_advanced,
@@ -145,6 +147,21 @@ impl Droplet {
         false
     }
 
+    // `Collision::Queue`: puts the head back on `line`, just behind the
+    // droplet ahead (but not behind its own tail). It keeps its own speed and
+    // is held again every step it catches up, so it moves on at that speed
+    // once the way is clear. A head that reached its end line this step is
+    // moving again, since it was held short of it.
+    pub fn hold_behind(&mut self, line: u16) {
+        self.head_put_line = line.max(self.tail_put_line.unwrap_or(0));
+        self.advance_remainder = 0.0;
+        if !self.is_head_crawling && line < self.end_line {
+            self.is_head_crawling = true;
+            self.is_tail_crawling = true;
+            self.head_stop_time = None;
+        }
+    }
+
     fn is_head_bright(&self, now: Instant) -> bool {
         if self.is_head_crawling {
             return true;
@@ -155,40 +172,63 @@ impl Droplet {
         false
     }
 
-    pub fn draw(&mut self, ctx: &DrawCtx<'_>, frame: &mut Frame, now: Instant, draw_everything: bool) {
+    // Where the head would be `lead` after the last simulation step.
+    pub fn head_line_after(&self, lead: Duration) -> u16 {
+        if !self.is_head_crawling {
+            return self.head_put_line;
+        }
+        let ahead =
+            (self.advance_remainder + self.chars_per_sec * lead.as_secs_f32()).floor() as u16;
+        self.head_put_line.saturating_add(ahead).min(self.end_line)
+    }
+
+    pub fn draw(
+        &mut self,
+        ctx: &DrawCtx<'_>,
+        frame: &mut Frame,
+        now: Instant,
+        lead: Duration,
+        draw_everything: bool,
+    ) {
         let bg = ctx.bg;
+        let head = self.head_line_after(lead);
+        let x = self.bound_col - ctx.col_base;
 
         let mut start_line = 0u16;
         if let Some(tp) = self.tail_put_line {
             for line in self.tail_cur_line..=tp {
-                frame.set(
-                    self.bound_col,
-                    line,
-                    crate::terminal::blank_cell(bg),
-                );
+                frame.set(x, line, crate::cell::Cell::blank_with_bg(bg));
             }
             self.tail_cur_line = tp;
             start_line = tp.saturating_add(1);
         }
 
-        for line in start_line..=self.head_put_line {
+        for line in start_line..=head {
             if line >= ctx.lines {
                 break;
             }
 
             let is_glitched = ctx.is_glitched(line, self.bound_col);
-            let val = ctx.get_char(line, self.char_pool_idx);
+            let val = match self.head_char {
+                Some(ch) if line == head => ch,
+                _ => ctx.get_char(line, self.bound_col, self.char_pool_idx),
+            };
 
             let mut loc = CharLoc::Middle;
-            if self.tail_put_line.is_some() && Some(line) == self.tail_put_line.map(|v| v + 1) {
+            if self
+                .tail_put_line
+                .is_some_and(|tp| line > tp && line - tp <= ctx.tail_ghost)
+            {
                 loc = CharLoc::Tail;
             }
-            if line == self.head_put_line && self.is_head_bright(now) {
-                loc = CharLoc::Head;
+            if head - line < ctx.head_len && self.is_head_bright(now) {
+                loc = CharLoc::Head(head - line);
             }
 
+            // Cells the head covered last frame are redrawn, so they drop
+            // back to their own shade once it moves on.
             if matches!(loc, CharLoc::Middle)
-                && line < self.head_cur_line
+                && line.saturating_add(ctx.head_len.saturating_sub(1)) < self.head_cur_line
                 && !is_glitched
                 && line != self.end_line
                 && !ctx.shading_distance
@@ -197,10 +237,19 @@ impl Droplet {
                 continue;
             }
 
-            let (fg, bold) = ctx.get_attr(line, self.bound_col, val, loc, now, self.head_put_line, self.length);
+            let (fg, bold) = ctx.get_attr(
+                line,
+                self.bound_col,
+                val,
+                loc,
+                now,
+                head,
+                self.length,
+                self.chars_per_sec,
+            );
 
             frame.set(
-                self.bound_col,
+                x,
                 line,
                 crate::cell::Cell {
                     ch: val,
@@ -210,22 +259,20 @@ impl Droplet {
                 },
             );
 
-            if ctx.full_width {
-                if self.bound_col + 1 < frame.width {
-                    frame.set(
-                        self.bound_col + 1,
-                        line,
-                        crate::cell::Cell {
-                            ch: ' ',
-                            fg: None,
-                            bg,
-                            bold: false,
-                        },
-                    );
-                }
+            if ctx.full_width && x + 1 < frame.width && val.width().unwrap_or(1) < 2 {
+                frame.set(
+                    x + 1,
+                    line,
+                    crate::cell::Cell {
+                        ch: ' ',
+                        fg: None,
+                        bg,
+                        bold: false,
+                    },
+                );
             }
         }
 
-        self.head_cur_line = self.head_put_line;
+        self.head_cur_line = head;
     }
 }
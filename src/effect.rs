@@ -0,0 +1,689 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::time::Duration;
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::{
+    cell::{Cell, Color},
+    droplet::Droplet,
+    frame::Frame,
+    palette::{lerp_color, Palette},
+    runtime::{BoldMode, ColorMode},
+};
+
+// What effects get to see of the cloud each frame.
+pub struct CloudState<'a> {
+    pub cols: u16,
+    pub lines: u16,
+    pub full_width: bool,
+    pub color_mode: ColorMode,
+    pub bold_mode: BoldMode,
+    pub chars_per_sec: f32,
+    pub palette: &'a Palette,
+    pub droplets: &'a [Droplet],
+}
+
+// Effects run in the order they were added, after the droplets are drawn.
+// `update` is called once per frame with the time since the previous one,
+// then `draw` paints over the frame.
+pub trait Effect {
+    fn update(&mut self, cloud: &CloudState, dt: Duration);
+    fn draw(&self, frame: &mut Frame);
+    fn box_clone(&self) -> Box<dyn Effect>;
+}
+
+impl Clone for Box<dyn Effect> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+// Tints the background around each falling head (truecolor only).
+#[derive(Clone, Default)]
+pub struct Glow {
+    head: Option<Color>,
+    bg: Option<Color>,
+    cells: Vec<(u16, u16, f32)>,
+    prev: Vec<(u16, u16)>,
+}
+
+impl Effect for Glow {
+    fn update(&mut self, cloud: &CloudState, _dt: Duration) {
+        self.prev.clear();
+        self.prev
+            .extend(self.cells.drain(..).map(|(x, y, _)| (x, y)));
+        self.bg = cloud.palette.bg;
+        self.head = cloud.palette.colors.last().copied();
+        if cloud.color_mode != ColorMode::TrueColor {
+            return;
+        }
+
+        let step = if cloud.full_width { 2 } else { 1 };
+        for d in cloud.droplets {
+            if !d.is_alive || !d.is_head_crawling {
+                continue;
+            }
+            for dy in -1i32..=1 {
+                for dx in -2i32..=2 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+                    let x = d.bound_col as i32 + dx * step;
+                    let y = d.head_put_line as i32 + dy;
+                    if x < 0 || y < 0 {
+                        continue;
+                    }
+                    let t = if dx.abs() <= 1 { 0.3 } else { 0.15 };
+                    self.cells.push((x as u16, y as u16, t));
+                }
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        for &(x, y) in &self.prev {
+            if let Some(i) = frame.index(x, y) {
+                frame.cells[i].bg = self.bg;
+            }
+        }
+        let Some(head) = self.head else {
+            return;
+        };
+        let bg = self.bg.unwrap_or(Color::Rgb { r: 0, g: 0, b: 0 });
+        for &(x, y, t) in &self.cells {
+            if let Some(i) = frame.index(x, y) {
+                let base = frame.cells[i].bg.unwrap_or(bg);
+                frame.cells[i].bg = Some(lerp_color(base, head, t));
+            }
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn Effect> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct MsgChr {
+    line: u16,
+    col: u16,
+    val: char,
+    draw: bool,
+}
+
+// Text centred on the screen whose letters stay lit once a droplet has
+// passed over them. Letters can only be revealed `delay` into each cycle;
+// with a `hold`, the fully revealed text stays that long and then hides
+// again for the next cycle, which shows the next text of the list. With
+// `scatter`, each cycle puts the text somewhere random instead.
+#[derive(Clone)]
+pub struct Message {
+    texts: Vec<Vec<char>>,
+    text: usize,
+    chars: Vec<MsgChr>,
+    size: (u16, u16),
+    cell: Cell,
+    delay: Duration,
+    hold: Option<Duration>,
+    scatter: bool,
+    since: Duration,
+    held: Duration,
+    cleared: Vec<(u16, u16)>,
+    rng: SmallRng,
+}
+
+impl Message {
+    pub fn new(msg: &str) -> Self {
+        Self::from_list(vec![msg.to_string()])
+    }
+
+    pub fn from_list(list: Vec<String>) -> Self {
+        Self {
+            texts: list.iter().map(|m| m.chars().collect()).collect(),
+            text: 0,
+            chars: Vec::new(),
+            size: (0, 0),
+            cell: Cell {
+                ch: ' ',
+                fg: None,
+                bg: None,
+                bold: false,
+            },
+            delay: Duration::ZERO,
+            hold: None,
+            scatter: false,
+            since: Duration::ZERO,
+            held: Duration::ZERO,
+            cleared: Vec::new(),
+            rng: SmallRng::seed_from_u64(0x6d657373616765),
+        }
+    }
+
+    pub fn set_timing(&mut self, delay: Duration, hold: Option<Duration>) {
+        self.delay = delay;
+        self.hold = hold;
+    }
+
+    pub fn set_scatter(&mut self, scatter: bool) {
+        self.scatter = scatter;
+    }
+
+    fn fully_revealed(&self) -> bool {
+        !self.chars.is_empty() && self.chars.iter().all(|mc| mc.draw || mc.line == u16::MAX)
+    }
+
+    // Hides the letters again and starts a new cycle with the next text.
+    fn restart(&mut self) {
+        for mc in self.chars.iter().filter(|mc| mc.draw) {
+            self.cleared.push((mc.col, mc.line));
+        }
+        self.since = Duration::ZERO;
+        self.held = Duration::ZERO;
+        if !self.texts.is_empty() {
+            self.text = (self.text + 1) % self.texts.len();
+        }
+        self.layout(self.size.0, self.size.1);
+    }
+
+    // Wraps the text in the middle half of the screen's width, and centres
+    // the block, or with `scatter` puts it anywhere it fits.
+    fn layout(&mut self, cols: u16, lines: u16) {
+        self.size = (cols, lines);
+        let text = self
+            .texts
+            .get(self.text)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        self.chars = text
+            .iter()
+            .map(|&val| MsgChr {
+                line: u16::MAX,
+                col: u16::MAX,
+                val,
+                draw: false,
+            })
+            .collect();
+        if self.chars.is_empty() {
+            return;
+        }
+
+        let first_col = cols / 4;
+        let last_col = (3 * cols) / 4;
+        let chars_per_col = last_col.saturating_sub(first_col) + 1;
+        if !self.scatter {
+            let msg_lines = (self.chars.len() as u16 / chars_per_col).saturating_add(1);
+            let first_line = (lines / 2).saturating_sub(msg_lines / 2);
+            self.place(first_col, chars_per_col, first_line, lines);
+            return;
+        }
+
+        let len = self.chars.len().min(u16::MAX as usize) as u16;
+        let width = len.min(chars_per_col);
+        let msg_lines = len.div_ceil(width);
+        let first_col = self.rng.random_range(0..=cols.saturating_sub(width));
+        let first_line = self.rng.random_range(0..=lines.saturating_sub(msg_lines));
+        self.place(first_col, width, first_line, lines);
+    }
+
+    // Lays the letters out in rows of `width` from the given corner, with a
+    // short last row centred under the others.
+    fn place(&mut self, first_col: u16, width: u16, first_line: u16, lines: u16) {
+        let last_col = first_col + width - 1;
+        let mut remaining = self.chars.len() as u16;
+        let mut line = first_line;
+        let mut col = first_col;
+        if remaining < width {
+            col += (width - remaining) / 2;
+        }
+
+        for mc in &mut self.chars {
+            if line < lines {
+                mc.line = line;
+                mc.col = col;
+            } else {
+                mc.line = u16::MAX;
+                mc.col = u16::MAX;
+            }
+
+            if col == last_col {
+                line = line.saturating_add(1);
+                col = first_col;
+                if remaining < width {
+                    col += (width - remaining) / 2;
+                }
+            } else {
+                col = col.saturating_add(1);
+            }
+            remaining = remaining.saturating_sub(1);
+        }
+    }
+}
+
+impl Effect for Message {
+    fn update(&mut self, cloud: &CloudState, dt: Duration) {
+        self.cleared.clear();
+        if self.size != (cloud.cols, cloud.lines) {
+            self.layout(cloud.cols, cloud.lines);
+        }
+        self.since += dt;
+        self.cell = Cell {
+            ch: ' ',
+            fg: match cloud.color_mode {
+                ColorMode::Mono => None,
+                _ => cloud.palette.colors.last().copied(),
+            },
+            bg: cloud.palette.bg,
+            bold: cloud.bold_mode != BoldMode::Off,
+        };
+        if self.since < self.delay {
+            return;
+        }
+
+        for d in cloud.droplets.iter().filter(|d| d.is_alive) {
+            for mc in self
+                .chars
+                .iter_mut()
+                .filter(|mc| mc.col == d.bound_col && !mc.draw)
+            {
+                let below_tail = d.tail_put_line.is_none_or(|t| mc.line > t);
+                if mc.line <= d.head_put_line && below_tail {
+                    mc.draw = true;
+                }
+            }
+        }
+
+        if let Some(hold) = self.hold.filter(|_| self.fully_revealed()) {
+            self.held += dt;
+            if self.held >= hold {
+                self.restart();
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        for &(x, y) in &self.cleared {
+            frame.set(x, y, Cell::blank_with_bg(self.cell.bg));
+        }
+        for mc in self.chars.iter().filter(|mc| mc.draw) {
+            frame.set(
+                mc.col,
+                mc.line,
+                Cell {
+                    ch: mc.val,
+                    ..self.cell
+                },
+            );
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn Effect> {
+        Box::new(self.clone())
+    }
+}
+
+// Columns given over to a phrase, one per this many screen columns.
+const COLS_PER_PHRASE: u16 = 20;
+// How long the phrases stay put before moving to new columns.
+const PHRASE_PERIOD: Duration = Duration::from_secs(12);
+
+#[derive(Clone, Debug)]
+struct Placed {
+    col: u16,
+    line: u16,
+    text: usize,
+    shown: f32,
+}
+
+// `--phrases`: words from a list written down a few random columns in the
+// head color, typed in at the rain's speed and moved every few seconds.
+#[derive(Clone)]
+pub struct Phrases {
+    list: Vec<Vec<char>>,
+    placed: Vec<Placed>,
+    prev: Vec<(u16, u16)>,
+    size: (u16, u16),
+    since: Duration,
+    rng: SmallRng,
+    cell: Cell,
+}
+
+impl Phrases {
+    pub fn new(list: Vec<String>) -> Self {
+        Self {
+            list: list
+                .iter()
+                .map(|p| p.chars().filter(|c| !c.is_control()).collect())
+                .collect(),
+            placed: Vec::new(),
+            prev: Vec::new(),
+            size: (0, 0),
+            since: Duration::ZERO,
+            rng: SmallRng::seed_from_u64(0x7068726173),
+            cell: Cell::blank_with_bg(None),
+        }
+    }
+
+    fn place(&mut self, cols: u16, lines: u16, full_width: bool) {
+        let old: Vec<(u16, u16)> = self.cells().collect();
+        self.prev.extend(old);
+        self.placed.clear();
+        self.size = (cols, lines);
+        self.since = Duration::ZERO;
+        if self.list.is_empty() || cols == 0 || lines == 0 {
+            return;
+        }
+
+        let step = if full_width { 2 } else { 1 };
+        let slots = cols.div_ceil(step);
+        let n = (cols / COLS_PER_PHRASE).max(1).min(slots);
+        while self.placed.len() < n as usize {
+            let col = self.rng.random_range(0..slots) * step;
+            if self.placed.iter().any(|p| p.col == col) {
+                continue;
+            }
+            let text = self.rng.random_range(0..self.list.len());
+            let room = lines.saturating_sub(self.list[text].len() as u16);
+            let line = self.rng.random_range(0..=room);
+            self.placed.push(Placed {
+                col,
+                line,
+                text,
+                shown: 0.0,
+            });
+        }
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        self.placed.iter().flat_map(|p| {
+            let n = (p.shown as usize).min(self.list[p.text].len());
+            (0..n as u16).map(move |i| (p.col, p.line.saturating_add(i)))
+        })
+    }
+}
+
+impl Effect for Phrases {
+    fn update(&mut self, cloud: &CloudState, dt: Duration) {
+        self.prev.clear();
+        self.since += dt;
+        if self.size != (cloud.cols, cloud.lines) || self.since >= PHRASE_PERIOD {
+            self.place(cloud.cols, cloud.lines, cloud.full_width);
+        }
+        for p in &mut self.placed {
+            p.shown += cloud.chars_per_sec * dt.as_secs_f32();
+        }
+        self.cell = Cell {
+            ch: ' ',
+            fg: match cloud.color_mode {
+                ColorMode::Mono => None,
+                _ => cloud.palette.colors.last().copied(),
+            },
+            bg: cloud.palette.bg,
+            bold: cloud.bold_mode != BoldMode::Off,
+        };
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        for &(x, y) in &self.prev {
+            frame.set(x, y, Cell::blank_with_bg(self.cell.bg));
+        }
+        for p in &self.placed {
+            let n = (p.shown as usize).min(self.list[p.text].len());
+            for (i, &ch) in self.list[p.text][..n].iter().enumerate() {
+                frame.set(
+                    p.col,
+                    p.line.saturating_add(i as u16),
+                    Cell { ch, ..self.cell },
+                );
+            }
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn Effect> {
+        Box::new(self.clone())
+    }
+}
+
+// `--marquee`: text scrolling right to left along one row, over the rain,
+// in the head color. Enters from the right edge again once it has left.
+#[derive(Clone)]
+pub struct Marquee {
+    text: Vec<char>,
+    row: Option<u16>,
+    speed: f32,
+    pos: f32,
+    cols: u16,
+    line: u16,
+    prev: Vec<(u16, u16)>,
+    cell: Cell,
+}
+
+impl Marquee {
+    // `row` counts from the top, or is the middle row when `None`; `speed`
+    // is in columns per second.
+    pub fn new(text: &str, row: Option<u16>, speed: f32) -> Self {
+        Self {
+            text: text.chars().filter(|c| !c.is_control()).collect(),
+            row,
+            speed,
+            pos: f32::MAX,
+            cols: 0,
+            line: 0,
+            prev: Vec::new(),
+            cell: Cell::blank_with_bg(None),
+        }
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (u16, char)> + '_ {
+        let start = self.pos.floor() as i32;
+        self.text.iter().enumerate().filter_map(move |(i, &ch)| {
+            let x = start.saturating_add(i as i32);
+            (0..self.cols as i32).contains(&x).then_some((x as u16, ch))
+        })
+    }
+}
+
+impl Effect for Marquee {
+    fn update(&mut self, cloud: &CloudState, dt: Duration) {
+        let line = self.line;
+        let old: Vec<(u16, u16)> = self.cells().map(|(x, _)| (x, line)).collect();
+        self.prev = old;
+        self.cols = cloud.cols;
+        self.line = self
+            .row
+            .unwrap_or(cloud.lines / 2)
+            .min(cloud.lines.saturating_sub(1));
+        self.pos -= self.speed * dt.as_secs_f32();
+        if self.pos > cloud.cols as f32 || self.pos < -(self.text.len() as f32) {
+            self.pos = cloud.cols as f32;
+        }
+        self.cell = Cell {
+            ch: ' ',
+            fg: match cloud.color_mode {
+                ColorMode::Mono => None,
+                _ => cloud.palette.colors.last().copied(),
+            },
+            bg: cloud.palette.bg,
+            bold: cloud.bold_mode != BoldMode::Off,
+        };
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        for &(x, y) in &self.prev {
+            frame.set(x, y, Cell::blank_with_bg(self.cell.bg));
+        }
+        for (x, ch) in self.cells() {
+            frame.set(x, self.line, Cell { ch, ..self.cell });
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn Effect> {
+        Box::new(self.clone())
+    }
+}
+
+// How long a backdrop letter stays lit after a head passes over it.
+const BACKDROP_GLOW: Duration = Duration::from_millis(600);
+
+#[derive(Clone, Debug)]
+struct ArtCell {
+    x: u16,
+    y: u16,
+    ch: char,
+    heat: f32,
+}
+
+// `--bg-art`: ASCII (or ANSI, with the colors dropped) art centred behind
+// the rain in the dimmest palette color. Its letters fill blank cells, and
+// light up in the head color for a moment when a droplet head crosses them.
+#[derive(Clone)]
+pub struct Backdrop {
+    art: Vec<ArtCell>,
+    art_size: (u16, u16),
+    origin: (i32, i32),
+    size: (u16, u16),
+    colors: Vec<Option<Color>>,
+    bg: Option<Color>,
+    // Letters that cooled down this frame, redrawn dim once over whatever
+    // droplet is there.
+    cooled: Vec<usize>,
+}
+
+impl Backdrop {
+    pub fn new(text: &str) -> Self {
+        let mut art = Vec::new();
+        let mut width = 0;
+        let mut height = 0;
+        for (y, line) in text.lines().enumerate() {
+            let mut x: u16 = 0;
+            let mut chars = line.chars().peekable();
+            while let Some(ch) = chars.next() {
+                match ch {
+                    // Escape sequences: CSI up to its final byte, others
+                    // up to the next letter.
+                    '\x1b' => {
+                        if chars.next_if_eq(&'[').is_some() {
+                            while chars.next().is_some_and(|c| !('@'..='~').contains(&c)) {}
+                        } else {
+                            while chars.next().is_some_and(|c| !c.is_ascii_alphabetic()) {}
+                        }
+                    }
+                    '\t' => x = (x / 8 + 1) * 8,
+                    c if c.is_control() => {}
+                    ' ' => x = x.saturating_add(1),
+                    c => {
+                        art.push(ArtCell {
+                            x,
+                            y: y as u16,
+                            ch: c,
+                            heat: 0.0,
+                        });
+                        x = x.saturating_add(1);
+                    }
+                }
+                width = width.max(x);
+            }
+            height = y as u16 + 1;
+        }
+        Self {
+            art,
+            art_size: (width, height),
+            origin: (0, 0),
+            size: (0, 0),
+            colors: Vec::new(),
+            bg: None,
+            cooled: Vec::new(),
+        }
+    }
+
+    fn pos(&self, a: &ArtCell) -> Option<(u16, u16)> {
+        let x = self.origin.0 + a.x as i32;
+        let y = self.origin.1 + a.y as i32;
+        let inside = (0..self.size.0 as i32).contains(&x) && (0..self.size.1 as i32).contains(&y);
+        inside.then_some((x as u16, y as u16))
+    }
+
+    fn color(&self, heat: f32) -> Option<Color> {
+        let n = self.colors.len();
+        if n == 0 {
+            return None;
+        }
+        self.colors[((heat * (n - 1) as f32).round() as usize).min(n - 1)]
+    }
+}
+
+impl Effect for Backdrop {
+    fn update(&mut self, cloud: &CloudState, dt: Duration) {
+        if self.size != (cloud.cols, cloud.lines) {
+            self.size = (cloud.cols, cloud.lines);
+            self.origin = (
+                (cloud.cols as i32 - self.art_size.0 as i32) / 2,
+                (cloud.lines as i32 - self.art_size.1 as i32) / 2,
+            );
+        }
+        self.bg = cloud.palette.bg;
+        self.colors = match cloud.color_mode {
+            ColorMode::Mono => vec![None],
+            _ => cloud.palette.colors.iter().map(|&c| Some(c)).collect(),
+        };
+
+        self.cooled.clear();
+        let cool = dt.as_secs_f32() / BACKDROP_GLOW.as_secs_f32();
+        for (i, a) in self.art.iter_mut().enumerate() {
+            if a.heat > 0.0 {
+                a.heat = (a.heat - cool).max(0.0);
+                if a.heat == 0.0 {
+                    self.cooled.push(i);
+                }
+            }
+        }
+
+        // A head can move several rows in a frame; light every row it crossed.
+        let rows = (cloud.chars_per_sec * dt.as_secs_f32()).ceil() as i32 + 1;
+        for d in cloud
+            .droplets
+            .iter()
+            .filter(|d| d.is_alive && d.is_head_crawling)
+        {
+            let x = d.bound_col as i32 - self.origin.0;
+            let head = d.head_put_line as i32 - self.origin.1;
+            for a in self.art.iter_mut().filter(|a| a.x as i32 == x) {
+                let y = a.y as i32;
+                if y <= head && y > head - rows {
+                    a.heat = 1.0;
+                }
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let dim = Cell {
+            ch: ' ',
+            fg: self.color(0.0),
+            bg: self.bg,
+            bold: false,
+        };
+        for (i, a) in self.art.iter().enumerate() {
+            let Some((x, y)) = self.pos(a) else {
+                continue;
+            };
+            if a.heat > 0.0 {
+                frame.set(
+                    x,
+                    y,
+                    Cell {
+                        ch: a.ch,
+                        fg: self.color(a.heat),
+                        ..dim
+                    },
+                );
+            } else if self.cooled.contains(&i) || frame.get(x, y).is_some_and(|c| c.ch == ' ') {
+                frame.set(x, y, Cell { ch: a.ch, ..dim });
+            }
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn Effect> {
+        Box::new(self.clone())
+    }
+}
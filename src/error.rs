@@ -0,0 +1,84 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum CosmostrixError {
+    UnknownCharset(String),
+    InvalidChars(String),
+    // A command-line or config file value that could not be used; `option`
+    // is the long flag name without the leading dashes.
+    InvalidOption {
+        option: &'static str,
+        reason: String,
+    },
+    Config {
+        path: PathBuf,
+        line: usize,
+        reason: String,
+    },
+    File {
+        path: PathBuf,
+        source: io::Error,
+    },
+    NoConfigDir,
+    Io(io::Error),
+    // Everything wrong with the options at once, one error per line.
+    Several(Vec<CosmostrixError>),
+}
+
+pub type Result<T> = std::result::Result<T, CosmostrixError>;
+
+impl CosmostrixError {
+    pub fn invalid(option: &'static str, reason: impl Into<String>) -> Self {
+        CosmostrixError::InvalidOption {
+            option,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for CosmostrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CosmostrixError::UnknownCharset(s) => write!(f, "unsupported charset: {}", s),
+            CosmostrixError::InvalidChars(reason) => write!(f, "--chars: {}", reason),
+            CosmostrixError::InvalidOption { option, reason } => {
+                write!(f, "--{}: {}", option, reason)
+            }
+            CosmostrixError::Config { path, line, reason } => {
+                write!(f, "{}:{}: {}", path.display(), line, reason)
+            }
+            CosmostrixError::File { path, source } => write!(f, "{}: {}", path.display(), source),
+            CosmostrixError::NoConfigDir => write!(f, "cannot locate the config directory"),
+            CosmostrixError::Io(e) => write!(f, "{}", e),
+            CosmostrixError::Several(errors) => {
+                for (i, e) in errors.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for CosmostrixError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CosmostrixError::File { source, .. } => Some(source),
+            CosmostrixError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CosmostrixError {
+    fn from(e: io::Error) -> Self {
+        CosmostrixError::Io(e)
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright (c) 2025 rezk_nightky
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::cell::Cell;
+use crate::clock::Instant;
+use crate::frame::Frame;
+use crate::palette::Palette;
+use crate::scene::Scene;
+
+// Physics is in cells and seconds, scaled by the screen height so a burst
+// fills a similar share of any terminal.
+const GRAVITY: f32 = 0.5;
+const LAUNCHES_PER_SEC: f32 = 0.9;
+const SPARKS: std::ops::Range<usize> = 30..70;
+const SPARK_LIFE: std::ops::Range<f32> = 1.0..2.2;
+
+#[derive(Clone, Copy)]
+struct Particle {
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    age: f32,
+    life: f32,
+}
+
+// Rockets rise from the bottom and burst near the top of their climb into
+// sparks that fall under gravity and fade down the palette ramp.
+pub struct Fireworks {
+    rockets: Vec<Particle>,
+    sparks: Vec<Particle>,
+    cols: u16,
+    lines: u16,
+    rng: SmallRng,
+    last: Option<Instant>,
+    launch_debt: f32,
+}
+
+impl Fireworks {
+    pub fn new() -> Self {
+        Self {
+            rockets: Vec::new(),
+            sparks: Vec::new(),
+            cols: 0,
+            lines: 0,
+            rng: SmallRng::seed_from_u64(0x5eed_f12e),
+            last: None,
+            launch_debt: 0.0,
+        }
+    }
+
+    fn scale(&self) -> f32 {
+        self.lines.max(1) as f32
+    }
+
+    fn launch(&mut self) {
+        let h = self.scale();
+        // Climb to between 15% and 50% from the top.
+        let apex = self.rng.random_range(0.5..0.85) * h;
+        let x = self.rng.random_range(0.1..0.9) * self.cols as f32;
+        self.rockets.push(Particle {
+            x,
+            y: h - 1.0,
+            vx: self.rng.random_range(-0.05..0.05) * h,
+            vy: -(2.0 * GRAVITY * h * apex).sqrt(),
+            age: 0.0,
+            life: f32::INFINITY,
+        });
+    }
+
+    fn burst(&mut self, at: Particle) {
+        let h = self.scale();
+        let speed = self.rng.random_range(0.25..0.45) * h;
+        for _ in 0..self.rng.random_range(SPARKS) {
+            let angle = self.rng.random_range(0.0..std::f32::consts::TAU);
+            let v = speed * self.rng.random_range(0.3..1.0);
+            self.sparks.push(Particle {
+                x: at.x,
+                y: at.y,
+                // Cells are about twice as tall as wide.
+                vx: angle.cos() * v * 2.0,
+                vy: angle.sin() * v,
+                age: 0.0,
+                life: self.rng.random_range(SPARK_LIFE),
+            });
+        }
+    }
+
+    fn step(&mut self, dt: f32) {
+        let g = GRAVITY * self.scale();
+
+        self.launch_debt += LAUNCHES_PER_SEC * (self.cols as f32 / 80.0).max(0.5) * dt;
+        while self.launch_debt >= 1.0 {
+            self.launch_debt -= 1.0;
+            self.launch();
+        }
+
+        let mut bursts = Vec::new();
+        self.rockets.retain_mut(|r| {
+            r.x += r.vx * dt;
+            r.y += r.vy * dt;
+            r.vy += g * dt;
+            if r.vy >= 0.0 {
+                bursts.push(*r);
+                return false;
+            }
+            true
+        });
+        for r in bursts {
+            self.burst(r);
+        }
+
+        let (w, h) = (self.cols as f32, self.lines as f32);
+        self.sparks.retain_mut(|s| {
+            s.x += s.vx * dt;
+            s.y += s.vy * dt;
+            s.vx *= 1.0 - 0.8 * dt;
+            s.vy += g * 0.3 * dt;
+            s.age += dt;
+            s.age < s.life && s.x >= 0.0 && s.x < w && s.y < h
+        });
+    }
+}
+
+impl Default for Fireworks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene for Fireworks {
+    fn resize(&mut self, cols: u16, lines: u16) {
+        self.cols = cols;
+        self.lines = lines;
+        self.rockets.clear();
+        self.sparks.clear();
+    }
+
+    fn draw(&mut self, frame: &mut Frame, palette: &Palette, now: Instant) {
+        let dt = self
+            .last
+            .map(|t| now.saturating_duration_since(t).as_secs_f32().min(0.1))
+            .unwrap_or(0.0);
+        self.last = Some(now);
+        self.step(dt);
+
+        frame.clear_with_bg(palette.bg);
+        let colors = &palette.colors;
+        let color = |fade: f32| match colors.len() {
+            0 => None,
+            n => Some(colors[(((1.0 - fade) * (n - 1) as f32).round() as usize).min(n - 1)]),
+        };
+
+        for s in &self.sparks {
+            if s.y < 0.0 {
+                continue;
+            }
+            let fade = (s.age / s.life).clamp(0.0, 1.0);
+            let ch = match fade {
+                f if f < 0.3 => '*',
+                f if f < 0.7 => '+',
+                _ => '.',
+            };
+            let cell = Cell {
+                ch,
+                fg: color(fade),
+                bg: palette.bg,
+                bold: fade < 0.3,
+            };
+            frame.set(s.x as u16, s.y as u16, cell);
+        }
+        for r in &self.rockets {
+            if r.y < 0.0 {
+                continue;
+            }
+            let cell = Cell {
+                ch: '|',
+                fg: color(0.4),
+                bg: palette.bg,
+                bold: false,
+            };
+            frame.set(r.x as u16, r.y as u16, cell);
+        }
+    }
+}
@@ -1,16 +1,83 @@
 // Copyright (c) 2025 rezk_nightky
 
-use crate::cell::Cell;
+use crate::cell::{Cell, Color};
 
-#[derive(Clone, Debug)]
+pub const UPPER_HALF: char = '\u{2580}';
+pub const LOWER_HALF: char = '\u{2584}';
+
+const MAX_POOLED: usize = 4;
+
+// How `Frame::composite` lays a source cell over the one beneath it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Blend {
+    // Every source cell replaces the cell beneath, blanks included.
+    Overwrite,
+    // Blank source cells let the cell beneath show through.
+    SkipBlank,
+    // As `SkipBlank`, but glyphs showing through are redrawn in the given
+    // color without bold, so the overlay stands out from them.
+    DimUnder(Option<Color>),
+}
+
+#[derive(Debug)]
 pub struct Frame {
     pub width: u16,
     pub height: u16,
     pub cells: Vec<Cell>,
 }
 
+impl Clone for Frame {
+    fn clone(&self) -> Self {
+        Self {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.clone(),
+        }
+    }
+
+    // Reuses the cell buffer, so per-frame copies kept for diffing don't allocate.
+    fn clone_from(&mut self, src: &Self) {
+        self.width = src.width;
+        self.height = src.height;
+        self.cells.clone_from(&src.cells);
+    }
+}
+
+// Frames released on resize or redraw, kept so their cell buffers are reused.
+#[derive(Default)]
+pub struct FramePool {
+    free: Vec<Frame>,
+}
+
+impl FramePool {
+    pub fn copy_of(&mut self, src: &Frame) -> Frame {
+        match self.free.pop() {
+            Some(mut f) => {
+                f.clone_from(src);
+                f
+            }
+            None => src.clone(),
+        }
+    }
+
+    pub fn give(&mut self, frame: Frame) {
+        if self.free.len() < MAX_POOLED {
+            self.free.push(frame);
+        }
+    }
+}
+
+// Indexes into `frame.cells` of what `Frame::diff` yields, in order.
+pub fn changed_cells(last: Option<&Frame>, frame: &Frame) -> Vec<usize> {
+    let w = frame.width as usize;
+    frame
+        .diff(last)
+        .map(|(x, y, _)| y as usize * w + x as usize)
+        .collect()
+}
+
 impl Frame {
-    pub fn new(width: u16, height: u16, bg: Option<crossterm::style::Color>) -> Self {
+    pub fn new(width: u16, height: u16, bg: Option<Color>) -> Self {
         let len = width as usize * height as usize;
         Self {
             width,
@@ -19,11 +86,33 @@ impl Frame {
         }
     }
 
-    pub fn clear(&mut self) {
-        self.clear_with_bg(None);
+    // Changes the size in place, keeping the cell buffer; every cell is blanked.
+    pub fn resize(&mut self, width: u16, height: u16, bg: Option<Color>) {
+        self.width = width;
+        self.height = height;
+        self.cells.clear();
+        self.cells
+            .resize(width as usize * height as usize, Cell::blank_with_bg(bg));
+    }
+
+    // The cells that differ from `previous`, row by row, as (x, y, cell);
+    // every cell when `previous` is missing or a different size.
+    // Continuation cells are left out, since drawing a wide glyph covers them.
+    pub fn diff<'a>(
+        &'a self,
+        previous: Option<&'a Frame>,
+    ) -> impl Iterator<Item = (u16, u16, Cell)> + 'a {
+        let previous = previous.filter(|p| p.width == self.width && p.height == self.height);
+        let w = self.width.max(1) as usize;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !c.is_continuation())
+            .filter(move |&(i, c)| previous.is_none_or(|p| p.cells[i] != *c))
+            .map(move |(i, &c)| ((i % w) as u16, (i / w) as u16, c))
     }
 
-    pub fn clear_with_bg(&mut self, bg: Option<crossterm::style::Color>) {
+    pub fn clear_with_bg(&mut self, bg: Option<Color>) {
         for cell in &mut self.cells {
             *cell = Cell::blank_with_bg(bg);
         }
@@ -40,9 +129,143 @@ impl Frame {
         self.index(x, y).map(|i| &self.cells[i])
     }
 
+    fn pixel(&self, x: u16, y: u16) -> (bool, Option<Color>) {
+        match self.get(x, y) {
+            Some(c) if c.ch != ' ' && !c.is_continuation() => (true, c.fg.or(Some(Color::White))),
+            Some(c) => (false, c.bg),
+            None => (false, None),
+        }
+    }
+
+    // Packs two rows of this frame into each row of `out` using half blocks.
+    pub fn pack_half_blocks(&self, out: &mut Frame) {
+        for y in 0..out.height {
+            for x in 0..out.width {
+                let (top_lit, top) = self.pixel(x, y * 2);
+                let (bottom_lit, bottom) = self.pixel(x, y * 2 + 1);
+                let cell = match (top_lit, bottom_lit) {
+                    (false, false) => Cell::blank_with_bg(top),
+                    (false, true) => Cell {
+                        ch: LOWER_HALF,
+                        fg: bottom,
+                        bg: top,
+                        bold: false,
+                    },
+                    _ => Cell {
+                        ch: UPPER_HALF,
+                        fg: top,
+                        bg: bottom,
+                        bold: false,
+                    },
+                };
+                if let Some(i) = out.index(x, y) {
+                    out.cells[i] = cell;
+                }
+            }
+        }
+    }
+
+    // Puts `from` back over a scattered share of the cells, all of them at
+    // t = 0 and none at t = 1, so stepping t dissolves one picture into this one.
+    pub fn dissolve_from(&mut self, from: &Frame, t: f32) {
+        if from.width != self.width || from.height != self.height {
+            return;
+        }
+        let cut = (t.clamp(0.0, 1.0) * 65536.0) as u32;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let i = y as usize * self.width as usize + x as usize;
+                let old = from.cells[i];
+                let scatter = (i as u32).wrapping_mul(2_654_435_761) >> 16;
+                if !old.is_continuation() && scatter >= cut {
+                    self.set(x, y, old);
+                }
+            }
+        }
+    }
+
+    // Copies `src` with its top-left corner at (x, y), clipped to this frame.
+    pub fn blit(&mut self, src: &Frame, x: u16, y: u16) {
+        self.composite(src, x, y, Blend::Overwrite);
+    }
+
+    // Lays `src` over this frame with its top-left corner at (x, y), clipped
+    // to this frame, combining cells by `blend`.
+    pub fn composite(&mut self, src: &Frame, x: u16, y: u16, blend: Blend) {
+        let dim = match blend {
+            Blend::Overwrite => return self.copy_from(src, x, y),
+            Blend::SkipBlank => None,
+            Blend::DimUnder(c) => Some(c),
+        };
+        for sy in 0..src.height {
+            let Some(dy) = y.checked_add(sy).filter(|&dy| dy < self.height) else {
+                break;
+            };
+            for sx in 0..src.width {
+                let Some(dx) = x.checked_add(sx).filter(|&dx| dx < self.width) else {
+                    break;
+                };
+                let cell = src.cells[sy as usize * src.width as usize + sx as usize];
+                if cell.is_continuation() {
+                    continue;
+                }
+                if cell.ch != ' ' {
+                    self.set(dx, dy, cell);
+                    continue;
+                }
+                if let Some(fg) = dim {
+                    let i = dy as usize * self.width as usize + dx as usize;
+                    let under = &mut self.cells[i];
+                    if under.ch != ' ' {
+                        under.fg = fg;
+                        under.bold = false;
+                    }
+                }
+            }
+        }
+    }
+
+    fn copy_from(&mut self, src: &Frame, x: u16, y: u16) {
+        for sy in 0..src.height {
+            let Some(dy) = y.checked_add(sy).filter(|&dy| dy < self.height) else {
+                break;
+            };
+            for sx in 0..src.width {
+                let Some(dx) = x.checked_add(sx).filter(|&dx| dx < self.width) else {
+                    break;
+                };
+                let i = dy as usize * self.width as usize + dx as usize;
+                self.cells[i] = src.cells[sy as usize * src.width as usize + sx as usize];
+            }
+        }
+    }
+
     pub fn set(&mut self, x: u16, y: u16, cell: Cell) {
-        if let Some(i) = self.index(x, y) {
-            self.cells[i] = cell;
+        let Some(i) = self.index(x, y) else {
+            return;
+        };
+
+        // Overwriting either half of a wide glyph blanks the other half.
+        if self.cells[i].is_continuation() && x > 0 {
+            let head = &mut self.cells[i - 1];
+            *head = Cell::blank_with_bg(head.bg);
+        }
+        if self.cells[i].width() == 2 && x + 1 < self.width {
+            let tail = &mut self.cells[i + 1];
+            *tail = Cell::blank_with_bg(tail.bg);
+        }
+
+        if cell.width() == 2 {
+            if x + 1 >= self.width {
+                self.cells[i] = Cell::blank_with_bg(cell.bg);
+                return;
+            }
+            if self.cells[i + 1].width() == 2 && x + 2 < self.width {
+                let tail = &mut self.cells[i + 2];
+                *tail = Cell::blank_with_bg(tail.bg);
+            }
+            self.cells[i + 1] = Cell::continuation(cell.bg);
         }
+        self.cells[i] = cell;
     }
 }
@@ -0,0 +1,95 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::time::Duration;
+
+use crate::cell::Color;
+use crate::clock::Instant;
+use crate::frame::Frame;
+use crate::palette::rgb_to_ansi256;
+use crate::runtime::ColorMode;
+
+// How long a cell stays warm after a head passes through it.
+const HEAT_SPAN: Duration = Duration::from_secs(4);
+
+// Fraction of full brightness the hottest cells reach, so the glyphs on top
+// stay readable.
+const HEAT_PEAK: f32 = 0.45;
+
+// The `H` overlay: each cell's background glows by how recently a droplet
+// head touched it, from deep blue through red to yellow. Cells are kept
+// column by column, like the glitch map.
+#[derive(Clone, Debug, Default)]
+pub struct HeatMap {
+    lines: u16,
+    touched: Vec<Option<Instant>>,
+}
+
+impl HeatMap {
+    pub fn resize(&mut self, cols: u16, lines: u16) {
+        self.lines = lines;
+        self.touched.clear();
+        self.touched.resize(cols as usize * lines as usize, None);
+    }
+
+    // Marks the lines a head crossed in one step.
+    pub fn touch(&mut self, col: u16, from: u16, to: u16, now: Instant) {
+        let start = col as usize * self.lines as usize;
+        for line in from..=to.min(self.lines.saturating_sub(1)) {
+            if let Some(t) = self.touched.get_mut(start + line as usize) {
+                *t = Some(now);
+            }
+        }
+    }
+
+    // Tints the background of every warm cell, and puts `bg` back on the
+    // ones that just cooled down.
+    pub fn draw(&mut self, frame: &mut Frame, now: Instant, bg: Option<Color>, mode: ColorMode) {
+        let lines = self.lines.max(1) as usize;
+        for (i, slot) in self.touched.iter_mut().enumerate() {
+            let Some(t) = *slot else {
+                continue;
+            };
+            let heat =
+                1.0 - now.saturating_duration_since(t).as_secs_f32() / HEAT_SPAN.as_secs_f32();
+            let color = if heat > 0.0 {
+                heat_color(heat, mode)
+            } else {
+                None
+            };
+            if color.is_none() {
+                *slot = None;
+            }
+            let Some(idx) = frame.index((i / lines) as u16, (i % lines) as u16) else {
+                continue;
+            };
+            let cell = &mut frame.cells[idx];
+            cell.bg = color.or(bg);
+        }
+    }
+}
+
+// Blue when barely warm, red, then yellow at the hottest. 16 and 8-color
+// modes pick the nearest named color; mono has no overlay.
+fn heat_color(heat: f32, mode: ColorMode) -> Option<Color> {
+    let h = heat.clamp(0.0, 1.0);
+    let (r, g, b) = if h < 0.5 {
+        let t = h * 2.0;
+        (t, 0.0, 1.0 - t)
+    } else {
+        (1.0, (h - 0.5) * 2.0, 0.0)
+    };
+    let level = |v: f32| (v * h * HEAT_PEAK * 255.0).round() as u8;
+    let (r, g, b) = (level(r), level(g), level(b));
+    match mode {
+        ColorMode::TrueColor => Some(Color::Rgb { r, g, b }),
+        ColorMode::Color256 => Some(Color::AnsiValue(rgb_to_ansi256(r, g, b))),
+        ColorMode::Color16 | ColorMode::Color8 => Some(if h > 0.66 {
+            Color::DarkYellow
+        } else if h > 0.33 {
+            Color::DarkRed
+        } else {
+            Color::DarkBlue
+        }),
+        ColorMode::Mono => None,
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::env;
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+
+use crate::terminal::Terminal;
+
+// Set on the rain started by `--idle`, so a config file with `idle` in it
+// does not make the child wait for idle again.
+pub const CHILD_ENV: &str = "COSMOSTRIX_IDLE_CHILD";
+
+const POLL: Duration = Duration::from_millis(500);
+
+// How long since the user last touched the keyboard or mouse anywhere we
+// can see: the X11 server's idle time when `xprintidle` is installed,
+// otherwise the newest access time of the user's terminals (what `w` shows
+// as IDLE). Wayland has no idle query outside the compositor, so sessions
+// without XWayland fall back to the terminals.
+fn system_idle() -> Option<Duration> {
+    x11_idle().or_else(tty_idle)
+}
+
+fn x11_idle() -> Option<Duration> {
+    env::var_os("DISPLAY")?;
+    let out = Command::new("xprintidle").output().ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let ms: u64 = String::from_utf8_lossy(&out.stdout).trim().parse().ok()?;
+    Some(Duration::from_millis(ms))
+}
+
+#[cfg(unix)]
+fn tty_idle() -> Option<Duration> {
+    use std::os::unix::fs::MetadataExt;
+
+    let uid = unsafe { libc::getuid() };
+    let newest = std::fs::read_dir("/dev/pts")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .chain(std::fs::read_link("/proc/self/fd/0"))
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .filter(|m| m.uid() == uid)
+        .filter_map(|m| m.accessed().ok())
+        .max()?;
+    newest.elapsed().ok()
+}
+
+#[cfg(not(unix))]
+fn tty_idle() -> Option<Duration> {
+    None
+}
+
+// Our own arguments minus `--idle`, for the rain to run with.
+fn child_args() -> Vec<OsString> {
+    let mut out = Vec::new();
+    let mut iter = env::args_os().skip(1);
+    while let Some(arg) = iter.next() {
+        if arg == "--idle" {
+            iter.next();
+        } else if !arg.to_str().is_some_and(|s| s.starts_with("--idle=")) {
+            out.push(arg);
+        }
+    }
+    out
+}
+
+// `--idle`: waits until nothing has been typed for `after`, runs the rain
+// full screen until the next key, and waits again. Keys typed into this
+// terminal count as activity too; `q` or Ctrl-C stops waiting.
+pub fn run(after: Duration) -> io::Result<()> {
+    let exe = env::current_exe()?;
+    let args = child_args();
+    println!(
+        "waiting for {} min without input (q to quit)",
+        after.as_secs_f32() / 60.0
+    );
+    io::stdout().flush()?;
+
+    let mut last_input = Instant::now();
+    loop {
+        terminal::enable_raw_mode()?;
+        let quit = wait(after, &mut last_input);
+        terminal::disable_raw_mode()?;
+        if quit? {
+            return Ok(());
+        }
+
+        log::debug!("idle for {:?}, starting the rain", after);
+        let status = Command::new(&exe)
+            .args(&args)
+            .arg("--screensaver")
+            .env(CHILD_ENV, "1")
+            .status()?;
+        if !status.success() {
+            log::debug!("rain exited with {}", status);
+        }
+        last_input = Instant::now();
+    }
+}
+
+// Returns true when the user asked to stop, false once idle long enough.
+fn wait(after: Duration, last_input: &mut Instant) -> io::Result<bool> {
+    loop {
+        if Terminal::poll_event(POLL)? {
+            if let Event::Key(k) = Terminal::read_event()? {
+                if k.kind == KeyEventKind::Press {
+                    let ctrl_c =
+                        k.code == KeyCode::Char('c') && k.modifiers.contains(KeyModifiers::CONTROL);
+                    if ctrl_c || matches!(k.code, KeyCode::Char('q') | KeyCode::Esc) {
+                        return Ok(true);
+                    }
+                }
+            }
+            *last_input = Instant::now();
+            continue;
+        }
+        let idle = system_idle().map_or(last_input.elapsed(), |d| d.min(last_input.elapsed()));
+        if idle >= after {
+            return Ok(false);
+        }
+    }
+}
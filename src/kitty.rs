@@ -0,0 +1,62 @@
+// Copyright (c) 2025 rezk_nightky
+
+use crate::pixel::Canvas;
+
+const CHUNK: usize = 4096;
+const IMAGE_ID: u32 = 1;
+
+const B64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        out.push(B64[(n >> 18) as usize & 63] as char);
+        out.push(B64[(n >> 12) as usize & 63] as char);
+        out.push(if chunk.len() > 1 {
+            B64[(n >> 6) as usize & 63] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            B64[n as usize & 63] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
+
+// Builds the escape sequence that transmits `canvas` and places it over
+// `cols` x `rows` cells at the cursor, replacing the previous frame.
+pub fn encode(canvas: &Canvas, cols: u16, rows: u16) -> String {
+    let payload = base64(&canvas.rgb);
+    let mut out = String::with_capacity(payload.len() + payload.len() / CHUNK * 8 + 128);
+
+    let chunks: Vec<&str> = payload
+        .as_bytes()
+        .chunks(CHUNK)
+        .map(|c| std::str::from_utf8(c).unwrap_or(""))
+        .collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        if i == 0 {
+            out.push_str(&format!(
+                "\x1b_Ga=T,f=24,s={},v={},i={},p=1,c={},r={},C=1,q=2,m={};{}\x1b\\",
+                canvas.width, canvas.height, IMAGE_ID, cols, rows, more, chunk
+            ));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out
+}
+
+pub fn clear() -> String {
+    format!("\x1b_Ga=d,d=I,i={},q=2\x1b\\", IMAGE_ID)
+}
@@ -0,0 +1,24 @@
+// Copyright (c) 2025 rezk_nightky
+
+pub mod afterimage;
+pub mod cell;
+pub mod charset;
+pub mod clock;
+pub mod cloud;
+pub mod droplet;
+pub mod effect;
+pub mod error;
+pub mod fireworks;
+pub mod frame;
+pub mod heatmap;
+pub mod life;
+pub mod palette;
+mod pool;
+pub mod render;
+pub mod runtime;
+pub mod scene;
+pub mod snow;
+pub mod starfield;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
+pub mod waterfall;
@@ -0,0 +1,129 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::cell::Cell;
+use crate::clock::Instant;
+use crate::frame::Frame;
+use crate::palette::Palette;
+use crate::scene::Scene;
+
+const GENERATION: Duration = Duration::from_millis(100);
+const SEED_DENSITY: f64 = 0.3;
+// Recent generations are remembered by hash, so still lifes and short
+// oscillators are caught; anything longer is cut off after MAX_GENERATIONS.
+const HISTORY: usize = 6;
+const MAX_GENERATIONS: u32 = 3000;
+
+// Conway's Game of Life on the cell grid, wrapping at the edges.
+pub struct Life {
+    cells: Vec<bool>,
+    next: Vec<bool>,
+    cols: u16,
+    lines: u16,
+    ascii: bool,
+    rng: SmallRng,
+    history: Vec<u64>,
+    generation: u32,
+    last: Option<Instant>,
+}
+
+impl Life {
+    pub fn new(ascii: bool) -> Self {
+        Self {
+            cells: Vec::new(),
+            next: Vec::new(),
+            cols: 0,
+            lines: 0,
+            ascii,
+            rng: SmallRng::seed_from_u64(0x5eed_11fe),
+            history: Vec::new(),
+            generation: 0,
+            last: None,
+        }
+    }
+
+    fn seed(&mut self) {
+        for c in &mut self.cells {
+            *c = self.rng.random_bool(SEED_DENSITY);
+        }
+        self.history.clear();
+        self.generation = 0;
+    }
+
+    fn step(&mut self) {
+        let (w, h) = (self.cols as usize, self.lines as usize);
+        for y in 0..h {
+            let (up, down) = ((y + h - 1) % h, (y + 1) % h);
+            for x in 0..w {
+                let (left, right) = ((x + w - 1) % w, (x + 1) % w);
+                let n = [
+                    (up, left),
+                    (up, x),
+                    (up, right),
+                    (y, left),
+                    (y, right),
+                    (down, left),
+                    (down, x),
+                    (down, right),
+                ]
+                .iter()
+                .filter(|&&(yy, xx)| self.cells[yy * w + xx])
+                .count();
+                let alive = self.cells[y * w + x];
+                self.next[y * w + x] = n == 3 || (alive && n == 2);
+            }
+        }
+        std::mem::swap(&mut self.cells, &mut self.next);
+        self.generation += 1;
+
+        let mut hasher = DefaultHasher::new();
+        self.cells.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.history.contains(&hash) || self.generation >= MAX_GENERATIONS {
+            self.seed();
+            return;
+        }
+        if self.history.len() == HISTORY {
+            self.history.remove(0);
+        }
+        self.history.push(hash);
+    }
+}
+
+impl Scene for Life {
+    fn resize(&mut self, cols: u16, lines: u16) {
+        self.cols = cols;
+        self.lines = lines;
+        let n = cols as usize * lines as usize;
+        self.cells.clear();
+        self.cells.resize(n, false);
+        self.next.clear();
+        self.next.resize(n, false);
+        self.seed();
+    }
+
+    fn draw(&mut self, frame: &mut Frame, palette: &Palette, now: Instant) {
+        let last = *self.last.get_or_insert(now);
+        if now.saturating_duration_since(last) >= GENERATION && !self.cells.is_empty() {
+            self.step();
+            self.last = Some(now);
+        }
+
+        frame.clear_with_bg(palette.bg);
+        let cell = Cell {
+            ch: if self.ascii { '#' } else { '\u{2588}' },
+            fg: palette.colors.first().copied(),
+            bg: palette.bg,
+            bold: false,
+        };
+        let w = self.cols.max(1) as usize;
+        for (i, _) in self.cells.iter().enumerate().filter(|(_, &alive)| alive) {
+            frame.set((i % w) as u16, (i / w) as u16, cell);
+        }
+    }
+}
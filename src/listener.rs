@@ -0,0 +1,106 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::io::{BufRead, BufReader, Read, Result};
+use std::net::{SocketAddr, TcpListener, UdpSocket};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::thread;
+
+// Longest line kept, in bytes; the rest of a longer one is dropped.
+const MAX_LINE: u64 = 4096;
+// Lines waiting for the render loop. TCP senders wait for room, UDP
+// datagrams that don't fit are dropped.
+const QUEUE_LEN: usize = 256;
+
+// A bare port listens on loopback only; other hosts need an explicit address.
+pub fn parse_listen_addr(s: &str) -> std::result::Result<SocketAddr, String> {
+    let s = s.trim();
+    if let Ok(port) = s.parse::<u16>() {
+        return Ok(SocketAddr::from(([127, 0, 0, 1], port)));
+    }
+    s.parse::<SocketAddr>()
+        .map_err(|_| format!("invalid listen address: {}", s))
+}
+
+pub struct Listener {
+    rx: Receiver<String>,
+}
+
+impl Listener {
+    pub fn bind(addr: SocketAddr) -> Result<Self> {
+        let tcp = TcpListener::bind(addr)?;
+        let udp = UdpSocket::bind(addr)?;
+        let (tx, rx) = sync_channel(QUEUE_LEN);
+
+        let tcp_tx = tx.clone();
+        thread::spawn(move || accept_tcp(tcp, tcp_tx));
+        thread::spawn(move || recv_udp(udp, tx));
+
+        Ok(Self { rx })
+    }
+
+    pub fn try_recv(&self) -> Option<String> {
+        self.rx.try_recv().ok()
+    }
+}
+
+fn accept_tcp(listener: TcpListener, tx: SyncSender<String>) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let tx = tx.clone();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(stream);
+            let mut buf = Vec::new();
+            while read_line(&mut reader, &mut buf) {
+                if tx.send(String::from_utf8_lossy(&buf).into_owned()).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+// Reads the next line into `buf` without its line ending, keeping at most
+// `MAX_LINE` bytes of it. False at the end of the stream or on an error.
+fn read_line(reader: &mut impl BufRead, buf: &mut Vec<u8>) -> bool {
+    buf.clear();
+    match reader.by_ref().take(MAX_LINE).read_until(b'\n', buf) {
+        Ok(0) | Err(_) => return false,
+        Ok(_) => {}
+    }
+    if buf.last() == Some(&b'\n') {
+        buf.pop();
+        if buf.last() == Some(&b'\r') {
+            buf.pop();
+        }
+        return true;
+    }
+    // Cut short: skip to the end of the line, a piece at a time.
+    let mut rest = Vec::new();
+    loop {
+        rest.clear();
+        match reader.by_ref().take(MAX_LINE).read_until(b'\n', &mut rest) {
+            Ok(0) => return true,
+            Ok(_) if rest.last() == Some(&b'\n') => return true,
+            Ok(_) => {}
+            Err(_) => return false,
+        }
+    }
+}
+
+fn recv_udp(socket: UdpSocket, tx: SyncSender<String>) {
+    let mut buf = [0u8; 65536];
+    loop {
+        let Ok((n, _)) = socket.recv_from(&mut buf) else {
+            continue;
+        };
+        let text = String::from_utf8_lossy(&buf[..n]);
+        for line in text.lines() {
+            match tx.try_send(line.to_string()) {
+                Ok(()) | Err(TrySendError::Full(_)) => {}
+                Err(TrySendError::Disconnected(_)) => return,
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, LineWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+// Appends records to a file, since the terminal itself is in raw mode on the
+// alternate screen while the rain runs.
+struct FileLogger {
+    start: Instant,
+    out: Mutex<LineWriter<File>>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+            && metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let t = self.start.elapsed().as_secs_f64();
+        if let Ok(mut out) = self.out.lock() {
+            let _ = writeln!(
+                out,
+                "{:>10.3} {:<5} {}: {}",
+                t,
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut out) = self.out.lock() {
+            let _ = out.flush();
+        }
+    }
+}
+
+pub fn parse_level(s: &str) -> Result<LevelFilter, String> {
+    s.trim().parse().map_err(|_| {
+        format!(
+            "expected off, error, warn, info, debug or trace, got: {}",
+            s
+        )
+    })
+}
+
+pub fn init(path: &Path, level: LevelFilter) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let logger = FileLogger {
+        start: Instant::now(),
+        out: Mutex::new(LineWriter::new(file)),
+    };
+    log::set_boxed_logger(Box::new(logger)).map_err(io::Error::other)?;
+    log::set_max_level(level);
+    log::info!(
+        "{} {} started",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+    Ok(())
+}
+
+// Summarises frame times once a second instead of logging every frame.
+pub struct FrameTimes {
+    since: Instant,
+    frames: u32,
+    busy: Duration,
+    worst: Duration,
+}
+
+impl FrameTimes {
+    pub fn new() -> Self {
+        Self {
+            since: Instant::now(),
+            frames: 0,
+            busy: Duration::ZERO,
+            worst: Duration::ZERO,
+        }
+    }
+
+    // `busy` is the time spent on the frame outside the sleep.
+    pub fn record(&mut self, busy: Duration) {
+        if !log::log_enabled!(Level::Debug) {
+            return;
+        }
+        self.frames += 1;
+        self.busy += busy;
+        self.worst = self.worst.max(busy);
+        let elapsed = self.since.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            log::debug!(
+                "{:.1} fps, frame work avg {:.2} ms, worst {:.2} ms",
+                self.frames as f64 / elapsed.as_secs_f64(),
+                self.busy.as_secs_f64() * 1000.0 / self.frames as f64,
+                self.worst.as_secs_f64() * 1000.0
+            );
+            *self = Self::new();
+        }
+    }
+}
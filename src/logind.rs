@@ -0,0 +1,65 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+const MATCH: &str =
+    "type='signal',interface='org.freedesktop.login1.Manager',member='PrepareForSleep'";
+
+// logind's `PrepareForSleep` signal, read through `dbus-monitor` so no D-Bus
+// library is needed: `true` just before the machine suspends, `false` once
+// it has woken up.
+pub struct SleepWatch {
+    child: Child,
+    rx: Receiver<bool>,
+}
+
+impl SleepWatch {
+    pub fn start() -> Option<Self> {
+        let mut child = Command::new("dbus-monitor")
+            .args(["--system", MATCH])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let stdout = child.stdout.take()?;
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            // The argument follows the signal header on a line of its own.
+            let mut in_signal = false;
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    return;
+                };
+                if line.contains("member=PrepareForSleep") {
+                    in_signal = true;
+                } else if in_signal {
+                    in_signal = false;
+                    let start = match line.trim() {
+                        "boolean true" => true,
+                        "boolean false" => false,
+                        _ => continue,
+                    };
+                    if tx.send(start).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Some(Self { child, rx })
+    }
+
+    pub fn try_recv(&self) -> Option<bool> {
+        self.rx.try_iter().last()
+    }
+}
+
+impl Drop for SleepWatch {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -1,38 +1,118 @@
 // Copyright (c) 2025 rezk_nightky
 
-mod cell;
-mod charset;
-mod cloud;
+mod bench;
+mod colorfile;
+mod completions;
 mod config;
-mod droplet;
-mod frame;
-mod palette;
-mod runtime;
+mod configfile;
+mod demo;
+mod doctor;
+mod idle;
+#[cfg(feature = "kitty")]
+mod kitty;
+mod listener;
+mod logger;
+#[cfg(all(feature = "logind", target_os = "linux"))]
+mod logind;
+mod man;
+mod menu;
+mod metrics;
+mod multiplexer;
+mod panes;
+#[cfg(any(feature = "kitty", feature = "sixel"))]
+mod pixel;
+mod record;
+mod schedule;
+mod screensaver;
+mod serve;
+#[cfg(feature = "sixel")]
+mod sixel;
+mod stdinfeed;
+mod still;
+mod sync;
+mod telemetry;
 mod terminal;
+mod themes;
+mod title;
+mod validate;
+#[cfg(feature = "weather")]
+mod weather;
+mod x11colors;
+
+use cosmostrix::{
+    cell, charset, clock, cloud, effect, error, frame, palette, render, runtime, scene,
+};
 
 use std::env;
 use std::fs;
+use std::io::IsTerminal;
 use std::time::Duration;
 
-use clap::Parser;
-use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use clap::{CommandFactory, FromArgMatches};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers, MouseEventKind};
+use rand::rngs::SmallRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
 
-use crate::charset::{build_chars, charset_from_str, parse_user_hex_chars};
+use crate::charset::{build_chars, charset_from_str, parse_user_hex_chars, Charset, Codepage};
+use crate::clock::{RealClock, ScaledClock};
 use crate::cloud::Cloud;
-use crate::config::Args;
+use crate::colorfile::{load_user_colors, terminal_user_colors};
+use crate::config::{Args, Command};
+use crate::demo::Demo;
+use crate::effect::{Backdrop, Glow, Marquee, Message, Phrases};
+use crate::error::CosmostrixError;
 use crate::frame::Frame;
-use crate::runtime::{BoldMode, ColorMode, ColorScheme, ShadingMode, UserColor, UserColors};
+use crate::listener::{parse_listen_addr, Listener};
+use crate::menu::{Item, Menu};
+use crate::metrics::Metrics;
+use crate::palette::HueCycle;
+use crate::panes::{parse_grid, Panes};
+use crate::runtime::{
+    Backend, BoldMode, BorderStyle, Collision, ColorMode, ColorOverride, ColorScheme,
+    DensityProfile, GlitchMode, Margin, MetricsMode, Region, ShadeCurve, ShadingMode, TypingMode,
+    UserColors, WaveStart,
+};
+use crate::scene::SceneKind;
+use crate::schedule::{parse_time_of_day, ThemeSchedule};
+use crate::screensaver::ScrMode;
+use crate::stdinfeed::StdinFeed;
+use crate::sync::SyncGroup;
+use crate::telemetry::{FrameRecord, Telemetry};
 use crate::terminal::Terminal;
+use crate::title::TitleRain;
+
+// The locale the C library would use for character encoding.
+fn locale() -> String {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|v| env::var(v).ok().filter(|s| !s.is_empty()))
+        .unwrap_or_default()
+}
 
 fn default_to_ascii() -> bool {
-    let lang = env::var("LANG").unwrap_or_default();
-    !lang.to_ascii_uppercase().contains("UTF")
+    !locale().to_ascii_uppercase().contains("UTF")
 }
 
 fn detect_color_mode(args: &Args) -> ColorMode {
+    let mut mode = requested_color_mode(args);
+    if args.colormode.is_none() {
+        mode = multiplexer::cap_color_mode(mode);
+    }
+    if terminal::vt_available() {
+        return mode;
+    }
+    match mode {
+        ColorMode::Color256 | ColorMode::TrueColor => ColorMode::Color16,
+        m => m,
+    }
+}
+
+fn requested_color_mode(args: &Args) -> ColorMode {
     if let Some(m) = args.colormode {
         return match m {
             0 => ColorMode::Mono,
+            8 => ColorMode::Color8,
             16 => ColorMode::Color16,
             32 => ColorMode::TrueColor,
             256 => ColorMode::Color256,
@@ -40,11 +120,21 @@ fn detect_color_mode(args: &Args) -> ColorMode {
         };
     }
 
-    let colorterm = env::var("COLORTERM").unwrap_or_default().to_ascii_lowercase();
+    if !args.force_color && env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        return ColorMode::Mono;
+    }
+
+    let colorterm = env::var("COLORTERM")
+        .unwrap_or_default()
+        .to_ascii_lowercase();
     if colorterm.contains("truecolor") || colorterm.contains("24bit") {
         return ColorMode::TrueColor;
     }
 
+    if cfg!(windows) && env::var_os("WT_SESSION").is_some() {
+        return ColorMode::TrueColor;
+    }
+
     let term = env::var("TERM").unwrap_or_default().to_ascii_lowercase();
     if term.contains("256color") {
         return ColorMode::Color256;
@@ -55,7 +145,7 @@ fn detect_color_mode(args: &Args) -> ColorMode {
 
 fn parse_color_scheme(s: &str) -> Result<ColorScheme, String> {
     match s.trim().to_ascii_lowercase().as_str() {
-        "user" => Ok(ColorScheme::User),
+        "user" | "terminal" => Ok(ColorScheme::User),
         "green" => Ok(ColorScheme::Green),
         "green2" => Ok(ColorScheme::Green2),
         "green3" => Ok(ColorScheme::Green3),
@@ -75,53 +165,532 @@ fn parse_color_scheme(s: &str) -> Result<ColorScheme, String> {
     }
 }
 
-fn parse_user_colors(path: &std::path::Path) -> std::result::Result<UserColors, String> {
+#[cfg(feature = "weather")]
+fn apply_weather(
+    cloud: &mut Cloud,
+    w: weather::Weather,
+    scheme: ColorScheme,
+    speed: f32,
+    density: f32,
+) {
+    use crate::weather::Weather;
+
+    let (scheme, speed, density) = match w {
+        Weather::Rain => (ColorScheme::Blue, speed * 1.25, density * 1.5),
+        Weather::Fog => (ColorScheme::Gray, speed * 0.5, density),
+        Weather::Clear => (ColorScheme::Gold, speed, density * 0.5),
+        Weather::Other => (scheme, speed, density),
+    };
+    cloud.set_color_scheme(scheme);
+    cloud.set_chars_per_sec(speed.clamp(0.001, 1_000_000.0));
+    cloud.set_droplet_density(density.clamp(0.01, 5.0));
+}
+
+fn detect_backend() -> Backend {
+    #[cfg(feature = "sixel")]
+    if terminal::supports_sixel() {
+        return Backend::Sixel;
+    }
+    Backend::Cell
+}
+
+fn parse_backend(s: &str) -> Result<Backend, String> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "auto" => Ok(detect_backend()),
+        "cell" => Ok(Backend::Cell),
+        #[cfg(feature = "kitty")]
+        "kitty" => Ok(Backend::Kitty),
+        #[cfg(feature = "sixel")]
+        "sixel" => Ok(Backend::Sixel),
+        _ => Err(format!("unsupported backend: {}", s)),
+    }
+}
+
+// `--auto-scale` keeps the look of the rain on a terminal this many rows tall.
+const REFERENCE_LINES: u16 = 24;
+
+// `--prefill` runs the rain for a random span in this range before the first frame.
+const PREFILL_SECS: std::ops::RangeInclusive<f32> = 5.0..=20.0;
+
+// `--time-scale` and the `<` / `>` keys stay within this range.
+const MIN_TIME_SCALE: f64 = 1.0 / 16.0;
+const MAX_TIME_SCALE: f64 = 16.0;
+
+// Length of `cosmostrix record` without `--duration`.
+const RECORD_SECS: f32 = 10.0;
+
+// `--sleep-after` dims the rain to black over this last stretch.
+const SLEEP_DIM: Duration = Duration::from_secs(60);
+// Dim levels are rounded to this many steps, so the screen is repainted
+// only when the colors actually change.
+const SLEEP_DIM_STEPS: f32 = 64.0;
+
+// Holding Up/Down (reported as repeats with the kitty keyboard protocol)
+// adds one more speed step per repeat for every this many repeats.
+const SPEED_RAMP_REPEATS: u32 = 8;
+
+// Cells redrawn per frame in low-bandwidth mode.
+const LOW_BANDWIDTH_CELLS: usize = 200;
+// Terminal round trips slower than this switch low-bandwidth mode on.
+const SLOW_LINK: Duration = Duration::from_millis(60);
+
+fn detect_low_bandwidth(s: &str) -> Result<bool, String> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "on" | "true" | "1" => Ok(true),
+        "off" | "false" | "0" => Ok(false),
+        "auto" => {
+            let remote =
+                env::var_os("SSH_CONNECTION").is_some() || env::var_os("SSH_TTY").is_some();
+            Ok(remote && terminal::measure_latency().is_some_and(|d| d >= SLOW_LINK))
+        }
+        _ => Err(format!("expected on, off or auto, got: {}", s)),
+    }
+}
+
+fn parse_typing_mode(s: &str) -> Result<TypingMode, String> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "random" => Ok(TypingMode::Random),
+        "cursor" => Ok(TypingMode::Cursor),
+        _ => Err(format!("invalid typing mode: {}", s)),
+    }
+}
+
+fn parse_shade_curve(s: &str) -> Result<ShadeCurve, String> {
+    let s = s.trim().to_ascii_lowercase();
+    if let Some(n) = s.strip_prefix("steps:") {
+        return match n.trim().parse::<u8>() {
+            Ok(n) if n >= 2 => Ok(ShadeCurve::Steps(n)),
+            _ => Err(format!("expected steps:N with N from 2 to 255, got: {}", s)),
+        };
+    }
+    match s.as_str() {
+        "linear" => Ok(ShadeCurve::Linear),
+        "quadratic" => Ok(ShadeCurve::Quadratic),
+        "exp" => Ok(ShadeCurve::Exp),
+        _ => Err(format!("invalid shade curve: {}", s)),
+    }
+}
+
+fn parse_glitch_mode(s: &str) -> Result<GlitchMode, String> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "global" => Ok(GlitchMode::Global),
+        "cell" => Ok(GlitchMode::Cell),
+        _ => Err(format!("invalid glitch mode: {}", s)),
+    }
+}
+
+fn parse_collision(s: &str) -> Result<Collision, String> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "pass" => Ok(Collision::Pass),
+        "merge" => Ok(Collision::Merge),
+        "queue" => Ok(Collision::Queue),
+        _ => Err(format!("expected merge, pass or queue, got: {}", s)),
+    }
+}
+
+fn parse_wave_start(s: &str) -> Result<WaveStart, String> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "left" => Ok(WaveStart::Left),
+        "center" => Ok(WaveStart::Center),
+        _ => Err(format!("expected left or center, got: {}", s)),
+    }
+}
+
+fn parse_metrics_mode(s: &str) -> Result<MetricsMode, String> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "cpu" => Ok(MetricsMode::Cpu),
+        "mem" | "memory" => Ok(MetricsMode::Mem),
+        "net" | "network" => Ok(MetricsMode::Net),
+        _ => Err(format!("invalid metrics source: {}", s)),
+    }
+}
+
+fn parse_density_profile(s: &str) -> Result<DensityProfile, String> {
+    let s = s.trim();
+    if let Some(path) = s.strip_prefix("file:") {
+        return parse_density_mask(std::path::Path::new(path));
+    }
+    match s.to_ascii_lowercase().as_str() {
+        "uniform" => Ok(DensityProfile::Uniform),
+        "center" => Ok(DensityProfile::Center),
+        "edges" => Ok(DensityProfile::Edges),
+        "gradient-left" => Ok(DensityProfile::GradientLeft),
+        _ => Err(format!("invalid density profile: {}", s)),
+    }
+}
+
+fn parse_region(s: &str) -> Result<Region, String> {
+    let parts: Vec<&str> = s.split(',').map(|p| p.trim()).collect();
+    if parts.len() != 4 {
+        return Err(format!("expected x,y,w,h, got: {}", s));
+    }
+    let mut v = [0u16; 4];
+    for (out, p) in v.iter_mut().zip(&parts) {
+        *out = p.parse().map_err(|_| format!("invalid number: {}", p))?;
+    }
+    if v[2] == 0 || v[3] == 0 {
+        return Err("width and height must be positive".to_string());
+    }
+    Ok(Region {
+        x: v[0],
+        y: v[1],
+        width: v[2],
+        height: v[3],
+    })
+}
+
+// `COLSxROWS`, the screen size for the off-screen commands.
+fn parse_size(s: &str) -> Result<(u16, u16), String> {
+    let (c, r) = s
+        .trim()
+        .to_ascii_lowercase()
+        .split_once('x')
+        .and_then(|(c, r)| Some((c.trim().parse::<u16>().ok()?, r.trim().parse::<u16>().ok()?)))
+        .ok_or_else(|| format!("expected COLSxROWS, got: {}", s))?;
+    if c == 0 || r == 0 {
+        return Err("width and height must be positive".to_string());
+    }
+    Ok((c, r))
+}
+
+// `T,R,B,L`, or a single number for all four sides.
+fn parse_margin(s: &str) -> Result<Margin, String> {
+    let v = s
+        .split(',')
+        .map(|p| {
+            p.trim()
+                .parse::<u16>()
+                .map_err(|_| format!("invalid number: {}", p.trim()))
+        })
+        .collect::<Result<Vec<u16>, String>>()?;
+    let m = match v[..] {
+        [n] => Margin {
+            top: n,
+            right: n,
+            bottom: n,
+            left: n,
+        },
+        [top, right, bottom, left] => Margin {
+            top,
+            right,
+            bottom,
+            left,
+        },
+        _ => return Err(format!("expected T,R,B,L or a single number, got: {}", s)),
+    };
+    // Room for a border on each side and one cell between them.
+    let room = |a: u16, b: u16| a as u32 + b as u32 + 3 <= u16::MAX as u32;
+    if !room(m.top, m.bottom) || !room(m.left, m.right) {
+        return Err(format!("margins leave no room to draw: {}", s));
+    }
+    Ok(m)
+}
+
+// A span such as `30m`, `1h30m` or `90s`; a bare number counts as minutes.
+fn parse_span(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    if let Ok(m) = s.parse::<f64>() {
+        return match Duration::try_from_secs_f64(m * 60.0) {
+            Ok(d) if m > 0.0 => Ok(d),
+            Err(_) if m.is_finite() && m > 0.0 => Err(format!("span too long: {}", s)),
+            _ => Err(format!("expected a positive span, got: {}", s)),
+        };
+    }
+    let mut total = 0.0;
+    let mut num = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() || c == '.' {
+            num.push(c);
+            continue;
+        }
+        let unit = match c.to_ascii_lowercase() {
+            'h' => 3600.0,
+            'm' => 60.0,
+            's' => 1.0,
+            _ => {
+                return Err(format!(
+                    "expected a span like 30m, 1h30m or 90s, got: {}",
+                    s
+                ))
+            }
+        };
+        let n: f64 = num
+            .parse()
+            .map_err(|_| format!("expected a span like 30m, 1h30m or 90s, got: {}", s))?;
+        total += n * unit;
+        num.clear();
+    }
+    if !num.is_empty() || total <= 0.0 {
+        return Err(format!(
+            "expected a span like 30m, 1h30m or 90s, got: {}",
+            s
+        ));
+    }
+    Duration::try_from_secs_f64(total).map_err(|_| format!("span too long: {}", s))
+}
+
+fn parse_border(s: &str) -> Result<Option<BorderStyle>, String> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "none" | "off" => Ok(None),
+        "single" => Ok(Some(BorderStyle::Single)),
+        "double" => Ok(Some(BorderStyle::Double)),
+        _ => Err(format!("expected single, double or none, got: {}", s)),
+    }
+}
+
+fn parse_density_mask(path: &std::path::Path) -> Result<DensityProfile, String> {
     let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
-    let mut colors: Vec<UserColor> = Vec::new();
+    let mut mask: Vec<f32> = Vec::new();
 
     for line in content.lines() {
         let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-        let first = line.chars().next().unwrap_or(' ');
-        if first == ';' || first == '#' || first == '/' || first == '*' || first == '@' {
+        if line.is_empty() || line.starts_with('#') {
             continue;
         }
-        if line.contains("neo_color_version") {
-            continue;
+        for part in line.split(|c: char| c == ',' || c.is_whitespace()) {
+            if part.is_empty() {
+                continue;
+            }
+            let v: f32 = part
+                .parse()
+                .map_err(|_| format!("invalid density mask weight: {}", part))?;
+            mask.push(v.clamp(0.0, 1.0));
         }
+    }
+
+    if mask.is_empty() {
+        return Err("density mask must contain at least one weight".to_string());
+    }
+
+    Ok(DensityProfile::Mask(mask))
+}
 
-        let parts: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
-        if parts.is_empty() {
+fn parse_color_override(s: &str) -> Result<ColorOverride, String> {
+    let s = s.trim();
+    let hex = s.strip_prefix('#').unwrap_or(s);
+    if hex.len() == 6 {
+        let v = u32::from_str_radix(hex, 16).map_err(|_| format!("invalid hex color: {}", s))?;
+        return Ok(ColorOverride::Rgb((v >> 16) as u8, (v >> 8) as u8, v as u8));
+    }
+    s.parse::<u8>()
+        .map(ColorOverride::Index)
+        .map_err(|_| format!("invalid color (expected #RRGGBB or 0-255): {}", s))
+}
+
+fn parse_auto_theme(s: &str) -> Result<ThemeSchedule, String> {
+    let mut entries: Vec<(u16, ColorScheme)> = Vec::new();
+    for part in s.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
             continue;
         }
-        let idx: u8 = parts[0]
-            .parse::<u16>()
-            .map_err(|_| "invalid color index".to_string())?
-            .min(255) as u8;
+        let (time, scheme) = part
+            .split_once('=')
+            .ok_or_else(|| format!("invalid auto-theme entry (expected HH:MM=COLOR): {}", part))?;
+        entries.push((parse_time_of_day(time)?, parse_color_scheme(scheme)?));
+    }
+    if entries.is_empty() {
+        return Err("auto-theme needs at least one HH:MM=COLOR entry".to_string());
+    }
+    entries.sort_by_key(|(m, _)| *m);
+    Ok(ThemeSchedule { entries })
+}
 
-        let rgb_1000 = if parts.len() >= 4 {
-            let r: u16 = parts[1].parse().map_err(|_| "invalid r".to_string())?;
-            let g: u16 = parts[2].parse().map_err(|_| "invalid g".to_string())?;
-            let b: u16 = parts[3].parse().map_err(|_| "invalid b".to_string())?;
-            Some((r, g, b))
-        } else {
-            None
-        };
+// Clones the configured cloud into the extra panes, then gives each pane
+// (the primary included) its entry from --pane-colors / --pane-charsets.
+fn build_panes(
+    args: &Args,
+    grid: &str,
+    cloud: &mut Cloud,
+    user_ranges: &[(char, char)],
+    def_ascii: bool,
+) -> Result<Panes, CosmostrixError> {
+    let (cols, rows) = parse_grid(grid).map_err(|e| CosmostrixError::invalid("panes", e))?;
+    let list = |s: &Option<String>| -> Vec<String> {
+        s.as_deref()
+            .map(|s| {
+                s.split(',')
+                    .map(|p| p.trim().to_string())
+                    .filter(|p| !p.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let schemes = list(&args.pane_colors)
+        .iter()
+        .map(|s| parse_color_scheme(s).map_err(|e| CosmostrixError::invalid("pane-colors", e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let charsets = list(&args.pane_charsets)
+        .iter()
+        .map(|s| charset_from_str(s, def_ascii))
+        .collect::<Result<Vec<_>, _>>()?;
 
-        colors.push(UserColor { index: idx, rgb_1000 });
+    let n = cols as usize * rows as usize;
+    let mut clouds = Vec::with_capacity(n);
+    for i in 0..n {
+        let mut c = cloud.clone();
+        c.set_seed(0x1234567 + i as u64);
+        if let Some(&scheme) = schemes.get(i % schemes.len().max(1)) {
+            c.set_color_scheme(scheme);
+        }
+        if let Some(&cs) = charsets.get(i % charsets.len().max(1)) {
+            c.init_chars(build_chars(cs, user_ranges, def_ascii));
+        }
+        clouds.push(c);
     }
+    *cloud = clouds.remove(0);
+    Ok(Panes::new(cols, rows, args.pane_separators, clouds))
+}
 
-    if colors.len() < 2 {
-        return Err("color file must contain at least two colors".to_string());
+fn force_redraw(cloud: &mut Cloud, panes: &mut Option<Panes>) {
+    cloud.force_draw_everything();
+    for c in panes.iter_mut().flat_map(|p| p.extra.iter_mut()) {
+        c.force_draw_everything();
     }
+}
 
-    Ok(UserColors { colors })
+fn handle_key(cloud: &mut Cloud, code: KeyCode, modifiers: KeyModifiers) {
+    match (code, modifiers) {
+        (KeyCode::Esc, _) => cloud.raining = false,
+        (KeyCode::Char(c), m)
+            if cloud.typing_mode.is_some() && !m.contains(KeyModifiers::CONTROL) =>
+        {
+            cloud.type_char(c);
+        }
+        (KeyCode::Char('q'), _) => cloud.raining = false,
+        (KeyCode::Char(' '), _) => {
+            cloud.reset(cloud.cols, cloud.lines);
+            cloud.force_draw_everything();
+        }
+        (KeyCode::Char('a'), _) => {
+            cloud.set_async(!cloud.async_mode);
+        }
+        (KeyCode::Char('p'), _) => {
+            cloud.toggle_pause();
+        }
+        (KeyCode::Char('H'), _) => cloud.toggle_heat_map(),
+        (KeyCode::Up, _) => menu::adjust(cloud, Item::Speed, 1),
+        (KeyCode::Down, _) => menu::adjust(cloud, Item::Speed, -1),
+        (KeyCode::Left, _) => menu::adjust(cloud, Item::Glitch, -1),
+        (KeyCode::Right, _) => menu::adjust(cloud, Item::Glitch, 1),
+        (KeyCode::Tab, _) => {
+            let sm = match cloud.shading_mode() {
+                ShadingMode::Random => ShadingMode::DistanceFromHead,
+                ShadingMode::DistanceFromHead => ShadingMode::Speed,
+                ShadingMode::Speed => ShadingMode::Random,
+            };
+            cloud.set_shading_mode(sm);
+        }
+        (KeyCode::Char('-'), _) => menu::adjust(cloud, Item::Density, -1),
+        (KeyCode::Char('+') | KeyCode::Char('='), _) => menu::adjust(cloud, Item::Density, 1),
+        (KeyCode::Char('1'), _) => cloud.set_color_scheme(ColorScheme::Green),
+        (KeyCode::Char('2'), _) => cloud.set_color_scheme(ColorScheme::Green2),
+        (KeyCode::Char('3'), _) => cloud.set_color_scheme(ColorScheme::Green3),
+        (KeyCode::Char('4'), _) => cloud.set_color_scheme(ColorScheme::Gold),
+        (KeyCode::Char('5'), _) => cloud.set_color_scheme(ColorScheme::Pink2),
+        (KeyCode::Char('6'), _) => cloud.set_color_scheme(ColorScheme::Red),
+        (KeyCode::Char('7'), _) => cloud.set_color_scheme(ColorScheme::Blue),
+        (KeyCode::Char('8'), _) => cloud.set_color_scheme(ColorScheme::Cyan),
+        (KeyCode::Char('9'), _) => cloud.set_color_scheme(ColorScheme::Purple),
+        (KeyCode::Char('0'), _) => cloud.set_color_scheme(ColorScheme::Gray),
+        (KeyCode::Char('!'), _) => cloud.set_color_scheme(ColorScheme::Rainbow),
+        (KeyCode::Char('@'), _) => cloud.set_color_scheme(ColorScheme::Yellow),
+        (KeyCode::Char('#'), _) => cloud.set_color_scheme(ColorScheme::Orange),
+        (KeyCode::Char('$'), _) => cloud.set_color_scheme(ColorScheme::Pink),
+        (KeyCode::Char('%'), _) => cloud.set_color_scheme(ColorScheme::Vaporwave),
+        _ => {}
+    }
 }
 
-fn main() -> std::io::Result<()> {
-    let args = Args::parse();
+// The runtime-adjustable settings as config file entries.
+fn current_settings(cloud: &Cloud, menu: &Menu, rows_per_cell: u16) -> Vec<(&'static str, String)> {
+    let shading = match cloud.shading_mode() {
+        ShadingMode::Random => 0,
+        ShadingMode::DistanceFromHead => 1,
+        ShadingMode::Speed => 2,
+    };
+    let bold = match cloud.bold_mode {
+        BoldMode::Off => 0,
+        BoldMode::Random => 1,
+        BoldMode::All => 2,
+    };
+    vec![
+        ("color", configfile::toml_str(cloud.color_scheme().name())),
+        (
+            "speed",
+            format!("{}", cloud.chars_per_sec / rows_per_cell as f32),
+        ),
+        ("density", format!("{}", cloud.droplet_density)),
+        (
+            "glitchpct",
+            format!("{}", (cloud.glitch_pct * 100.0).round()),
+        ),
+        ("charset", configfile::toml_str(menu.charset_name())),
+        ("shadingmode", shading.to_string()),
+        ("bold", bold.to_string()),
+    ]
+}
+
+fn save_settings(cloud: &Cloud, menu: &Menu, rows_per_cell: u16) -> Result<(), CosmostrixError> {
+    let path = configfile::path().ok_or(CosmostrixError::NoConfigDir)?;
+    configfile::save(&path, &current_settings(cloud, menu, rows_per_cell))
+        .map_err(|source| CosmostrixError::File { path, source })
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), CosmostrixError> {
+    let (mut argv, scr_mode) = screensaver::split_args(env::args_os().collect());
+    let mut from_file = 0;
+    if let Some(path) = configfile::path() {
+        let extra = configfile::load_args(&path)?;
+        from_file = extra.len();
+        argv.splice(1..1, extra);
+    }
+    let matches = Args::command().get_matches_from(&argv);
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if args.dump_config || matches!(args.command, Some(Command::Export)) {
+        print!("{}", configfile::dump(&matches, from_file));
+        return Ok(());
+    }
+    match &args.command {
+        Some(Command::Completions { shell }) => {
+            print!(
+                "{}",
+                completions::script(shell)
+                    .map_err(|e| CosmostrixError::invalid("completions", e))?
+            );
+            return Ok(());
+        }
+        Some(Command::Man) => {
+            print!("{}", man::page());
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    match scr_mode {
+        Some(ScrMode::Preview) => return Ok(()),
+        Some(ScrMode::Configure) => {
+            println!(
+                "{} has no screensaver settings; it runs with its default options.",
+                env!("CARGO_PKG_NAME")
+            );
+            return Ok(());
+        }
+        Some(ScrMode::Show) => {
+            if screensaver::relaunch_in_windows_terminal(&argv) {
+                return Ok(());
+            }
+            args.screensaver = true;
+        }
+        None => {}
+    }
 
     if args.info {
         println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
@@ -130,14 +699,44 @@ fn main() -> std::io::Result<()> {
         return Ok(());
     }
 
+    validate::check(&args)?;
+
+    if let Some(path) = &args.log_file {
+        let level = logger::parse_level(&args.log_level)
+            .map_err(|e| CosmostrixError::invalid("log-level", e))?;
+        logger::init(path, level).map_err(|source| CosmostrixError::File {
+            path: path.clone(),
+            source,
+        })?;
+    }
+
+    if let Some(minutes) = args.idle.filter(|_| env::var_os(idle::CHILD_ENV).is_none()) {
+        let wait = Duration::try_from_secs_f32(minutes * 60.0)
+            .map_err(|_| CosmostrixError::invalid("idle", "too long"))?;
+        return Ok(idle::run(wait)?);
+    }
+
     let def_ascii = default_to_ascii();
-    let color_mode = detect_color_mode(&args);
+    let low_bandwidth = detect_low_bandwidth(&args.low_bandwidth)
+        .map_err(|e| CosmostrixError::invalid("low-bandwidth", e))?;
+
+    let mut color_mode = detect_color_mode(&args);
+    if low_bandwidth
+        && args.colormode.is_none()
+        && matches!(color_mode, ColorMode::Color256 | ColorMode::TrueColor)
+    {
+        color_mode = ColorMode::Color16;
+    }
 
     let shading_mode = match args.shading_mode {
         1 => ShadingMode::DistanceFromHead,
+        2 => ShadingMode::Speed,
         _ => ShadingMode::Random,
     };
 
+    let shade_curve = parse_shade_curve(&args.shade_curve)
+        .map_err(|e| CosmostrixError::invalid("shade-curve", e))?;
+
     let bold_mode = match args.bold {
         0 => BoldMode::Off,
         2 => BoldMode::All,
@@ -146,29 +745,162 @@ fn main() -> std::io::Result<()> {
 
     let mut user_colors: Option<UserColors> = None;
     if let Some(path) = &args.colorfile {
-        match parse_user_colors(path) {
-            Ok(uc) => user_colors = Some(uc),
-            Err(e) => {
-                eprintln!("{}", e);
-                std::process::exit(1);
-            }
-        }
+        user_colors =
+            Some(load_user_colors(path).map_err(|e| CosmostrixError::invalid("colorfile", e))?);
     }
 
-    let mut color_scheme = match parse_color_scheme(&args.color) {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("{}", e);
-            std::process::exit(1);
-        }
-    };
+    if user_colors.is_none() && args.color.trim().eq_ignore_ascii_case("terminal") {
+        user_colors = Some(terminal_user_colors());
+    }
+
+    let mut color_scheme =
+        parse_color_scheme(&args.color).map_err(|e| CosmostrixError::invalid("color", e))?;
 
     if user_colors.is_some() {
         color_scheme = ColorScheme::User;
     }
 
-    let mut term = Terminal::new()?;
-    let (w, h) = term.size()?;
+    let density_profile = parse_density_profile(&args.density_profile)
+        .map_err(|e| CosmostrixError::invalid("density-profile", e))?;
+
+    let mut metrics = args
+        .metrics
+        .as_deref()
+        .map(parse_metrics_mode)
+        .transpose()
+        .map_err(|e| CosmostrixError::invalid("metrics", e))?
+        .map(Metrics::new);
+
+    let glitch_mode = parse_glitch_mode(&args.glitch_mode)
+        .map_err(|e| CosmostrixError::invalid("glitch-mode", e))?;
+
+    let collision =
+        parse_collision(&args.collision).map_err(|e| CosmostrixError::invalid("collision", e))?;
+
+    let wave_start = args
+        .wave_start
+        .as_deref()
+        .map(parse_wave_start)
+        .transpose()
+        .map_err(|e| CosmostrixError::invalid("wave-start", e))?;
+
+    let typing_mode = args
+        .typing
+        .as_deref()
+        .map(parse_typing_mode)
+        .transpose()
+        .map_err(|e| CosmostrixError::invalid("typing", e))?;
+
+    let listen_addr = args
+        .listen
+        .as_deref()
+        .map(parse_listen_addr)
+        .transpose()
+        .map_err(|e| CosmostrixError::invalid("listen", e))?;
+    let listener = match listen_addr {
+        Some(addr) => Some(
+            Listener::bind(addr)
+                .map_err(|e| CosmostrixError::invalid("listen", format!("{}: {}", addr, e)))?,
+        ),
+        None => None,
+    };
+
+    #[cfg(feature = "weather")]
+    let weather_feed = args.weather.as_deref().map(weather::WeatherFeed::start);
+
+    let auto_theme = args
+        .auto_theme
+        .as_deref()
+        .map(parse_auto_theme)
+        .transpose()
+        .map_err(|e| CosmostrixError::invalid("auto-theme", e))?;
+
+    let head_color = args
+        .head_color
+        .as_deref()
+        .map(parse_color_override)
+        .transpose()
+        .map_err(|e| CosmostrixError::invalid("head-color", e))?;
+    let tail_color = args
+        .tail_color
+        .as_deref()
+        .map(parse_color_override)
+        .transpose()
+        .map_err(|e| CosmostrixError::invalid("tail-color", e))?;
+
+    let light = if args.light {
+        true
+    } else if args.dark || color_mode == ColorMode::Mono {
+        false
+    } else {
+        terminal::detect_light_background().unwrap_or(false)
+    };
+
+    if let Some(Command::Themes) = args.command {
+        return Ok(themes::print(color_mode, light, args.defaultbg)?);
+    }
+
+    let region = args
+        .region
+        .as_deref()
+        .map(parse_region)
+        .transpose()
+        .map_err(|e| CosmostrixError::invalid("region", e))?;
+    let margin = args
+        .margin
+        .as_deref()
+        .map(parse_margin)
+        .transpose()
+        .map_err(|e| CosmostrixError::invalid("margin", e))?;
+    let border = parse_border(&args.border).map_err(|e| CosmostrixError::invalid("border", e))?;
+
+    let mut scene_kind = SceneKind::from_name(&args.scene).ok_or_else(|| {
+        CosmostrixError::invalid("scene", format!("unknown scene: {}", args.scene))
+    })?;
+    let scene_feed = match args.scene_input.trim().to_ascii_lowercase().as_str() {
+        "random" => None,
+        "stdin" if std::io::stdin().is_terminal() => {
+            return Err(CosmostrixError::invalid(
+                "scene-input",
+                "stdin must be piped, not a terminal",
+            ));
+        }
+        "stdin" => Some(StdinFeed::start()),
+        s => {
+            return Err(CosmostrixError::invalid(
+                "scene-input",
+                format!("expected random or stdin, got: {}", s),
+            ))
+        }
+    };
+
+    let backend =
+        parse_backend(&args.backend).map_err(|e| CosmostrixError::invalid("backend", e))?;
+
+    let rows_per_cell: u16 = match backend {
+        Backend::Cell if args.hi_res => 2,
+        Backend::Cell => 1,
+        #[cfg(feature = "kitty")]
+        Backend::Kitty => pixel::ROWS_PER_CELL,
+        #[cfg(feature = "sixel")]
+        Backend::Sixel => pixel::ROWS_PER_CELL,
+    };
+    #[cfg(any(feature = "kitty", feature = "sixel"))]
+    let mut canvas = pixel::Canvas::new();
+    if backend == Backend::Cell && args.bg_image.is_some() {
+        return Err(CosmostrixError::invalid(
+            "bg-image",
+            "needs --backend kitty or sixel",
+        ));
+    }
+    #[cfg(any(feature = "kitty", feature = "sixel"))]
+    if let Some(path) = &args.bg_image {
+        let image = pixel::Image::load_ppm(path).map_err(|source| CosmostrixError::File {
+            path: path.clone(),
+            source,
+        })?;
+        canvas.background = Some(image);
+    }
 
     let mut cloud = Cloud::new(
         color_mode,
@@ -181,167 +913,668 @@ fn main() -> std::io::Result<()> {
         user_colors,
     );
 
-    cloud.glitchy = !args.noglitch;
+    cloud.set_light(light);
+    let codepage = Codepage::from_locale(&locale());
+    cloud.set_codepage(codepage);
+    cloud.set_shade_curve(shade_curve, args.shade_len.saturating_mul(rows_per_cell));
+    cloud.set_head_len(args.head_len.saturating_mul(rows_per_cell));
+    cloud.set_tail_ghost(args.tail_ghost.saturating_mul(rows_per_cell));
+    cloud.set_color_overrides(head_color, tail_color);
+    cloud.glitchy = !args.noglitch && !low_bandwidth;
     cloud.set_glitch_pct((args.glitch_pct / 100.0).clamp(0.0, 1.0));
     cloud.set_glitch_times(args.glitch_ms.low, args.glitch_ms.high);
+    cloud.set_glitch_mode(glitch_mode);
+    cloud.set_glitch_drift(args.glitch_drift);
+    cloud.set_stable_glyphs(args.stable_glyphs);
+    cloud.set_decay(args.decay.map(Duration::from_millis));
+    cloud.set_reshade(
+        args.reshade
+            .and_then(|s| Duration::try_from_secs_f32(s).ok()),
+    );
+    if let Some(secs) = args.hue_cycle {
+        cloud.set_palette_source(Some(Box::new(HueCycle::new(secs, color_mode))));
+    }
     cloud.set_linger_times(args.linger_ms.low, args.linger_ms.high);
     cloud.short_pct = (args.shortpct / 100.0).clamp(0.0, 1.0);
     cloud.die_early_pct = (args.rippct / 100.0).clamp(0.0, 1.0);
     cloud.set_max_droplets_per_column(args.max_droplets_per_column.clamp(1, 3));
+    cloud.set_collision(collision);
+    cloud.set_max_droplets(args.max_droplets);
+    cloud.set_max_active(args.max_active);
+    cloud.set_spawn_rate(args.spawn_rate.map(|r| r.max(0.0)));
+    let sleep_after = args
+        .sleep_after
+        .as_deref()
+        .map(parse_span)
+        .transpose()
+        .map_err(|e| CosmostrixError::invalid("sleep-after", e))?;
+    let time_scale = ScaledClock::new(
+        Box::new(RealClock),
+        args.time_scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE),
+    );
+    cloud.set_clock(Box::new(time_scale.clone()));
+    let threads = match args.threads {
+        0 => std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+        n => n,
+    };
+    cloud.set_threads(threads);
 
     cloud.set_droplet_density(args.density.clamp(0.01, 5.0));
-    cloud.set_chars_per_sec(args.speed.clamp(0.001, 1_000_000.0));
+    cloud.set_density_profile(density_profile);
+    cloud.set_wave_start(wave_start);
+    if args.auto_scale {
+        cloud.set_auto_scale(Some(REFERENCE_LINES * rows_per_cell));
+    }
+    cloud.set_col_stride(args.col_stride, args.col_offset);
+    cloud.typing_mode = typing_mode;
+    if args.glow {
+        cloud.add_effect(Box::new(Glow::default()));
+    }
+    cloud.set_speed_jitter((args.speed_jitter / 100.0).clamp(0.0, 0.9));
+    cloud.set_chars_per_sec((args.speed * rows_per_cell as f32).clamp(0.001, 1_000_000.0));
 
     let mut user_ranges: Vec<(char, char)> = Vec::new();
     if let Some(spec) = &args.chars {
-        match parse_user_hex_chars(spec) {
-            Ok(list) => {
-                if list.len() % 2 != 0 {
-                    eprintln!("--chars: odd number of unicode chars given (must be even)");
-                    std::process::exit(1);
-                }
-                for pair in list.chunks(2) {
-                    let a = pair[0];
-                    let b = pair[1];
-                    user_ranges.push((a, b));
-                }
-            }
-            Err(e) => {
-                eprintln!("{}", e);
-                std::process::exit(1);
-            }
+        let list = parse_user_hex_chars(spec)?;
+        if list.len() % 2 != 0 {
+            return Err(CosmostrixError::InvalidChars(
+                "odd number of unicode chars given (must be even)".to_string(),
+            ));
+        }
+        for pair in list.chunks(2) {
+            let a = pair[0];
+            let b = pair[1];
+            user_ranges.push((a, b));
         }
     }
 
-    let charset = match charset_from_str(&args.charset, def_ascii) {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("{}", e);
-            std::process::exit(1);
-        }
-    };
+    let charset = charset_from_str(&args.charset, def_ascii)?;
 
     let chars = build_chars(charset, &user_ranges, def_ascii);
+    if let Some(Command::Doctor) = args.command {
+        doctor::run(&args.charset, &chars, SLOW_LINK);
+        return Ok(());
+    }
+    let mut title_rain = args.title_rain.then(|| {
+        TitleRain::new(&codepage.map_or_else(|| chars.clone(), |cp| cp.fit(chars.clone())))
+    });
     cloud.init_chars(chars);
-    cloud.reset(w, h);
 
+    if let Some(path) = &args.bg_art {
+        let text = fs::read_to_string(path).map_err(|source| CosmostrixError::File {
+            path: path.clone(),
+            source,
+        })?;
+        cloud.add_effect(Box::new(Backdrop::new(&text)));
+    }
     if let Some(msg) = &args.message {
-        cloud.set_message(msg);
+        // Each line is a message of its own, shown one per cycle.
+        let list: Vec<String> = msg
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(String::from)
+            .collect();
+        let mut message = Message::from_list(list);
+        message.set_timing(
+            Duration::try_from_secs_f32(args.message_delay.max(0.0)).unwrap_or_default(),
+            args.message_hold
+                .and_then(|s| Duration::try_from_secs_f32(s.max(0.0)).ok()),
+        );
+        message.set_scatter(args.message_scatter);
+        cloud.add_effect(Box::new(message));
+    }
+    if let Some(text) = &args.marquee {
+        cloud.add_effect(Box::new(Marquee::new(
+            text,
+            args.marquee_row,
+            args.marquee_speed.max(0.0),
+        )));
+    }
+    if let Some(path) = &args.phrases {
+        let text = fs::read_to_string(path).map_err(|source| CosmostrixError::File {
+            path: path.clone(),
+            source,
+        })?;
+        let list: Vec<String> = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(String::from)
+            .collect();
+        if list.is_empty() {
+            return Err(CosmostrixError::invalid(
+                "phrases",
+                format!("{}: no phrases found", path.display()),
+            ));
+        }
+        cloud.add_effect(Box::new(Phrases::new(list)));
+    }
+
+    let mut panes = args
+        .panes
+        .as_deref()
+        .map(|s| build_panes(&args, s, &mut cloud, &user_ranges, def_ascii))
+        .transpose()?;
+
+    terminal::install_signal_handlers();
+    match &args.command {
+        Some(Command::Serve { bind, port }) => {
+            return Ok(serve::run(
+                cloud,
+                (bind.as_str(), *port),
+                args.fps.max(1.0),
+            )?);
+        }
+        Some(Command::Bench { frames, size }) => {
+            let size = parse_size(size).map_err(|e| CosmostrixError::invalid("size", e))?;
+            bench::run(cloud, size, *frames, args.fps.max(1.0));
+            return Ok(());
+        }
+        Some(Command::Record { file, size }) => {
+            let size = parse_size(size).map_err(|e| CosmostrixError::invalid("size", e))?;
+            let duration =
+                Duration::try_from_secs_f32(args.duration.unwrap_or(RECORD_SECS).max(0.0))
+                    .unwrap_or(Duration::MAX);
+            return record::run(cloud, file, size, duration, args.fps.max(1.0)).map_err(|source| {
+                CosmostrixError::File {
+                    path: file.clone(),
+                    source,
+                }
+            });
+        }
+        _ => {}
+    }
+
+    if terminal::is_dumb() {
+        print!("{}", still::render(cloud, still::size(), args.fps.max(1.0)));
+        return Ok(());
+    }
+
+    terminal::install_panic_hook();
+    let mut term = Terminal::new()?;
+    term.set_region(region);
+    term.set_frame_style(margin.unwrap_or_default(), border);
+    if low_bandwidth {
+        term.set_max_changes(Some(LOW_BANDWIDTH_CELLS));
+    }
+    if args.win_screensaver || args.mouse {
+        term.enable_mouse_capture()?;
+    }
+    let mut mouse_origin: Option<(u16, u16)> = None;
+    let (w, h) = term.size()?;
+    match panes.as_mut() {
+        Some(p) => p.resize(&mut cloud, w, h * rows_per_cell),
+        None => cloud.reset(w, h * rows_per_cell),
     }
 
-    let mut frame = Frame::new(w, h, cloud.palette.bg);
+    if args.prefill {
+        let span = Duration::from_secs_f32(rand::rng().random_range(PREFILL_SECS));
+        cloud.prefill(span);
+        for c in panes.iter_mut().flat_map(|p| p.extra.iter_mut()) {
+            c.prefill(span);
+        }
+    }
+
+    let mut frame = Frame::new(w, h * rows_per_cell, cloud.palette.bg);
+    let mut scene = scene_kind.build(def_ascii);
+    if let Some(s) = scene.as_mut() {
+        s.resize(frame.width, frame.height);
+    }
+    let mut screen = Frame::new(w, h, cloud.palette.bg);
 
     let target_fps = args.fps.max(1.0);
     let target_period = Duration::from_secs_f64(1.0 / target_fps);
     let mut prev = std::time::Instant::now();
     let mut prev_delay = Duration::from_millis(5);
+    let mut last_metrics_time: Option<std::time::Instant> = None;
+    let mut last_theme_check: Option<std::time::Instant> = None;
+    let mut scheduled_scheme: Option<ColorScheme> = None;
+    let shuffle_period = args
+        .shuffle
+        .and_then(|s| Duration::try_from_secs_f32(s.max(1.0)).ok());
+    let mut last_shuffle = std::time::Instant::now();
+    let mut shuffle_rng = SmallRng::from_rng(&mut rand::rng());
+    let mut shuffle_index: Option<u64> = None;
+    let sync_group = args
+        .sync_group
+        .as_deref()
+        .map(SyncGroup::join)
+        .transpose()
+        .map_err(|e| CosmostrixError::invalid("sync-group", e.to_string()))?;
+    let mut synced_scheme = cloud.color_scheme();
+    #[cfg(all(feature = "logind", target_os = "linux"))]
+    let sleep_watch = logind::SleepWatch::start();
+    #[cfg(all(feature = "logind", target_os = "linux"))]
+    let mut paused_for_sleep = false;
+    let mut demo = args.demo.map(Demo::new);
+    if let Some(t) = &args.title {
+        term.set_title(t)?;
+    }
+    let mut transition: Option<(Frame, std::time::Instant)> = None;
+    let mut menu = Menu::new(&args.charset, def_ascii);
+    let mut save_error: Option<CosmostrixError> = None;
+    let mut frame_times = logger::FrameTimes::new();
+    let mut telemetry = match &args.telemetry {
+        Some(path) => Some(
+            Telemetry::create(path).map_err(|source| CosmostrixError::File {
+                path: path.clone(),
+                source,
+            })?,
+        ),
+        None => None,
+    };
+
+    // Spans too long for the clock never run out.
+    let deadline = args
+        .duration
+        .and_then(|s| Duration::try_from_secs_f32(s.max(0.0)).ok())
+        .and_then(|d| std::time::Instant::now().checked_add(d));
+    let sleep_at = sleep_after.and_then(|d| std::time::Instant::now().checked_add(d));
+    let sleep_dim = sleep_after.map_or(SLEEP_DIM, |d| d.min(SLEEP_DIM));
+    let mut settle_at: Option<std::time::Instant> = None;
+    let mut held_repeats: u32 = 0;
+    while cloud.raining
+        && !terminal::interrupted()
+        && deadline.is_none_or(|d| std::time::Instant::now() < d)
+    {
+        let mut resize: Option<(u16, u16)> = None;
+        if terminal::take_suspend_request() {
+            term.suspend()?;
+            resize = Some(term.size()?);
+        }
+        if terminal::take_resumed() {
+            term.resume()?;
+            resize = Some(term.size()?);
+        }
 
-    while cloud.raining {
         while Terminal::poll_event(Duration::from_millis(0))? {
             let ev = Terminal::read_event()?;
             match ev {
                 Event::Resize(nw, nh) => {
-                    cloud.reset(nw, nh);
-                    frame = Frame::new(nw, nh, cloud.palette.bg);
-                    cloud.force_draw_everything();
+                    resize = Some(term.resized(nw, nh)?);
+                    if multiplexer::detect().is_some() {
+                        settle_at = Some(std::time::Instant::now() + multiplexer::RESIZE_SETTLE);
+                    }
+                }
+                Event::Mouse(m) if args.win_screensaver => {
+                    let pos = (m.column, m.row);
+                    if m.kind != MouseEventKind::Moved || mouse_origin.is_some_and(|o| o != pos) {
+                        cloud.raining = false;
+                        break;
+                    }
+                    mouse_origin = Some(pos);
+                }
+                Event::Mouse(m)
+                    if matches!(
+                        m.kind,
+                        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown
+                    ) =>
+                {
+                    let dir = if m.kind == MouseEventKind::ScrollUp {
+                        1
+                    } else {
+                        -1
+                    };
+                    let item = if m.modifiers.contains(KeyModifiers::SHIFT) {
+                        Item::Density
+                    } else {
+                        Item::Speed
+                    };
+                    for c in std::iter::once(&mut cloud)
+                        .chain(panes.iter_mut().flat_map(|p| p.extra.iter_mut()))
+                    {
+                        menu::adjust(c, item, dir);
+                    }
                 }
-                Event::Key(k) if k.kind == KeyEventKind::Press => {
+                Event::Key(k) if k.kind == KeyEventKind::Release => held_repeats = 0,
+                Event::Key(k) => {
                     if args.screensaver {
                         cloud.raining = false;
                         break;
                     }
 
-                    match (k.code, k.modifiers) {
-                        (KeyCode::Esc, _) => cloud.raining = false,
-                        (KeyCode::Char('q'), _) => cloud.raining = false,
-                        (KeyCode::Char(' '), _) => {
-                            cloud.reset(frame.width, frame.height);
-                            cloud.force_draw_everything();
-                        }
-                        (KeyCode::Char('a'), _) => {
-                            cloud.set_async(!cloud.async_mode);
-                        }
-                        (KeyCode::Char('p'), _) => {
-                            cloud.toggle_pause();
-                        }
-                        (KeyCode::Up, _) => {
-                            let mut cps = cloud.chars_per_sec;
-                            if cps <= 0.5 {
-                                cps *= 2.0;
-                            } else {
-                                cps += 1.0;
-                            }
-                            cloud.set_chars_per_sec(cps.min(1000.0));
-                        }
-                        (KeyCode::Down, _) => {
-                            let mut cps = cloud.chars_per_sec;
-                            if cps <= 1.0 {
-                                cps /= 2.0;
-                            } else {
-                                cps -= 1.0;
+                    if cfg!(unix)
+                        && k.code == KeyCode::Char('z')
+                        && k.modifiers.contains(KeyModifiers::CONTROL)
+                    {
+                        term.suspend()?;
+                        resize = Some(term.size()?);
+                        continue;
+                    }
+
+                    if menu.open {
+                        let clouds = std::iter::once(&mut cloud)
+                            .chain(panes.iter_mut().flat_map(|p| p.extra.iter_mut()));
+                        if menu.handle_key(k.code, clouds) {
+                            if !menu.open {
+                                force_redraw(&mut cloud, &mut panes);
                             }
-                            cloud.set_chars_per_sec(cps.max(0.001));
+                            continue;
                         }
-                        (KeyCode::Left, _) => {
-                            if cloud.glitchy {
-                                let gp = (cloud.glitch_pct - 0.05).max(0.0);
-                                cloud.set_glitch_pct(gp);
-                            }
+                    } else if k.code == KeyCode::Char('m')
+                        && cloud.typing_mode.is_none()
+                        && backend == Backend::Cell
+                    {
+                        menu.open = true;
+                        continue;
+                    } else if k.code == KeyCode::Char('w') && cloud.typing_mode.is_none() {
+                        save_error = save_settings(&cloud, &menu, rows_per_cell).err();
+                        continue;
+                    } else if k.code == KeyCode::Char('s') && cloud.typing_mode.is_none() {
+                        scene_kind = scene_kind.next();
+                        scene = scene_kind.build(def_ascii);
+                        if let Some(s) = scene.as_mut() {
+                            s.resize(frame.width, frame.height);
                         }
-                        (KeyCode::Right, _) => {
-                            if cloud.glitchy {
-                                let gp = (cloud.glitch_pct + 0.05).min(1.0);
-                                cloud.set_glitch_pct(gp);
+                        force_redraw(&mut cloud, &mut panes);
+                        log::debug!("switched to the {} scene", scene_kind.name());
+                        continue;
+                    } else if matches!(k.code, KeyCode::Char('<') | KeyCode::Char('>'))
+                        && cloud.typing_mode.is_none()
+                    {
+                        let step = if k.code == KeyCode::Char('>') {
+                            2.0
+                        } else {
+                            0.5
+                        };
+                        time_scale.set_factor(
+                            (time_scale.factor() * step).clamp(MIN_TIME_SCALE, MAX_TIME_SCALE),
+                        );
+                        log::debug!("time scale {}", time_scale.factor());
+                        continue;
+                    }
+
+                    let times = if k.kind == KeyEventKind::Repeat
+                        && matches!(k.code, KeyCode::Up | KeyCode::Down)
+                    {
+                        held_repeats += 1;
+                        1 + held_repeats / SPEED_RAMP_REPEATS
+                    } else {
+                        1
+                    };
+                    for _ in 0..times {
+                        handle_key(&mut cloud, k.code, k.modifiers);
+                        if let Some(p) = panes.as_mut() {
+                            for c in &mut p.extra {
+                                handle_key(c, k.code, k.modifiers);
                             }
                         }
-                        (KeyCode::Tab, _) => {
-                            let sm = if matches!(cloud.shading_distance, true) {
-                                ShadingMode::Random
-                            } else {
-                                ShadingMode::DistanceFromHead
-                            };
-                            cloud.set_shading_mode(sm);
-                        }
-                        (KeyCode::Char('-'), _) => {
-                            let d = (cloud.droplet_density - 0.25).max(0.01);
-                            cloud.set_droplet_density(d);
-                        }
-                        (KeyCode::Char('+'), _) | (KeyCode::Char('='), KeyModifiers::SHIFT) => {
-                            let d = (cloud.droplet_density + 0.25).min(5.0);
-                            cloud.set_droplet_density(d);
-                        }
-                        (KeyCode::Char('1'), _) => cloud.set_color_scheme(ColorScheme::Green),
-                        (KeyCode::Char('2'), _) => cloud.set_color_scheme(ColorScheme::Green2),
-                        (KeyCode::Char('3'), _) => cloud.set_color_scheme(ColorScheme::Green3),
-                        (KeyCode::Char('4'), _) => cloud.set_color_scheme(ColorScheme::Gold),
-                        (KeyCode::Char('5'), _) => cloud.set_color_scheme(ColorScheme::Pink2),
-                        (KeyCode::Char('6'), _) => cloud.set_color_scheme(ColorScheme::Red),
-                        (KeyCode::Char('7'), _) => cloud.set_color_scheme(ColorScheme::Blue),
-                        (KeyCode::Char('8'), _) => cloud.set_color_scheme(ColorScheme::Cyan),
-                        (KeyCode::Char('9'), _) => cloud.set_color_scheme(ColorScheme::Purple),
-                        (KeyCode::Char('0'), _) => cloud.set_color_scheme(ColorScheme::Gray),
-                        (KeyCode::Char('!'), _) => cloud.set_color_scheme(ColorScheme::Rainbow),
-                        (KeyCode::Char('@'), _) => cloud.set_color_scheme(ColorScheme::Yellow),
-                        (KeyCode::Char('#'), _) => cloud.set_color_scheme(ColorScheme::Orange),
-                        (KeyCode::Char('$'), _) => cloud.set_color_scheme(ColorScheme::Pink),
-                        (KeyCode::Char('%'), _) => cloud.set_color_scheme(ColorScheme::Vaporwave),
-                        _ => {}
                     }
                 }
                 _ => {}
             }
         }
 
-        cloud.rain(&mut frame);
-        term.draw(&frame)?;
+        if settle_at.is_some_and(|t| std::time::Instant::now() >= t) && resize.is_none() {
+            settle_at = None;
+            let (nw, nh) = term.size()?;
+            if (nw, nh) != (screen.width, screen.height) {
+                resize = Some(term.resized(nw, nh)?);
+            }
+        }
+
+        if let Some((nw, nh)) = resize {
+            log::debug!("terminal resized to {}x{}", nw, nh);
+            match panes.as_mut() {
+                Some(p) => p.resize(&mut cloud, nw, nh * rows_per_cell),
+                None => cloud.reset(nw, nh * rows_per_cell),
+            }
+            frame.resize(nw, nh * rows_per_cell, cloud.palette.bg);
+            screen.resize(nw, nh, cloud.palette.bg);
+            if let Some(s) = scene.as_mut() {
+                s.resize(frame.width, frame.height);
+            }
+            cloud.force_draw_everything();
+        }
+
+        if let Some(schedule) = &auto_theme {
+            let due = last_theme_check
+                .map(|t| t.elapsed() >= Duration::from_secs(1))
+                .unwrap_or(true);
+            if due {
+                let scheme = schedule.current();
+                if scheme.is_some() && scheme != scheduled_scheme {
+                    scheduled_scheme = scheme;
+                    cloud.set_color_scheme(scheme.unwrap_or(color_scheme));
+                }
+                last_theme_check = Some(std::time::Instant::now());
+            }
+        }
+
+        if let Some(period) = shuffle_period {
+            // In a sync group every member switches at the same moment, to
+            // the same pick.
+            let due = match &sync_group {
+                Some(g) => {
+                    let i = g.period_index(period);
+                    let due = shuffle_index.is_some_and(|p| p != i);
+                    shuffle_index = Some(i);
+                    if due {
+                        shuffle_rng = SmallRng::seed_from_u64(i);
+                    }
+                    due
+                }
+                None => last_shuffle.elapsed() >= period,
+            };
+            if due {
+                let current = cloud.color_scheme();
+                let choices: Vec<ColorScheme> = ColorScheme::BUILTIN
+                    .iter()
+                    .copied()
+                    .filter(|&s| s != current)
+                    .collect();
+                if let Some(&scheme) = choices.choose(&mut shuffle_rng) {
+                    cloud.set_color_scheme(scheme);
+                }
+
+                if args.shuffle_charsets {
+                    let pool: Vec<Charset> = if def_ascii {
+                        Charset::ASCII_SHUFFLE.to_vec()
+                    } else {
+                        [
+                            Charset::ASCII_SHUFFLE.as_slice(),
+                            Charset::UNICODE_SHUFFLE.as_slice(),
+                        ]
+                        .concat()
+                    };
+                    if let Some(&cs) = pool.choose(&mut shuffle_rng) {
+                        cloud.init_chars(build_chars(cs, &[], def_ascii));
+                        cloud.force_draw_everything();
+                    }
+                }
+                last_shuffle = std::time::Instant::now();
+            }
+        }
+
+        // `--sleep-after`: fade to black over the last stretch, then stop as
+        // if quit with a key, so `--on-exit` runs.
+        if let Some(at) = sleep_at {
+            let left = at.saturating_duration_since(std::time::Instant::now());
+            if left.is_zero() {
+                log::info!("sleep timer ran out");
+                cloud.raining = false;
+            } else if left < sleep_dim {
+                let level = (left.as_secs_f32() / sleep_dim.as_secs_f32() * SLEEP_DIM_STEPS).ceil()
+                    / SLEEP_DIM_STEPS;
+                for c in std::iter::once(&mut cloud)
+                    .chain(panes.iter_mut().flat_map(|p| p.extra.iter_mut()))
+                {
+                    c.set_dim(level);
+                }
+            }
+        }
+
+        // Pause before the machine suspends, so no time passes in the rain,
+        // and repaint everything after it wakes.
+        #[cfg(all(feature = "logind", target_os = "linux"))]
+        if let Some(sleeping) = sleep_watch.as_ref().and_then(|w| w.try_recv()) {
+            log::debug!(
+                "logind: {}",
+                if sleeping {
+                    "going to sleep"
+                } else {
+                    "woke up"
+                }
+            );
+            if sleeping != paused_for_sleep && (!sleeping || !cloud.pause) {
+                paused_for_sleep = sleeping;
+                for c in std::iter::once(&mut cloud)
+                    .chain(panes.iter_mut().flat_map(|p| p.extra.iter_mut()))
+                {
+                    c.toggle_pause();
+                }
+            }
+            if !sleeping {
+                force_redraw(&mut cloud, &mut panes);
+                term.force_redraw();
+            }
+        }
+
+        if let Some(g) = &sync_group {
+            if let Some(scheme) = g.recv_scheme().filter(|&s| s != cloud.color_scheme()) {
+                for c in std::iter::once(&mut cloud)
+                    .chain(panes.iter_mut().flat_map(|p| p.extra.iter_mut()))
+                {
+                    c.set_color_scheme(scheme);
+                }
+                synced_scheme = scheme;
+            }
+            if cloud.color_scheme() != synced_scheme {
+                synced_scheme = cloud.color_scheme();
+                g.send_scheme(synced_scheme);
+            }
+        }
+
+        if let Some(pick) = demo
+            .as_mut()
+            .and_then(|d| d.next(scene_kind, cloud.color_scheme(), def_ascii))
+        {
+            transition = Some((frame.clone(), std::time::Instant::now()));
+            scene_kind = pick.scene;
+            scene = scene_kind.build(def_ascii);
+            if let Some(s) = scene.as_mut() {
+                s.resize(frame.width, frame.height);
+            }
+            cloud.set_color_scheme(pick.scheme);
+            cloud.init_chars(build_chars(pick.charset, &[], def_ascii));
+            cloud.set_chars_per_sec(pick.speed * rows_per_cell as f32);
+            log::debug!(
+                "demo: {} scene, {} scheme",
+                scene_kind.name(),
+                pick.scheme.name()
+            );
+        }
+        if transition.is_some() {
+            force_redraw(&mut cloud, &mut panes);
+        }
+
+        if let Some(m) = metrics.as_mut() {
+            let due = last_metrics_time
+                .map(|t| t.elapsed() >= metrics::SAMPLE_PERIOD)
+                .unwrap_or(true);
+            if due {
+                cloud.set_column_loads(&m.sample());
+                last_metrics_time = Some(std::time::Instant::now());
+            }
+        }
+
+        #[cfg(feature = "weather")]
+        if let Some(w) = weather_feed.as_ref().and_then(|f| f.try_recv()) {
+            apply_weather(
+                &mut cloud,
+                w,
+                color_scheme,
+                args.speed * rows_per_cell as f32,
+                args.density,
+            );
+        }
+
+        if let Some(f) = &scene_feed {
+            while let Some(values) = f.try_recv() {
+                if let Some(s) = scene.as_mut() {
+                    s.feed(&values);
+                }
+            }
+        }
+
+        if let Some(l) = &listener {
+            while let Some(text) = l.try_recv() {
+                cloud.inject_text(&text);
+            }
+        }
+
+        if let Some(t) = title_rain.as_mut().and_then(TitleRain::tick) {
+            term.set_title(&t)?;
+        }
+
+        let sim_start = std::time::Instant::now();
+        match (scene.as_mut(), panes.as_mut()) {
+            (Some(s), _) => {
+                if !cloud.pause {
+                    let now = cloud.now();
+                    cloud.update_fade(now);
+                    s.draw(&mut frame, &cloud.palette, now);
+                }
+            }
+            (None, Some(p)) => p.rain(&mut cloud, &mut frame),
+            (None, None) => cloud.rain(&mut frame),
+        }
+        if let Some((from, start)) = &transition {
+            let t = start.elapsed().as_secs_f32() / demo::TRANSITION.as_secs_f32();
+            if t >= 1.0 {
+                transition = None;
+                force_redraw(&mut cloud, &mut panes);
+            } else {
+                frame.dissolve_from(from, t);
+            }
+        }
+        let draw_start = std::time::Instant::now();
+        match backend {
+            Backend::Cell if args.hi_res => {
+                frame.pack_half_blocks(&mut screen);
+                if menu.open {
+                    menu.draw(&cloud, &mut screen);
+                }
+                term.draw(&screen)?;
+            }
+            Backend::Cell => {
+                if menu.open {
+                    menu.draw(&cloud, &mut frame);
+                }
+                term.draw(&frame)?
+            }
+            #[cfg(feature = "kitty")]
+            Backend::Kitty => {
+                canvas.render(&frame);
+                term.draw_raw(&kitty::encode(&canvas, screen.width, screen.height))?;
+            }
+            #[cfg(feature = "sixel")]
+            Backend::Sixel => {
+                canvas.render(&frame);
+                let (cw, ch) = terminal::cell_pixel_size().unwrap_or((10, 20));
+                term.draw_raw(&sixel::encode(&canvas, cw, ch))?;
+            }
+        }
 
         let cur = std::time::Instant::now();
+        if let Some(t) = telemetry.as_mut() {
+            let stats = term.last_draw();
+            t.record(&FrameRecord {
+                sim: draw_start.duration_since(sim_start),
+                draw: cur.duration_since(draw_start),
+                cells: stats.cells,
+                bytes: stats.bytes,
+            })?;
+        }
         let elapsed = cur.duration_since(prev);
         let calc_delay = if elapsed >= target_period {
             Duration::from_nanos(0)
         } else {
             target_period - elapsed
         };
+        frame_times.record(elapsed.saturating_sub(prev_delay));
 
         let cur_delay = (prev_delay.mul_f32(7.0) + calc_delay).div_f32(8.0);
         std::thread::sleep(cur_delay);
@@ -349,5 +1582,32 @@ fn main() -> std::io::Result<()> {
         prev_delay = cur_delay;
     }
 
+    #[cfg(feature = "kitty")]
+    if backend == Backend::Kitty {
+        term.draw_raw(&kitty::clear())?;
+    }
+
+    if args.save_config {
+        save_error = save_settings(&cloud, &menu, rows_per_cell).err();
+    }
+    drop(term);
+    log::info!("exiting");
+    // The hooks only follow an exit asked for with a key or the mouse.
+    if !cloud.raining && !terminal::interrupted() {
+        if let Some(cmd) = &args.on_exit {
+            screensaver::run_hook(cmd).map_err(|e| CosmostrixError::invalid("on-exit", e))?;
+        }
+        if args.lock {
+            screensaver::run_hook(screensaver::lock_command())
+                .map_err(|e| CosmostrixError::invalid("lock", e))?;
+        }
+    }
+    if let Some(t) = telemetry {
+        println!("{}", t.finish()?);
+    }
+    if let Some(e) = save_error {
+        eprintln!("failed to save settings: {}", e);
+    }
+
     Ok(())
 }
@@ -0,0 +1,153 @@
+// Copyright (c) 2025 rezk_nightky
+
+use clap::{Arg, Command, CommandFactory};
+
+use crate::config::Args;
+use crate::validate::choices;
+
+// Escapes text for roff: backslashes, hyphens (so they aren't turned into
+// dashes) and a leading `.` or `'` that would start a request.
+fn roff(s: &str) -> String {
+    let s = s.replace('\\', "\\e").replace('-', "\\-");
+    match s.starts_with('.') || s.starts_with('\'') {
+        true => format!("\\&{}", s),
+        false => s,
+    }
+}
+
+fn option(out: &mut String, a: &Arg) {
+    let Some(long) = a.get_long() else {
+        return;
+    };
+    let mut head = match a.get_short() {
+        Some(s) => format!("\\fB\\-{}\\fR, \\fB\\-\\-{}\\fR", s, roff(long)),
+        None => format!("\\fB\\-\\-{}\\fR", roff(long)),
+    };
+    if a.get_action().takes_values() {
+        let name = a
+            .get_value_names()
+            .and_then(|n| n.first())
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "VALUE".to_string());
+        let optional = a.get_num_args().is_some_and(|n| n.min_values() == 0);
+        head.push_str(&match optional {
+            true => format!(" [\\fI{}\\fR]", roff(&name)),
+            false => format!(" \\fI{}\\fR", roff(&name)),
+        });
+    }
+    out.push_str(".TP\n");
+    out.push_str(&head);
+    out.push('\n');
+    let mut text = a.get_help().map(|h| h.to_string()).unwrap_or_default();
+    let defaults: Vec<String> = a
+        .get_default_values()
+        .iter()
+        .map(|v| v.to_string_lossy().into_owned())
+        .collect();
+    if !defaults.is_empty() && a.get_action().takes_values() {
+        text.push_str(&format!(" (default: {})", defaults.join(",")));
+    }
+    out.push_str(&roff(&text));
+    out.push('\n');
+}
+
+fn list(out: &mut String, title: &str, intro: &str, names: &[&str]) {
+    out.push_str(&format!(".SH {}\n{}\n.PP\n", title, roff(intro)));
+    out.push_str(&roff(&names.join(", ")));
+    out.push('\n');
+}
+
+// `cosmostrix man`: the manual page in roff, built from the option table
+// so it lists every flag the parser knows about.
+pub fn page() -> String {
+    let mut cmd: Command = Args::command();
+    cmd.build();
+    let name = env!("CARGO_PKG_NAME");
+    let mut out = String::new();
+    out.push_str(&format!(
+        ".TH {} 1 \"\" \"{} {}\"\n",
+        name.to_ascii_uppercase(),
+        name,
+        env!("CARGO_PKG_VERSION")
+    ));
+    out.push_str(&format!(
+        ".SH NAME\n{} \\- {}\n",
+        name,
+        roff(env!("CARGO_PKG_DESCRIPTION"))
+    ));
+    out.push_str(&format!(
+        ".SH SYNOPSIS\n.B {}\n[\\fIOPTIONS\\fR] [\\fICOMMAND\\fR]\n",
+        name
+    ));
+    out.push_str(".SH DESCRIPTION\n");
+    out.push_str(&roff("Fills the terminal with falling columns of glyphs. Options can also be set in the config file; the command line wins."));
+    out.push('\n');
+
+    out.push_str(".SH OPTIONS\n");
+    for a in cmd
+        .get_arguments()
+        .filter(|a| !a.is_hide_set() && !a.is_positional())
+    {
+        option(&mut out, a);
+    }
+
+    out.push_str(".SH COMMANDS\n");
+    for sub in cmd
+        .get_subcommands()
+        .filter(|c| !c.is_hide_set() && c.get_name() != "help")
+    {
+        let mut usage = format!("\\fB{}\\fR", sub.get_name());
+        for p in sub.get_positionals() {
+            let n = p
+                .get_value_names()
+                .and_then(|n| n.first())
+                .map(|n| n.to_string())
+                .unwrap_or_default();
+            usage.push_str(&format!(" \\fI{}\\fR", roff(&n)));
+        }
+        out.push_str(&format!(".TP\n{}\n", usage));
+        out.push_str(&roff(
+            &sub.get_about().map(|a| a.to_string()).unwrap_or_default(),
+        ));
+        out.push('\n');
+        // Options are global, so a command only lists its own.
+        let args: Vec<&Arg> = sub
+            .get_arguments()
+            .filter(|a| {
+                !a.is_hide_set()
+                    && !a.is_positional()
+                    && !a.is_global_set()
+                    && a.get_long() != Some("help")
+            })
+            .collect();
+        if !args.is_empty() {
+            out.push_str(".RS\n");
+            for a in args {
+                option(&mut out, a);
+            }
+            out.push_str(".RE\n");
+        }
+    }
+
+    list(
+        &mut out,
+        "CHARSETS",
+        "Names accepted by --charset:",
+        &choices("charset"),
+    );
+    list(
+        &mut out,
+        "COLOR SCHEMES",
+        "Names accepted by --color (gray also accepts grey):",
+        &choices("color"),
+    );
+    out.push_str(".SH FILES\n.TP\n\\fI~/.config/cosmostrix/config.toml\\fR\n");
+    out.push_str(&roff("Default options, one key = value per line using the long option name (also under $XDG_CONFIG_HOME)."));
+    out.push('\n');
+    out.push_str(".SH ENVIRONMENT\n.TP\n\\fBNO_COLOR\\fR\n");
+    out.push_str(&roff(
+        "When set and not empty, draw without colors unless --force-color or --colormode is given.",
+    ));
+    out.push('\n');
+    out
+}
@@ -0,0 +1,251 @@
+// Copyright (c) 2025 rezk_nightky
+
+use crossterm::event::KeyCode;
+
+use crate::cell::Cell;
+use crate::charset::{build_chars, Charset};
+use crate::cloud::Cloud;
+use crate::frame::{Blend, Frame};
+use crate::runtime::{BoldMode, ColorScheme};
+
+const WIDTH: u16 = 36;
+const HINT: &str = "Up/Down select  Left/Right change";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Item {
+    Speed,
+    Density,
+    Scheme,
+    Charset,
+    Glitch,
+    Bold,
+}
+
+const ITEMS: [Item; 6] = [
+    Item::Speed,
+    Item::Density,
+    Item::Scheme,
+    Item::Charset,
+    Item::Glitch,
+    Item::Bold,
+];
+
+impl Item {
+    fn label(self) -> &'static str {
+        match self {
+            Item::Speed => "Speed",
+            Item::Density => "Density",
+            Item::Scheme => "Scheme",
+            Item::Charset => "Charset",
+            Item::Glitch => "Glitch",
+            Item::Bold => "Bold",
+        }
+    }
+}
+
+pub fn bold_name(mode: BoldMode) -> &'static str {
+    match mode {
+        BoldMode::Off => "off",
+        BoldMode::Random => "random",
+        BoldMode::All => "all",
+    }
+}
+
+// Steps one setting up (`dir > 0`) or down, with the same increments as the
+// hotkeys. Charsets are not tracked by the cloud, so `Menu` steps those.
+pub fn adjust(cloud: &mut Cloud, item: Item, dir: i32) {
+    match item {
+        Item::Speed => {
+            let mut cps = cloud.chars_per_sec;
+            if dir > 0 {
+                if cps <= 0.5 {
+                    cps *= 2.0;
+                } else {
+                    cps += 1.0;
+                }
+                cloud.set_chars_per_sec(cps.min(1000.0));
+            } else {
+                if cps <= 1.0 {
+                    cps /= 2.0;
+                } else {
+                    cps -= 1.0;
+                }
+                cloud.set_chars_per_sec(cps.max(0.001));
+            }
+        }
+        Item::Density => {
+            let d = (cloud.droplet_density + 0.25 * dir as f32).clamp(0.01, 5.0);
+            cloud.set_droplet_density(d);
+        }
+        Item::Scheme => {
+            let all = ColorScheme::BUILTIN;
+            let n = all.len() as i32;
+            let cur = all.iter().position(|&s| s == cloud.color_scheme());
+            let next = match cur {
+                Some(i) => (i as i32 + dir).rem_euclid(n),
+                None => 0,
+            };
+            cloud.set_color_scheme(all[next as usize]);
+        }
+        Item::Glitch if cloud.glitchy => {
+            let gp = (cloud.glitch_pct + 0.05 * dir as f32).clamp(0.0, 1.0);
+            cloud.set_glitch_pct(gp);
+        }
+        Item::Bold => {
+            let modes = [BoldMode::Off, BoldMode::Random, BoldMode::All];
+            let cur = modes
+                .iter()
+                .position(|&m| m == cloud.bold_mode)
+                .unwrap_or(1) as i32;
+            cloud.set_bold_mode(modes[(cur + dir).rem_euclid(3) as usize]);
+        }
+        Item::Charset | Item::Glitch => {}
+    }
+}
+
+// The `m` overlay: a box of settings drawn over the rain, which keeps
+// running behind it so every change previews live.
+pub struct Menu {
+    pub open: bool,
+    selected: usize,
+    charsets: Vec<(&'static str, Charset)>,
+    charset: Option<usize>,
+    charset_label: String,
+    def_ascii: bool,
+}
+
+impl Menu {
+    pub fn new(charset: &str, def_ascii: bool) -> Self {
+        let charsets: Vec<_> = Charset::NAMED
+            .iter()
+            .copied()
+            .filter(|(_, cs)| !def_ascii || cs.is_ascii())
+            .collect();
+        let current = charset.trim().to_ascii_lowercase();
+        Self {
+            open: false,
+            selected: 0,
+            charset: charsets.iter().position(|(name, _)| *name == current),
+            charsets,
+            charset_label: current,
+            def_ascii,
+        }
+    }
+
+    pub fn charset_name(&self) -> &str {
+        match self.charset {
+            Some(i) => self.charsets[i].0,
+            None => &self.charset_label,
+        }
+    }
+
+    // Handles a key while the menu is open, applying changes to every cloud;
+    // returns false for keys it leaves to the normal controls.
+    pub fn handle_key<'a>(
+        &mut self,
+        code: KeyCode,
+        clouds: impl Iterator<Item = &'a mut Cloud>,
+    ) -> bool {
+        let dir = match code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('m') => {
+                self.open = false;
+                return true;
+            }
+            KeyCode::Up => {
+                self.selected = (self.selected + ITEMS.len() - 1) % ITEMS.len();
+                return true;
+            }
+            KeyCode::Down => {
+                self.selected = (self.selected + 1) % ITEMS.len();
+                return true;
+            }
+            KeyCode::Left => -1,
+            KeyCode::Right => 1,
+            _ => return false,
+        };
+
+        let item = ITEMS[self.selected];
+        if item == Item::Charset {
+            let n = self.charsets.len() as i32;
+            let next = match self.charset {
+                Some(i) => (i as i32 + dir).rem_euclid(n) as usize,
+                None => 0,
+            };
+            self.charset = Some(next);
+            let chars = build_chars(self.charsets[next].1, &[], self.def_ascii);
+            for c in clouds {
+                c.init_chars(chars.clone());
+                c.force_draw_everything();
+            }
+        } else {
+            for c in clouds {
+                adjust(c, item, dir);
+            }
+        }
+        true
+    }
+
+    fn value(&self, cloud: &Cloud, item: Item) -> String {
+        match item {
+            Item::Speed => format!("{:.1}", cloud.chars_per_sec),
+            Item::Density => format!("{:.2}", cloud.droplet_density),
+            Item::Scheme => cloud.color_scheme().name().to_string(),
+            Item::Charset => self.charset_name().to_string(),
+            Item::Glitch if cloud.glitchy => format!("{:.0}%", cloud.glitch_pct * 100.0),
+            Item::Glitch => "off".to_string(),
+            Item::Bold => bold_name(cloud.bold_mode).to_string(),
+        }
+    }
+
+    pub fn draw(&self, cloud: &Cloud, frame: &mut Frame) {
+        let colors = &cloud.palette.colors;
+        let bg = cloud.palette.bg;
+        let bright = colors.last().copied();
+        let dim = colors.get(colors.len() / 2).copied().or(bright);
+
+        let mut lines = vec![(String::new(), false)];
+        for (i, &item) in ITEMS.iter().enumerate() {
+            let marker = if i == self.selected { '>' } else { ' ' };
+            let value = format!("< {} >", self.value(cloud, item));
+            lines.push((
+                format!(" {} {:<10}{:>20} ", marker, item.label(), value),
+                i == self.selected,
+            ));
+        }
+        lines.push((String::new(), false));
+        lines.push((format!("{:^34}", HINT), false));
+
+        let height = lines.len() as u16 + 2;
+        let x0 = frame.width.saturating_sub(WIDTH) / 2;
+        let y0 = frame.height.saturating_sub(height) / 2;
+        let mut panel = Frame::new(WIDTH, height, bg);
+        let mut put = |x: u16, y: u16, ch: char, fg, bold| {
+            panel.set(x, y, Cell { ch, fg, bg, bold });
+        };
+
+        for x in 0..WIDTH {
+            let (top, bottom) = match x {
+                0 => ('\u{250c}', '\u{2514}'),
+                _ if x == WIDTH - 1 => ('\u{2510}', '\u{2518}'),
+                _ => ('\u{2500}', '\u{2500}'),
+            };
+            put(x, 0, top, bright, false);
+            put(x, height - 1, bottom, bright, false);
+        }
+        for (x, ch) in " Settings ".chars().enumerate() {
+            put(2 + x as u16, 0, ch, bright, true);
+        }
+        for (row, (text, selected)) in lines.iter().enumerate() {
+            let y = row as u16 + 1;
+            put(0, y, '\u{2502}', bright, false);
+            put(WIDTH - 1, y, '\u{2502}', bright, false);
+            let chars: Vec<char> = text.chars().collect();
+            for x in 1..WIDTH - 1 {
+                let ch = chars.get(x as usize - 1).copied().unwrap_or(' ');
+                let fg = if *selected { bright } else { dim };
+                put(x, y, ch, fg, *selected);
+            }
+        }
+        frame.composite(&panel, x0, y0, Blend::Overwrite);
+    }
+}
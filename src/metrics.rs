@@ -0,0 +1,145 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use crate::runtime::MetricsMode;
+
+pub const SAMPLE_PERIOD: Duration = Duration::from_secs(1);
+
+pub struct Metrics {
+    mode: MetricsMode,
+    prev_cpu: Vec<(u64, u64)>,
+    prev_net: Option<(u64, u64, Instant)>,
+    net_peak: f64,
+}
+
+impl Metrics {
+    pub fn new(mode: MetricsMode) -> Self {
+        Self {
+            mode,
+            prev_cpu: Vec::new(),
+            prev_net: None,
+            net_peak: 1.0,
+        }
+    }
+
+    // One load in 0..=1 per band (cpu core, rx/tx); empty when unavailable.
+    pub fn sample(&mut self) -> Vec<f32> {
+        match self.mode {
+            MetricsMode::Cpu => self.sample_cpu(),
+            MetricsMode::Mem => sample_mem().map(|v| vec![v]).unwrap_or_default(),
+            MetricsMode::Net => self.sample_net(),
+        }
+    }
+
+    fn sample_cpu(&mut self) -> Vec<f32> {
+        let Ok(content) = fs::read_to_string("/proc/stat") else {
+            return Vec::new();
+        };
+
+        let mut cur: Vec<(u64, u64)> = Vec::new();
+        for line in content.lines() {
+            let mut parts = line.split_whitespace();
+            let Some(name) = parts.next() else {
+                continue;
+            };
+            if !name.starts_with("cpu") || name == "cpu" {
+                continue;
+            }
+            let vals: Vec<u64> = parts.filter_map(|v| v.parse().ok()).collect();
+            if vals.len() < 4 {
+                continue;
+            }
+            let idle = vals[3] + vals.get(4).copied().unwrap_or(0);
+            let total: u64 = vals.iter().sum();
+            cur.push((idle, total));
+        }
+
+        let loads = if self.prev_cpu.len() == cur.len() {
+            cur.iter()
+                .zip(&self.prev_cpu)
+                .map(|(&(idle, total), &(pidle, ptotal))| {
+                    let dt = total.saturating_sub(ptotal);
+                    if dt == 0 {
+                        return 0.0;
+                    }
+                    let di = idle.saturating_sub(pidle).min(dt);
+                    (1.0 - di as f32 / dt as f32).clamp(0.0, 1.0)
+                })
+                .collect()
+        } else {
+            vec![0.0; cur.len()]
+        };
+
+        self.prev_cpu = cur;
+        loads
+    }
+
+    fn sample_net(&mut self) -> Vec<f32> {
+        let Ok(content) = fs::read_to_string("/proc/net/dev") else {
+            return Vec::new();
+        };
+
+        let mut rx = 0u64;
+        let mut tx = 0u64;
+        for line in content.lines().skip(2) {
+            let Some((iface, rest)) = line.split_once(':') else {
+                continue;
+            };
+            if iface.trim() == "lo" {
+                continue;
+            }
+            let vals: Vec<u64> = rest
+                .split_whitespace()
+                .filter_map(|v| v.parse().ok())
+                .collect();
+            if vals.len() < 9 {
+                continue;
+            }
+            rx = rx.saturating_add(vals[0]);
+            tx = tx.saturating_add(vals[8]);
+        }
+
+        let now = Instant::now();
+        let loads = match self.prev_net {
+            Some((prx, ptx, pt)) => {
+                let secs = now.saturating_duration_since(pt).as_secs_f64().max(0.001);
+                let rx_rate = rx.saturating_sub(prx) as f64 / secs;
+                let tx_rate = tx.saturating_sub(ptx) as f64 / secs;
+                self.net_peak = self.net_peak.max(rx_rate).max(tx_rate);
+                vec![
+                    (rx_rate / self.net_peak) as f32,
+                    (tx_rate / self.net_peak) as f32,
+                ]
+            }
+            None => vec![0.0, 0.0],
+        };
+
+        self.prev_net = Some((rx, tx, now));
+        loads
+    }
+}
+
+fn sample_mem() -> Option<f32> {
+    let content = fs::read_to_string("/proc/meminfo").ok()?;
+    let mut total = None;
+    let mut avail = None;
+    for line in content.lines() {
+        let Some((key, rest)) = line.split_once(':') else {
+            continue;
+        };
+        let val = rest
+            .split_whitespace()
+            .next()
+            .and_then(|v| v.parse::<u64>().ok());
+        match key {
+            "MemTotal" => total = val,
+            "MemAvailable" => avail = val,
+            _ => {}
+        }
+    }
+    let total = total.filter(|&t| t > 0)? as f32;
+    let avail = avail? as f32;
+    Some((1.0 - avail / total).clamp(0.0, 1.0))
+}
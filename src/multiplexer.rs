@@ -0,0 +1,103 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::env;
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::runtime::ColorMode;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Multiplexer {
+    Tmux,
+    Screen,
+}
+
+// tmux can report a size before the pane has settled, so the size is read
+// again this long after a resize.
+pub const RESIZE_SETTLE: Duration = Duration::from_millis(250);
+
+// GNU screen drops passthrough strings longer than this.
+const SCREEN_CHUNK: usize = 512;
+
+pub fn detect() -> Option<Multiplexer> {
+    static DETECTED: OnceLock<Option<Multiplexer>> = OnceLock::new();
+    *DETECTED.get_or_init(|| {
+        if env::var_os("TMUX").is_some() {
+            Some(Multiplexer::Tmux)
+        } else if env::var_os("STY").is_some() {
+            Some(Multiplexer::Screen)
+        } else {
+            None
+        }
+    })
+}
+
+// Wraps `seq` so the multiplexer hands it to the outer terminal unchanged
+// instead of interpreting (or dropping) it. tmux only forwards it with
+// `set -g allow-passthrough on`.
+pub fn passthrough(seq: &str) -> String {
+    match detect() {
+        None => seq.to_string(),
+        Some(Multiplexer::Tmux) => format!("\x1bPtmux;{}\x1b\\", seq.replace('\x1b', "\x1b\x1b")),
+        Some(Multiplexer::Screen) => {
+            let mut out = String::new();
+            let mut rest = seq;
+            while !rest.is_empty() {
+                let mut end = rest.len().min(SCREEN_CHUNK);
+                while !rest.is_char_boundary(end) {
+                    end -= 1;
+                }
+                out.push_str("\x1bP");
+                out.push_str(&rest[..end]);
+                out.push_str("\x1b\\");
+                rest = &rest[end..];
+            }
+            out
+        }
+    }
+}
+
+// The most colors the outer terminal can show. tmux knows what its client
+// supports; screen (4.x) passes at most 256 colors through.
+fn outer_color_limit(m: Multiplexer) -> ColorMode {
+    match m {
+        Multiplexer::Screen => ColorMode::Color256,
+        Multiplexer::Tmux => {
+            let features = Command::new("tmux")
+                .args(["display-message", "-p", "#{client_termfeatures}"])
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| String::from_utf8_lossy(&o.stdout).into_owned());
+            match features {
+                Some(f) if f.split(',').any(|x| x.trim() == "RGB") => ColorMode::TrueColor,
+                _ => ColorMode::Color256,
+            }
+        }
+    }
+}
+
+fn rank(mode: ColorMode) -> u8 {
+    match mode {
+        ColorMode::Mono => 0,
+        ColorMode::Color8 => 1,
+        ColorMode::Color16 => 2,
+        ColorMode::Color256 => 3,
+        ColorMode::TrueColor => 4,
+    }
+}
+
+// Lowers `mode` to what reaches the screen through the multiplexer, if any.
+pub fn cap_color_mode(mode: ColorMode) -> ColorMode {
+    let Some(m) = detect() else {
+        return mode;
+    };
+    let limit = outer_color_limit(m);
+    if rank(mode) > rank(limit) {
+        log::debug!("{:?}: limiting colors to {:?}", m, limit);
+        limit
+    } else {
+        mode
+    }
+}
@@ -1,8 +1,7 @@
 // Copyright (c) 2025 rezk_nightky
 
-use crossterm::style::Color;
-
-use crate::runtime::{ColorMode, ColorScheme, UserColors};
+use crate::cell::Color;
+use crate::runtime::{ColorMode, ColorOverride, ColorScheme, UserColor, UserColors};
 
 #[derive(Clone, Debug)]
 pub struct Palette {
@@ -10,29 +9,484 @@ pub struct Palette {
     pub bg: Option<Color>,
 }
 
+// Where droplets get their colors. `sample` picks a color by position along
+// the ramp, 0 at the dim tail end and 1 at the head, `t` seconds into the
+// rain, so a source can be procedural instead of a fixed list.
+pub trait PaletteSource: Send + Sync {
+    fn sample(&self, pos: f32, t: f32) -> Option<Color>;
+    fn bg(&self) -> Option<Color>;
+    // How many distinct colors the ramp has, for callers that step along it.
+    fn steps(&self) -> usize;
+
+    // Whether `sample` changes with `t`, so the screen needs repainting as
+    // time passes.
+    fn is_animated(&self) -> bool {
+        false
+    }
+
+    // Called with the cloud's scheme palette before each frame, for sources
+    // that vary it rather than replace it.
+    fn set_base(&mut self, _base: &Palette) {}
+
+    fn box_clone(&self) -> Box<dyn PaletteSource>;
+}
+
+impl Clone for Box<dyn PaletteSource> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+impl PaletteSource for Palette {
+    fn sample(&self, pos: f32, _t: f32) -> Option<Color> {
+        let last = self.colors.len().checked_sub(1)?;
+        let i = (pos.clamp(0.0, 1.0) * last as f32).round() as usize;
+        self.colors.get(i).copied()
+    }
+
+    fn bg(&self) -> Option<Color> {
+        self.bg
+    }
+
+    fn steps(&self) -> usize {
+        self.colors.len()
+    }
+
+    fn box_clone(&self) -> Box<dyn PaletteSource> {
+        Box::new(self.clone())
+    }
+}
+
+// `--hue-cycle`: the scheme's colors with their hue turned full circle
+// every `period` seconds. Below 256 colors there are no hues to turn
+// through, and the scheme shows as it is.
+#[derive(Clone, Debug)]
+pub struct HueCycle {
+    base: Palette,
+    period: f32,
+    mode: ColorMode,
+}
+
+impl HueCycle {
+    pub fn new(period: f32, mode: ColorMode) -> Self {
+        Self {
+            base: Palette {
+                colors: Vec::new(),
+                bg: None,
+            },
+            period: period.max(f32::EPSILON),
+            mode,
+        }
+    }
+}
+
+impl PaletteSource for HueCycle {
+    fn sample(&self, pos: f32, t: f32) -> Option<Color> {
+        let c = self.base.sample(pos, t)?;
+        let Some((r, g, b)) = color_to_rgb(c)
+            .filter(|_| matches!(self.mode, ColorMode::TrueColor | ColorMode::Color256))
+        else {
+            return Some(c);
+        };
+        let (r, g, b) = rotate_hue((r, g, b), (t / self.period).fract() * 360.0);
+        Some(match self.mode {
+            ColorMode::TrueColor => Color::Rgb { r, g, b },
+            _ => Color::AnsiValue(rgb_to_ansi256(r, g, b)),
+        })
+    }
+
+    fn bg(&self) -> Option<Color> {
+        self.base.bg
+    }
+
+    fn steps(&self) -> usize {
+        self.base.steps()
+    }
+
+    fn is_animated(&self) -> bool {
+        true
+    }
+
+    fn set_base(&mut self, base: &Palette) {
+        self.base.clone_from(base);
+    }
+
+    fn box_clone(&self) -> Box<dyn PaletteSource> {
+        Box::new(self.clone())
+    }
+}
+
+// Turns an RGB color `degrees` around the hue circle, keeping its
+// saturation and brightness.
+fn rotate_hue((r, g, b): (u8, u8, u8), degrees: f32) -> (u8, u8, u8) {
+    let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    if chroma <= 0.0 {
+        let v = (max * 255.0).round() as u8;
+        return (v, v, v);
+    }
+    let hue = if max == r {
+        ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    } * 60.0;
+    let h = (hue + degrees).rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let v = |c: f32| ((c + min) * 255.0).round() as u8;
+    (v(r), v(g), v(b))
+}
+
 fn rgb_from_1000(r: u16, g: u16, b: u16) -> Color {
     let rr = ((r as u32).saturating_mul(255) / 1000) as u8;
     let gg = ((g as u32).saturating_mul(255) / 1000) as u8;
     let bb = ((b as u32).saturating_mul(255) / 1000) as u8;
-    Color::Rgb { r: rr, g: gg, b: bb }
+    Color::Rgb {
+        r: rr,
+        g: gg,
+        b: bb,
+    }
+}
+
+const ANSI16_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (128, 0, 0),
+    (0, 128, 0),
+    (128, 128, 0),
+    (0, 0, 128),
+    (128, 0, 128),
+    (0, 128, 128),
+    (192, 192, 192),
+    (128, 128, 128),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (0, 0, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+fn ansi_to_rgb(v: u8) -> (u8, u8, u8) {
+    match v {
+        0..=15 => ANSI16_RGB[v as usize],
+        16..=231 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let i = v - 16;
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let g = 8 + (v - 232) * 10;
+            (g, g, g)
+        }
+    }
+}
+
+pub fn color_to_rgb(c: Color) -> Option<(u8, u8, u8)> {
+    let idx = match c {
+        Color::Rgb { r, g, b } => return Some((r, g, b)),
+        Color::AnsiValue(v) => return Some(ansi_to_rgb(v)),
+        Color::Black => 0,
+        Color::DarkRed => 1,
+        Color::DarkGreen => 2,
+        Color::DarkYellow => 3,
+        Color::DarkBlue => 4,
+        Color::DarkMagenta => 5,
+        Color::DarkCyan => 6,
+        Color::Grey => 7,
+        Color::DarkGrey => 8,
+        Color::Red => 9,
+        Color::Green => 10,
+        Color::Yellow => 11,
+        Color::Blue => 12,
+        Color::Magenta => 13,
+        Color::Cyan => 14,
+        Color::White => 15,
+        Color::Reset => return None,
+    };
+    Some(ANSI16_RGB[idx])
+}
+
+pub fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let to_level = |v: u8| {
+        if v < 48 {
+            0
+        } else if v < 115 {
+            1
+        } else {
+            (v - 35) / 40
+        }
+    };
+    let (lr, lg, lb) = (to_level(r), to_level(g), to_level(b));
+    let cube = 16 + 36 * lr + 6 * lg + lb;
+
+    let avg = (r as u16 + g as u16 + b as u16) / 3;
+    let gray = if avg > 238 {
+        255
+    } else {
+        232 + (avg.saturating_sub(3) / 10) as u8
+    };
+
+    let dist = |c: u8| {
+        let (cr, cg, cb) = ansi_to_rgb(c);
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(cr, r) + d(cg, g) + d(cb, b)
+    };
+    if dist(gray) < dist(cube) {
+        gray
+    } else {
+        cube
+    }
+}
+
+fn rgb_to_ansi16(r: u8, g: u8, b: u8) -> Color {
+    const NAMED: [Color; 16] = [
+        Color::Black,
+        Color::DarkRed,
+        Color::DarkGreen,
+        Color::DarkYellow,
+        Color::DarkBlue,
+        Color::DarkMagenta,
+        Color::DarkCyan,
+        Color::Grey,
+        Color::DarkGrey,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+    ];
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+    let best = ANSI16_RGB
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, &(cr, cg, cb))| d(cr, r) + d(cg, g) + d(cb, b))
+        .map(|(i, _)| i)
+        .unwrap_or(15);
+    NAMED[best]
+}
+
+fn to_color8(c: Color) -> Color {
+    let c = match c {
+        Color::AnsiValue(v) => {
+            let (r, g, b) = ansi_to_rgb(v);
+            rgb_to_ansi16(r, g, b)
+        }
+        Color::Rgb { r, g, b } => rgb_to_ansi16(r, g, b),
+        c => c,
+    };
+    match c {
+        Color::DarkGrey => Color::Black,
+        Color::Red => Color::DarkRed,
+        Color::Green => Color::DarkGreen,
+        Color::Yellow => Color::DarkYellow,
+        Color::Blue => Color::DarkBlue,
+        Color::Magenta => Color::DarkMagenta,
+        Color::Cyan => Color::DarkCyan,
+        Color::White => Color::Grey,
+        c => c,
+    }
+}
+
+pub fn resolve_color(spec: ColorOverride, mode: ColorMode) -> Option<Color> {
+    match (spec, mode) {
+        (_, ColorMode::Mono) => None,
+        (spec, ColorMode::Color8) => resolve_color(spec, ColorMode::Color16).map(to_color8),
+        (ColorOverride::Index(i), ColorMode::Color16) => {
+            let (r, g, b) = ansi_to_rgb(i);
+            Some(rgb_to_ansi16(r, g, b))
+        }
+        (ColorOverride::Index(i), _) => Some(Color::AnsiValue(i)),
+        (ColorOverride::Rgb(r, g, b), ColorMode::TrueColor) => Some(Color::Rgb { r, g, b }),
+        (ColorOverride::Rgb(r, g, b), ColorMode::Color256) => {
+            Some(Color::AnsiValue(rgb_to_ansi256(r, g, b)))
+        }
+        (ColorOverride::Rgb(r, g, b), ColorMode::Color16) => Some(rgb_to_ansi16(r, g, b)),
+    }
+}
+
+pub fn apply_light(
+    palette: &mut Palette,
+    scheme: ColorScheme,
+    mode: ColorMode,
+    default_background: bool,
+) {
+    if scheme != ColorScheme::User {
+        palette.colors.reverse();
+    }
+    if !default_background {
+        palette.bg = Some(match mode {
+            ColorMode::Color8 => Color::Grey,
+            ColorMode::Color16 => Color::White,
+            ColorMode::TrueColor => Color::Rgb {
+                r: 255,
+                g: 255,
+                b: 255,
+            },
+            _ => Color::AnsiValue(231),
+        });
+    }
+}
+
+pub fn apply_overrides(
+    palette: &mut Palette,
+    mode: ColorMode,
+    head: Option<ColorOverride>,
+    tail: Option<ColorOverride>,
+) {
+    if let Some(c) = head.and_then(|h| resolve_color(h, mode)) {
+        if let Some(last) = palette.colors.last_mut() {
+            *last = c;
+        }
+    }
+    if let Some(c) = tail.and_then(|t| resolve_color(t, mode)) {
+        if palette.colors.len() < 2 {
+            palette.colors.insert(0, c);
+        } else {
+            palette.colors[0] = c;
+        }
+    }
+}
+
+// Darkens the palette toward black: `level` 1 leaves it as it is, 0 makes
+// it black. 16-color and mono palettes have no shades to step through and
+// are left alone.
+pub fn dim_palette(palette: &mut Palette, level: f32, mode: ColorMode) {
+    let level = level.clamp(0.0, 1.0);
+    if level >= 1.0 {
+        return;
+    }
+    let dim = |c: Color| {
+        let Some((r, g, b)) = color_to_rgb(c) else {
+            return c;
+        };
+        let f = |v: u8| (v as f32 * level).round() as u8;
+        match mode {
+            ColorMode::TrueColor => Color::Rgb {
+                r: f(r),
+                g: f(g),
+                b: f(b),
+            },
+            ColorMode::Color256 => Color::AnsiValue(rgb_to_ansi256(f(r), f(g), f(b))),
+            _ => c,
+        }
+    };
+    for c in &mut palette.colors {
+        *c = dim(*c);
+    }
+    palette.bg = palette.bg.map(dim);
+}
+
+pub fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    match (color_to_rgb(a), color_to_rgb(b)) {
+        (Some((ar, ag, ab)), Some((br, bg, bb))) => {
+            let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+            Color::Rgb {
+                r: mix(ar, br),
+                g: mix(ag, bg),
+                b: mix(ab, bb),
+            }
+        }
+        _ => b,
+    }
+}
+
+pub fn blend_palettes(from: &Palette, to: &Palette, t: f32) -> Palette {
+    let t = t.clamp(0.0, 1.0);
+    let n = to.colors.len();
+    let m = from.colors.len();
+    let colors = to
+        .colors
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            let j = if n <= 1 || m == 0 {
+                0
+            } else {
+                (i * (m - 1) + (n - 1) / 2) / (n - 1)
+            };
+            match from.colors.get(j) {
+                Some(&f) => lerp_color(f, c, t),
+                None => c,
+            }
+        })
+        .collect();
+    let bg = match (from.bg, to.bg) {
+        (Some(f), Some(c)) => Some(lerp_color(f, c, t)),
+        _ => to.bg,
+    };
+    Palette { colors, bg }
+}
+
+fn user_color(c: &UserColor, mode: ColorMode) -> Color {
+    match (mode, c.rgb_1000) {
+        (ColorMode::TrueColor, Some((r, g, b))) => rgb_from_1000(r, g, b),
+        _ => Color::AnsiValue(c.index),
+    }
+}
+
+// Backgrounds tinted to suit a scheme. Only truecolor can show tints this
+// dark; the nearest 256-color entries would clash with the droplet ramps.
+fn scheme_bg(scheme: ColorScheme) -> Option<Color> {
+    let (r, g, b) = match scheme {
+        ColorScheme::Blue => (0, 6, 30),
+        ColorScheme::Cyan => (0, 14, 20),
+        ColorScheme::Purple => (12, 8, 30),
+        ColorScheme::Vaporwave => (22, 4, 34),
+        ColorScheme::Red => (18, 0, 2),
+        _ => return None,
+    };
+    Some(Color::Rgb { r, g, b })
 }
 
 fn from_ansi_list(list: &[u8]) -> Vec<Color> {
     list.iter().map(|&v| Color::AnsiValue(v)).collect()
 }
 
+// The colors of `scheme`, as a source the cloud can sample.
 pub fn build_palette(
     scheme: ColorScheme,
     mode: ColorMode,
     default_background: bool,
     user: Option<&UserColors>,
+) -> Box<dyn PaletteSource> {
+    Box::new(scheme_palette(scheme, mode, default_background, user))
+}
+
+// The fixed color list of `scheme`, for callers that adjust or list it.
+pub fn scheme_palette(
+    scheme: ColorScheme,
+    mode: ColorMode,
+    default_background: bool,
+    user: Option<&UserColors>,
 ) -> Palette {
+    if mode == ColorMode::Color8 {
+        let p = scheme_palette(scheme, ColorMode::Color16, default_background, user);
+        return Palette {
+            colors: p.colors.into_iter().map(to_color8).collect(),
+            bg: p.bg.map(to_color8),
+        };
+    }
+
     let mut bg = if default_background {
         None
     } else {
         Some(match mode {
             ColorMode::Color16 => Color::Black,
-            ColorMode::TrueColor => Color::Rgb { r: 0, g: 0, b: 0 },
+            ColorMode::TrueColor => scheme_bg(scheme).unwrap_or(Color::Rgb { r: 0, g: 0, b: 0 }),
             _ => Color::AnsiValue(16),
         })
     };
@@ -40,44 +494,21 @@ pub fn build_palette(
     let colors: Vec<Color> = match scheme {
         ColorScheme::User => {
             if let Some(u) = user {
-                if !u.colors.is_empty() {
-                    if !default_background {
-                        bg = Some(match mode {
-                            ColorMode::TrueColor => {
-                                if let Some((r, g, b)) = u.colors[0].rgb_1000 {
-                                    rgb_from_1000(r, g, b)
-                                } else {
-                                    Color::AnsiValue(u.colors[0].index)
-                                }
-                            }
-                            _ => Color::AnsiValue(u.colors[0].index),
-                        });
+                if !default_background {
+                    if let Some(c) = &u.bg {
+                        bg = Some(user_color(c, mode));
                     }
+                }
 
-                    let mut out = Vec::new();
-                    for (i, c) in u.colors.iter().enumerate() {
-                        if i == 0 {
-                            continue;
-                        }
-                        out.push(match mode {
-                            ColorMode::TrueColor => {
-                                if let Some((r, g, b)) = c.rgb_1000 {
-                                    rgb_from_1000(r, g, b)
-                                } else {
-                                    Color::AnsiValue(c.index)
-                                }
-                            }
-                            _ => Color::AnsiValue(c.index),
-                        });
-                    }
+                let mut out: Vec<Color> = u.colors.iter().map(|c| user_color(c, mode)).collect();
+                if let Some(c) = &u.head {
+                    out.push(user_color(c, mode));
+                }
 
-                    if out.is_empty() {
-                        out.push(Color::Green);
-                    }
-                    out
-                } else {
-                    vec![Color::Green]
+                if out.is_empty() {
+                    out.push(Color::Green);
                 }
+                out
             } else {
                 vec![Color::Green]
             }
@@ -89,7 +520,12 @@ pub fn build_palette(
         },
         ColorScheme::Green2 => match mode {
             ColorMode::Mono => vec![Color::White],
-            ColorMode::Color16 => vec![Color::DarkGrey, Color::DarkGreen, Color::Green, Color::White],
+            ColorMode::Color16 => vec![
+                Color::DarkGrey,
+                Color::DarkGreen,
+                Color::Green,
+                Color::White,
+            ],
             _ => from_ansi_list(&[28, 34, 76, 84, 120, 157, 231]),
         },
         ColorScheme::Green3 => match mode {
@@ -99,7 +535,12 @@ pub fn build_palette(
         },
         ColorScheme::Gold => match mode {
             ColorMode::Mono => vec![Color::White],
-            ColorMode::Color16 => vec![Color::DarkGrey, Color::DarkYellow, Color::Yellow, Color::White],
+            ColorMode::Color16 => vec![
+                Color::DarkGrey,
+                Color::DarkYellow,
+                Color::Yellow,
+                Color::White,
+            ],
             _ => from_ansi_list(&[58, 94, 172, 178, 228, 230, 231]),
         },
         ColorScheme::Yellow => match mode {
@@ -144,8 +585,16 @@ pub fn build_palette(
         },
         ColorScheme::Vaporwave => match mode {
             ColorMode::Mono => vec![Color::White],
-            ColorMode::Color16 => vec![Color::Magenta, Color::Magenta, Color::Yellow, Color::Cyan, Color::White],
-            _ => from_ansi_list(&[53, 54, 55, 134, 177, 219, 214, 220, 227, 229, 87, 123, 159, 195, 231]),
+            ColorMode::Color16 => vec![
+                Color::Magenta,
+                Color::Magenta,
+                Color::Yellow,
+                Color::Cyan,
+                Color::White,
+            ],
+            _ => from_ansi_list(&[
+                53, 54, 55, 134, 177, 219, 214, 220, 227, 229, 87, 123, 159, 195, 231,
+            ]),
         },
         ColorScheme::Gray => match mode {
             ColorMode::Mono => vec![Color::White],
@@ -154,7 +603,14 @@ pub fn build_palette(
         },
         ColorScheme::Rainbow => match mode {
             ColorMode::Mono => vec![Color::White],
-            ColorMode::Color16 => vec![Color::Red, Color::Blue, Color::Yellow, Color::Green, Color::Cyan, Color::Magenta],
+            ColorMode::Color16 => vec![
+                Color::Red,
+                Color::Blue,
+                Color::Yellow,
+                Color::Green,
+                Color::Cyan,
+                Color::Magenta,
+            ],
             _ => from_ansi_list(&[196, 208, 226, 46, 21, 93, 201]),
         },
     };
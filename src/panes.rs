@@ -0,0 +1,151 @@
+// Copyright (c) 2025 rezk_nightky
+
+use crate::cell::Cell;
+use crate::cloud::Cloud;
+use crate::frame::Frame;
+use crate::runtime::Region;
+
+const VERTICAL: char = '\u{2502}';
+const HORIZONTAL: char = '\u{2500}';
+const CROSS: char = '\u{253c}';
+
+pub fn parse_grid(s: &str) -> Result<(u16, u16), String> {
+    let (c, r) = s
+        .trim()
+        .to_ascii_lowercase()
+        .split_once('x')
+        .map(|(c, r)| (c.trim().to_string(), r.trim().to_string()))
+        .ok_or_else(|| format!("expected COLSxROWS, got: {}", s))?;
+    let cols: u16 = c
+        .parse()
+        .map_err(|_| format!("invalid column count: {}", c))?;
+    let rows: u16 = r.parse().map_err(|_| format!("invalid row count: {}", r))?;
+    if cols == 0 || rows == 0 || cols as u32 * rows as u32 > 64 {
+        return Err(format!("grid must have between 1 and 64 panes: {}", s));
+    }
+    Ok((cols, rows))
+}
+
+// Splits `total` cells into `n` spans, leaving `gap` cells between them.
+fn spans(total: u16, n: u16, gap: u16) -> Vec<(u16, u16)> {
+    let usable = total.saturating_sub(gap * (n - 1)) as u32;
+    let n32 = n as u32;
+    let mut out = Vec::with_capacity(n as usize);
+    let mut pos = 0u16;
+    for i in 0..n32 {
+        let len = ((usable * (i + 1) / n32 - usable * i / n32) as u16).max(1);
+        out.push((pos, len));
+        pos = pos.saturating_add(len + gap);
+    }
+    out
+}
+
+// Extra clouds composited next to the primary one, which keeps every
+// control and feed and always occupies the top-left pane.
+pub struct Panes {
+    pub cols: u16,
+    pub rows: u16,
+    pub separators: bool,
+    pub extra: Vec<Cloud>,
+    rects: Vec<Region>,
+    frames: Vec<Frame>,
+}
+
+impl Panes {
+    pub fn new(cols: u16, rows: u16, separators: bool, extra: Vec<Cloud>) -> Self {
+        Self {
+            cols,
+            rows,
+            separators,
+            extra,
+            rects: Vec::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn resize(&mut self, primary: &mut Cloud, width: u16, height: u16) {
+        let gap = u16::from(self.separators);
+        let xs = spans(width, self.cols, gap);
+        let ys = spans(height, self.rows, gap);
+        self.rects = ys
+            .iter()
+            .flat_map(|&(y, h)| {
+                xs.iter().map(move |&(x, w)| Region {
+                    x,
+                    y,
+                    width: w,
+                    height: h,
+                })
+            })
+            .collect();
+
+        self.frames.truncate(self.rects.len());
+        for (i, r) in self.rects.iter().enumerate() {
+            let cloud = if i == 0 {
+                &mut *primary
+            } else {
+                &mut self.extra[i - 1]
+            };
+            cloud.reset(r.width, r.height);
+            cloud.force_draw_everything();
+            match self.frames.get_mut(i) {
+                Some(f) => f.resize(r.width, r.height, cloud.palette.bg),
+                None => self
+                    .frames
+                    .push(Frame::new(r.width, r.height, cloud.palette.bg)),
+            }
+        }
+    }
+
+    pub fn rain(&mut self, primary: &mut Cloud, out: &mut Frame) {
+        for (i, frame) in self.frames.iter_mut().enumerate() {
+            let cloud = if i == 0 {
+                &mut *primary
+            } else {
+                &mut self.extra[i - 1]
+            };
+            cloud.rain(frame);
+        }
+        for (r, frame) in self.rects.iter().zip(&self.frames) {
+            out.blit(frame, r.x, r.y);
+        }
+        if self.separators {
+            self.draw_separators(primary, out);
+        }
+    }
+
+    fn draw_separators(&self, primary: &Cloud, out: &mut Frame) {
+        let bg = primary.palette.bg;
+        let fg = primary.palette.colors.first().copied();
+        let line = |ch| Cell {
+            ch,
+            fg,
+            bg,
+            bold: false,
+        };
+
+        let cols: Vec<u16> = self.rects[..self.cols as usize - 1]
+            .iter()
+            .map(|r| r.x + r.width)
+            .collect();
+        let rows: Vec<u16> = self
+            .rects
+            .iter()
+            .step_by(self.cols as usize)
+            .take(self.rows as usize - 1)
+            .map(|r| r.y + r.height)
+            .collect();
+
+        for &x in &cols {
+            for y in 0..out.height {
+                out.set(x, y, line(VERTICAL));
+            }
+        }
+        for &y in &rows {
+            for x in 0..out.width {
+                let ch = if cols.contains(&x) { CROSS } else { HORIZONTAL };
+                out.set(x, y, line(ch));
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::io;
+use std::path::Path;
+
+use crossterm::style::Color;
+
+use crate::frame::Frame;
+use crate::palette::color_to_rgb;
+
+// Simulation rows per terminal row, giving sub-cell vertical motion.
+pub const ROWS_PER_CELL: u16 = 4;
+// Image pixels per terminal column.
+pub const PX_PER_COL: usize = 2;
+
+// How bright `--bg-image` shows behind the rain.
+const BG_DIM: f32 = 0.35;
+
+// An RGB image, 3 bytes per pixel.
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub rgb: Vec<u8>,
+}
+
+impl Image {
+    // Reads a binary (P6) or plain (P3) PPM file, the one format that needs
+    // no decoder; `magick wall.png wall.ppm` converts anything else.
+    pub fn load_ppm(path: &Path) -> io::Result<Image> {
+        let data = std::fs::read(path)?;
+        parse_ppm(&data)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "not a PPM (P3/P6) image"))
+    }
+
+    fn sample(&self, x: usize, y: usize, width: usize, height: usize) -> (u8, u8, u8) {
+        let sx = x * self.width / width.max(1);
+        let sy = y * self.height / height.max(1);
+        let i = (sy.min(self.height - 1) * self.width + sx.min(self.width - 1)) * 3;
+        (self.rgb[i], self.rgb[i + 1], self.rgb[i + 2])
+    }
+}
+
+fn parse_ppm(data: &[u8]) -> Option<Image> {
+    let mut pos = 0;
+    let mut token = || -> Option<&[u8]> {
+        loop {
+            while data.get(pos)?.is_ascii_whitespace() {
+                pos += 1;
+            }
+            if data[pos] != b'#' {
+                break;
+            }
+            while *data.get(pos)? != b'\n' {
+                pos += 1;
+            }
+        }
+        let start = pos;
+        while data.get(pos).is_some_and(|b| !b.is_ascii_whitespace()) {
+            pos += 1;
+        }
+        Some(&data[start..pos])
+    };
+    let number = |t: &[u8]| std::str::from_utf8(t).ok()?.parse::<usize>().ok();
+
+    let magic = token()?;
+    let width = number(token()?)?;
+    let height = number(token()?)?;
+    let max = number(token()?)?;
+    if width == 0 || height == 0 || max == 0 || max > 65535 {
+        return None;
+    }
+    let n = width.checked_mul(height)?.checked_mul(3)?;
+    let scale = |v: usize| (v.min(max) * 255 / max) as u8;
+    let rgb: Vec<u8> = match magic {
+        b"P3" => (0..n)
+            .map(|_| token().and_then(number).map(scale))
+            .collect::<Option<_>>()?,
+        b"P6" => {
+            // One whitespace byte separates the header from the samples.
+            let body = data.get(pos + 1..)?;
+            if max < 256 {
+                body.get(..n)?.iter().map(|&b| scale(b as usize)).collect()
+            } else {
+                body.get(..n * 2)?
+                    .chunks_exact(2)
+                    .map(|c| scale(u16::from_be_bytes([c[0], c[1]]) as usize))
+                    .collect()
+            }
+        }
+        _ => return None,
+    };
+    Some(Image { width, height, rgb })
+}
+
+pub struct Canvas {
+    pub width: usize,
+    pub height: usize,
+    pub rgb: Vec<u8>,
+    // `--bg-image`, stretched over the canvas and dimmed behind blank cells.
+    pub background: Option<Image>,
+}
+
+impl Canvas {
+    pub fn new() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            rgb: Vec::new(),
+            background: None,
+        }
+    }
+
+    // Renders a simulation frame (ROWS_PER_CELL rows per terminal row) with
+    // one image row per simulation row.
+    pub fn render(&mut self, frame: &Frame) {
+        self.width = frame.width as usize * PX_PER_COL;
+        self.height = frame.height as usize;
+        self.rgb.resize(self.width * self.height * 3, 0);
+
+        for y in 0..frame.height {
+            for x in 0..frame.width {
+                let Some(cell) = frame.get(x, y) else {
+                    continue;
+                };
+                let lit = cell.ch != ' ' && !cell.is_continuation();
+                let color = if lit {
+                    cell.fg.unwrap_or(Color::White)
+                } else {
+                    cell.bg.unwrap_or(Color::Black)
+                };
+                let (r, g, b) = color_to_rgb(color).unwrap_or((0, 0, 0));
+                let row = y as usize * self.width;
+                for px in 0..PX_PER_COL {
+                    let col = x as usize * PX_PER_COL + px;
+                    let (r, g, b) = match &self.background {
+                        Some(img) if !lit => {
+                            let (ir, ig, ib) = img.sample(col, y as usize, self.width, self.height);
+                            let dim = |c: u8, base: u8| base.max((c as f32 * BG_DIM) as u8);
+                            (dim(ir, r), dim(ig, g), dim(ib, b))
+                        }
+                        _ => (r, g, b),
+                    };
+                    let i = (row + col) * 3;
+                    self.rgb[i] = r;
+                    self.rgb[i + 1] = g;
+                    self.rgb[i + 2] = b;
+                }
+            }
+        }
+    }
+}
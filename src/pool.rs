@@ -0,0 +1,158 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+// Worker threads that live as long as the cloud and are handed borrowed work
+// every frame, so a frame doesn't pay for spawning threads.
+pub struct Pool {
+    workers: Vec<Worker>,
+}
+
+struct Worker {
+    jobs: Option<Sender<Job>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+// Reports a job as finished when dropped, so a job that panics still does.
+struct Done(Sender<bool>);
+
+impl Drop for Done {
+    fn drop(&mut self) {
+        let _ = self.0.send(thread::panicking());
+    }
+}
+
+impl Pool {
+    pub fn new(workers: usize) -> Self {
+        let workers = (0..workers)
+            .map(|i| {
+                let (tx, rx) = mpsc::channel::<Job>();
+                let handle = thread::Builder::new()
+                    .name(format!("cosmostrix-draw-{i}"))
+                    .spawn(move || {
+                        for job in rx {
+                            // The panic is reported through `Done`; keep the worker.
+                            let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                        }
+                    })
+                    .ok();
+                Worker {
+                    jobs: handle.is_some().then_some(tx),
+                    handle,
+                }
+            })
+            .collect();
+        Self { workers }
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers.len()
+    }
+
+    // Runs the first job on the calling thread and hands the rest to the
+    // workers in turn, returning once every one of them has finished.
+    pub fn run<F>(&self, jobs: impl IntoIterator<Item = F>)
+    where
+        F: FnOnce() + Send,
+    {
+        // Taken from the iterator before any is handed out, so a panic in
+        // the iterator can't leave a worker holding a borrow.
+        let mut jobs: Vec<F> = jobs.into_iter().collect();
+        if jobs.is_empty() {
+            return;
+        }
+        let local = jobs.remove(0);
+        let (done_tx, done_rx) = mpsc::channel();
+        let mut wait = Wait {
+            done: done_rx,
+            pending: 0,
+            panicked: false,
+        };
+        for (i, job) in jobs.into_iter().enumerate() {
+            let done = Done(done_tx.clone());
+            let job: Box<dyn FnOnce() + Send + '_> = Box::new(move || {
+                let _done = done;
+                job();
+            });
+            // SAFETY: `wait` blocks until every job counted in `pending` has
+            // reported back, when `run` returns and also when it unwinds, so
+            // whatever a job borrows outlives it even though the worker sees
+            // it as 'static.
+            let job = unsafe { std::mem::transmute::<Box<dyn FnOnce() + Send + '_>, Job>(job) };
+            wait.pending += 1;
+            let worker = match self.workers.len() {
+                0 => None,
+                n => self.workers[i % n].jobs.as_ref(),
+            };
+            // No live worker to take it: run it here, reporting a panic the same way.
+            let job = match worker {
+                Some(w) => match w.send(job) {
+                    Ok(()) => continue,
+                    Err(mpsc::SendError(job)) => job,
+                },
+                None => job,
+            };
+            let _ = panic::catch_unwind(AssertUnwindSafe(job));
+        }
+        drop(done_tx);
+
+        let local = panic::catch_unwind(AssertUnwindSafe(local));
+        wait.finish();
+        if let Err(p) = local {
+            panic::resume_unwind(p);
+        }
+        if wait.panicked {
+            panic!("a draw worker panicked");
+        }
+    }
+}
+
+// The jobs `run` has handed out and not yet heard back from.
+struct Wait {
+    done: Receiver<bool>,
+    pending: usize,
+    panicked: bool,
+}
+
+impl Wait {
+    fn finish(&mut self) {
+        while self.pending > 0 {
+            match self.done.recv() {
+                Ok(p) => self.panicked |= p,
+                // Every `Done` is gone, so every job is too.
+                Err(_) => break,
+            }
+            self.pending -= 1;
+        }
+        self.pending = 0;
+    }
+}
+
+impl Drop for Wait {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+impl Clone for Pool {
+    fn clone(&self) -> Self {
+        Self::new(self.workers())
+    }
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        for w in &mut self.workers {
+            w.jobs = None;
+        }
+        for w in &mut self.workers {
+            if let Some(h) = w.handle.take() {
+                let _ = h.join();
+            }
+        }
+    }
+}
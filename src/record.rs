@@ -0,0 +1,78 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::clock::ManualClock;
+use crate::cloud::Cloud;
+use crate::frame::{changed_cells, Frame};
+use crate::terminal::{self, queue_cells};
+
+// Hides the cursor and clears the player's screen before the first frame.
+const HELLO: &str = "\x1b[?25l\x1b[2J";
+const BYE: &str = "\x1b[0m\x1b[?25h";
+
+// `cosmostrix record`: renders `duration` of rain off screen, `fps` frames a
+// second, into an asciicast v2 file that `asciinema play` and the web player
+// can replay. Time is simulated, so a long recording takes only as long as
+// drawing it does.
+pub fn run(
+    mut cloud: Cloud,
+    path: &Path,
+    size: (u16, u16),
+    duration: Duration,
+    fps: f64,
+) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(
+        out,
+        "{{\"version\": 2, \"width\": {}, \"height\": {}, \"env\": {{\"TERM\": \"xterm-256color\"}}}}",
+        size.0, size.1
+    )?;
+
+    let clock = ManualClock::new(Instant::now());
+    cloud.set_clock(Box::new(clock.clone()));
+    cloud.reset(size.0, size.1);
+    let mut frame = Frame::new(size.0, size.1, cloud.palette.bg);
+    let mut last: Option<Frame> = None;
+    let mut buf: Vec<u8> = HELLO.as_bytes().to_vec();
+    let period = Duration::from_secs_f64(1.0 / fps);
+
+    let mut t = Duration::ZERO;
+    while t < duration && !terminal::interrupted() {
+        cloud.rain(&mut frame);
+        let cells = changed_cells(last.as_ref(), &frame);
+        if !cells.is_empty() {
+            queue_cells(&mut buf, &frame, &cells, (0, 0))?;
+        }
+        if !buf.is_empty() {
+            event(&mut out, t, &String::from_utf8_lossy(&buf))?;
+            buf.clear();
+        }
+        match last.as_mut() {
+            Some(l) => l.clone_from(&frame),
+            None => last = Some(frame.clone()),
+        }
+        clock.advance(period);
+        t += period;
+    }
+    event(&mut out, t, BYE)?;
+    out.flush()
+}
+
+// One `[time, "o", data]` output line.
+fn event<W: Write>(out: &mut W, t: Duration, data: &str) -> io::Result<()> {
+    write!(out, "[{:.6}, \"o\", \"", t.as_secs_f64())?;
+    for c in data.chars() {
+        match c {
+            '"' => out.write_all(b"\\\"")?,
+            '\\' => out.write_all(b"\\\\")?,
+            '\n' => out.write_all(b"\\n")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => write!(out, "{}", c)?,
+        }
+    }
+    out.write_all(b"\"]\n")
+}
@@ -0,0 +1,78 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::fmt::Write as _;
+use std::io;
+
+use crate::cell::{Cell, Color};
+use crate::frame::Frame;
+
+// Where finished frames go: the terminal when running, a recorder in tests.
+pub trait Renderer {
+    fn draw(&mut self, frame: &Frame) -> io::Result<()>;
+}
+
+// Keeps every drawn frame as text, for snapshot tests.
+#[derive(Default)]
+pub struct TestBackend {
+    pub frames: Vec<String>,
+}
+
+impl Renderer for TestBackend {
+    fn draw(&mut self, frame: &Frame) -> io::Result<()> {
+        self.frames.push(snapshot(frame));
+        Ok(())
+    }
+}
+
+// Renders a frame as its glyphs followed by a style grid of the same size.
+// Each style cell is a letter naming a foreground color in the legend at the
+// end, uppercase when bold, or `.` for a cell with no glyph. Continuation
+// cells of wide glyphs are left out of the glyph rows.
+pub fn snapshot(frame: &Frame) -> String {
+    let mut legend: Vec<Option<Color>> = Vec::new();
+    let mut out = String::new();
+    let _ = writeln!(out, "{}x{}", frame.width, frame.height);
+
+    for y in 0..frame.height {
+        out.push('|');
+        out.extend(row(frame, y).filter(|c| !c.is_continuation()).map(|c| c.ch));
+        out.push_str("|\n");
+    }
+    for y in 0..frame.height {
+        out.push('|');
+        for c in row(frame, y) {
+            out.push(style_code(c, &mut legend));
+        }
+        out.push_str("|\n");
+    }
+    for (i, c) in legend.iter().enumerate() {
+        let _ = writeln!(out, "{} = {:?}", (b'a' + i as u8) as char, c);
+    }
+    if let Some(bg) = frame.cells.first().map(|c| c.bg) {
+        let _ = writeln!(out, "bg = {:?}", bg);
+    }
+    out
+}
+
+fn row(frame: &Frame, y: u16) -> impl Iterator<Item = &Cell> {
+    (0..frame.width).filter_map(move |x| frame.get(x, y))
+}
+
+fn style_code(c: &Cell, legend: &mut Vec<Option<Color>>) -> char {
+    if c.ch == ' ' || c.is_continuation() {
+        return '.';
+    }
+    let i = match legend.iter().position(|&l| l == c.fg) {
+        Some(i) => i,
+        None => {
+            legend.push(c.fg);
+            legend.len() - 1
+        }
+    };
+    let code = (b'a' + (i % 26) as u8) as char;
+    if c.bold {
+        code.to_ascii_uppercase()
+    } else {
+        code
+    }
+}
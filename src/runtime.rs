@@ -3,15 +3,103 @@
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ColorMode {
     Mono,
+    Color8,
     Color16,
     Color256,
     TrueColor,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+// `--margin`: blank cells kept around the rain, in terminal cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Margin {
+    pub top: u16,
+    pub right: u16,
+    pub bottom: u16,
+    pub left: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BorderStyle {
+    Single,
+    Double,
+}
+
+impl BorderStyle {
+    // Top-left, top-right, bottom-left and bottom-right corners, then the
+    // horizontal and vertical edges.
+    pub fn chars(self) -> [char; 6] {
+        match self {
+            BorderStyle::Single => ['┌', '┐', '└', '┘', '─', '│'],
+            BorderStyle::Double => ['╔', '╗', '╚', '╝', '═', '║'],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    Cell,
+    #[cfg(feature = "kitty")]
+    Kitty,
+    #[cfg(feature = "sixel")]
+    Sixel,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ShadingMode {
     Random,
     DistanceFromHead,
+    Speed,
+}
+
+// How brightness falls off from the head in distance-from-head shading.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShadeCurve {
+    Linear,
+    Quadratic,
+    Exp,
+    Steps(u8),
+}
+
+impl ShadeCurve {
+    // Brightness from 1 at the head to 0 at `x` = 1, the end of the shaded run.
+    pub fn brightness(self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            ShadeCurve::Linear => 1.0 - x,
+            ShadeCurve::Quadratic => (1.0 - x) * (1.0 - x),
+            ShadeCurve::Exp => ((-4.0 * x).exp() - (-4.0f32).exp()) / (1.0 - (-4.0f32).exp()),
+            ShadeCurve::Steps(n) => {
+                let n = n.max(2) as f32;
+                ((1.0 - x) * n).floor().min(n - 1.0) / (n - 1.0)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GlitchMode {
+    Global,
+    Cell,
+}
+
+// What happens when a droplet catches up with the one ahead of it in the
+// same column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Collision {
+    // The rear droplet runs through the front one, drawing over it.
+    Pass,
+    // The rear droplet takes over the front one's head and speed.
+    Merge,
+    // The rear droplet waits behind the front one's tail at its speed.
+    Queue,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -21,6 +109,35 @@ pub enum BoldMode {
     All,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypingMode {
+    Random,
+    Cursor,
+}
+
+// Where `--wave-start` lets the rain begin before it spreads across the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WaveStart {
+    Left,
+    Center,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetricsMode {
+    Cpu,
+    Mem,
+    Net,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DensityProfile {
+    Uniform,
+    Center,
+    Edges,
+    GradientLeft,
+    Mask(Vec<f32>),
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ColorScheme {
     User,
@@ -41,6 +158,53 @@ pub enum ColorScheme {
     Gray,
 }
 
+impl ColorScheme {
+    pub const BUILTIN: [ColorScheme; 15] = [
+        ColorScheme::Green,
+        ColorScheme::Green2,
+        ColorScheme::Green3,
+        ColorScheme::Yellow,
+        ColorScheme::Orange,
+        ColorScheme::Red,
+        ColorScheme::Blue,
+        ColorScheme::Cyan,
+        ColorScheme::Gold,
+        ColorScheme::Rainbow,
+        ColorScheme::Purple,
+        ColorScheme::Pink,
+        ColorScheme::Pink2,
+        ColorScheme::Vaporwave,
+        ColorScheme::Gray,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorScheme::User => "user",
+            ColorScheme::Green => "green",
+            ColorScheme::Green2 => "green2",
+            ColorScheme::Green3 => "green3",
+            ColorScheme::Yellow => "yellow",
+            ColorScheme::Orange => "orange",
+            ColorScheme::Red => "red",
+            ColorScheme::Blue => "blue",
+            ColorScheme::Cyan => "cyan",
+            ColorScheme::Gold => "gold",
+            ColorScheme::Rainbow => "rainbow",
+            ColorScheme::Purple => "purple",
+            ColorScheme::Pink => "pink",
+            ColorScheme::Pink2 => "pink2",
+            ColorScheme::Vaporwave => "vaporwave",
+            ColorScheme::Gray => "gray",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorOverride {
+    Index(u8),
+    Rgb(u8, u8, u8),
+}
+
 #[derive(Clone, Debug)]
 pub struct UserColor {
     pub index: u8,
@@ -50,4 +214,6 @@ pub struct UserColor {
 #[derive(Clone, Debug)]
 pub struct UserColors {
     pub colors: Vec<UserColor>,
+    pub bg: Option<UserColor>,
+    pub head: Option<UserColor>,
 }
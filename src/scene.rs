@@ -0,0 +1,77 @@
+// Copyright (c) 2025 rezk_nightky
+
+use crate::clock::Instant;
+use crate::fireworks::Fireworks;
+use crate::frame::Frame;
+use crate::life::Life;
+use crate::palette::Palette;
+use crate::snow::Snow;
+use crate::starfield::Starfield;
+use crate::waterfall::Waterfall;
+
+// A full-screen animation drawn instead of the rain. Scenes redraw the
+// whole frame every time and take their colors from the active scheme.
+// `now` comes from the cloud's clock, so scenes follow its pause and speed.
+pub trait Scene {
+    fn resize(&mut self, cols: u16, lines: u16);
+    fn draw(&mut self, frame: &mut Frame, palette: &Palette, now: Instant);
+
+    // Values from an outside data source, such as `--scene-input stdin`;
+    // scenes that don't visualise data ignore them.
+    fn feed(&mut self, _values: &[f32]) {}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SceneKind {
+    Rain,
+    Starfield,
+    Snow,
+    Life,
+    Fireworks,
+    Waterfall,
+}
+
+impl SceneKind {
+    pub const ALL: [SceneKind; 6] = [
+        SceneKind::Rain,
+        SceneKind::Starfield,
+        SceneKind::Snow,
+        SceneKind::Life,
+        SceneKind::Fireworks,
+        SceneKind::Waterfall,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SceneKind::Rain => "rain",
+            SceneKind::Starfield => "starfield",
+            SceneKind::Snow => "snow",
+            SceneKind::Life => "life",
+            SceneKind::Fireworks => "fireworks",
+            SceneKind::Waterfall => "waterfall",
+        }
+    }
+
+    pub fn from_name(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        Self::ALL.iter().copied().find(|k| k.name() == s)
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&k| k == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    // The rain is the cloud itself, so it has no separate scene. `ascii`
+    // keeps scenes to ASCII glyphs, as with the default charset.
+    pub fn build(self, ascii: bool) -> Option<Box<dyn Scene>> {
+        match self {
+            SceneKind::Rain => None,
+            SceneKind::Starfield => Some(Box::new(Starfield::new())),
+            SceneKind::Snow => Some(Box::new(Snow::new(ascii))),
+            SceneKind::Life => Some(Box::new(Life::new(ascii))),
+            SceneKind::Fireworks => Some(Box::new(Fireworks::new())),
+            SceneKind::Waterfall => Some(Box::new(Waterfall::new(ascii))),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::runtime::ColorScheme;
+
+#[derive(Clone, Debug)]
+pub struct ThemeSchedule {
+    // (minute of day, scheme), sorted by minute
+    pub entries: Vec<(u16, ColorScheme)>,
+}
+
+impl ThemeSchedule {
+    pub fn scheme_at(&self, minute: u16) -> Option<ColorScheme> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(m, _)| *m <= minute)
+            .or_else(|| self.entries.last())
+            .map(|(_, s)| *s)
+    }
+
+    pub fn current(&self) -> Option<ColorScheme> {
+        self.scheme_at(local_minute_of_day())
+    }
+}
+
+pub fn parse_time_of_day(s: &str) -> Result<u16, String> {
+    let (h, m) = s
+        .trim()
+        .split_once(':')
+        .ok_or_else(|| format!("invalid time (expected HH:MM): {}", s))?;
+    let h: u16 = h.parse().map_err(|_| format!("invalid hour: {}", h))?;
+    let m: u16 = m.parse().map_err(|_| format!("invalid minute: {}", m))?;
+    if h > 23 || m > 59 {
+        return Err(format!("time out of range: {}", s));
+    }
+    Ok(h * 60 + m)
+}
+
+#[cfg(unix)]
+pub fn local_minute_of_day() -> u16 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0) as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let ok = unsafe { !libc::localtime_r(&secs, &mut tm).is_null() };
+    if !ok {
+        return ((secs / 60) % (24 * 60)) as u16;
+    }
+    (tm.tm_hour * 60 + tm.tm_min) as u16
+}
+
+#[cfg(not(unix))]
+pub fn local_minute_of_day() -> u16 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    ((secs / 60) % (24 * 60)) as u16
+}
@@ -0,0 +1,138 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+
+pub const FLAG: &str = "--win-screensaver";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrMode {
+    // `/s`: run full screen until any input.
+    Show,
+    // `/p <hwnd>`: preview inside the control panel thumbnail.
+    Preview,
+    // `/c[:hwnd]` or no switch on a `.scr`: open settings.
+    Configure,
+}
+
+fn is_scr(argv0: &OsStr) -> bool {
+    Path::new(argv0)
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("scr"))
+}
+
+// `-s` style switches are only taken on a `.scr`; with `--win-screensaver`
+// they are cosmostrix's own short options (`-c`, `-s`, ...).
+fn parse_switch(arg: &OsStr, dash: bool) -> Option<ScrMode> {
+    let s = arg.to_str()?;
+    let rest = s
+        .strip_prefix('/')
+        .or_else(|| s.strip_prefix('-').filter(|_| dash))?;
+    let (switch, _hwnd) = rest.split_once(':').unwrap_or((rest, ""));
+    match switch.to_ascii_lowercase().as_str() {
+        "s" => Some(ScrMode::Show),
+        "p" => Some(ScrMode::Preview),
+        "c" => Some(ScrMode::Configure),
+        _ => None,
+    }
+}
+
+// Pulls the Windows screensaver switches out of the command line when the
+// binary runs as a renamed `.scr` or with `--win-screensaver`, leaving the
+// rest (plus `--win-screensaver`) for clap.
+pub fn split_args(argv: Vec<OsString>) -> (Vec<OsString>, Option<ScrMode>) {
+    let scr = argv.first().is_some_and(|a| is_scr(a));
+    let flagged = argv.iter().skip(1).any(|a| a == FLAG);
+    if !scr && !flagged {
+        return (argv, None);
+    }
+
+    let mut out = Vec::with_capacity(argv.len() + 1);
+    let mut mode = None;
+    let mut iter = argv.into_iter();
+    out.extend(iter.next());
+    while let Some(arg) = iter.next() {
+        match parse_switch(&arg, scr) {
+            Some(m) if mode.is_none() => {
+                mode = Some(m);
+                let inline_hwnd = arg.to_str().is_some_and(|s| s.contains(':'));
+                if m == ScrMode::Preview && !inline_hwnd {
+                    iter.next();
+                }
+            }
+            _ => out.push(arg),
+        }
+    }
+    if !flagged {
+        out.push(OsString::from(FLAG));
+    }
+
+    let default = if scr {
+        ScrMode::Configure
+    } else {
+        ScrMode::Show
+    };
+    (out, Some(mode.unwrap_or(default)))
+}
+
+// Re-launches full screen in Windows Terminal when started outside it, as
+// the screensaver host starts a `.scr` in a bare console window.
+#[cfg(windows)]
+pub fn relaunch_in_windows_terminal(args: &[OsString]) -> bool {
+    if std::env::var_os("WT_SESSION").is_some() {
+        return false;
+    }
+    let Ok(exe) = std::env::current_exe() else {
+        return false;
+    };
+    std::process::Command::new("wt.exe")
+        .arg("--fullscreen")
+        .arg(exe)
+        .args(args.iter().skip(1))
+        .arg("/s")
+        .spawn()
+        .is_ok()
+}
+
+#[cfg(not(windows))]
+pub fn relaunch_in_windows_terminal(_args: &[OsString]) -> bool {
+    false
+}
+
+fn in_path(name: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|p| std::env::split_paths(&p).any(|d| d.join(name).is_file()))
+}
+
+// `--lock`: the screen locker for this session, preferring the one that
+// matches the display server and falling back to asking logind.
+pub fn lock_command() -> &'static str {
+    if cfg!(windows) {
+        "rundll32.exe user32.dll,LockWorkStation"
+    } else if cfg!(target_os = "macos") {
+        "pmset displaysleepnow"
+    } else if std::env::var_os("WAYLAND_DISPLAY").is_some() && in_path("swaylock") {
+        "swaylock"
+    } else if std::env::var_os("DISPLAY").is_some() && in_path("i3lock") {
+        "i3lock"
+    } else {
+        "loginctl lock-session"
+    }
+}
+
+// Runs `cmd` through the shell and waits for it, so a locker is up before
+// the terminal gets its prompt back.
+pub fn run_hook(cmd: &str) -> Result<(), String> {
+    #[cfg(windows)]
+    let status = std::process::Command::new("cmd")
+        .arg("/C")
+        .arg(cmd)
+        .status();
+    #[cfg(not(windows))]
+    let status = std::process::Command::new("sh").arg("-c").arg(cmd).status();
+    match status {
+        Ok(s) if s.success() => Ok(()),
+        Ok(s) => Err(format!("{}: {}", cmd, s)),
+        Err(e) => Err(format!("{}: {}", cmd, e)),
+    }
+}
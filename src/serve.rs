@@ -0,0 +1,245 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::io::{ErrorKind, Read, Result, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use crate::cloud::Cloud;
+use crate::frame::{changed_cells, Frame};
+use crate::terminal::{self, queue_cells};
+
+const IAC: u8 = 255;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const WILL: u8 = 251;
+const DO: u8 = 253;
+const OPT_ECHO: u8 = 1;
+const OPT_SGA: u8 = 3;
+const OPT_NAWS: u8 = 31;
+
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+// Every client shares one simulation as large as the largest window, so a
+// reported size is capped before it can make that allocation huge.
+const MAX_SIZE: (u16, u16) = (500, 200);
+
+// Asks for window size reports and character-at-a-time mode without echo;
+// plain `nc` clients just see these bytes as noise before the first frame.
+const HELLO: &[u8] = &[
+    IAC, DO, OPT_NAWS, IAC, WILL, OPT_ECHO, IAC, WILL, OPT_SGA, b'\x1b', b'[', b'?', b'2', b'5',
+    b'l', b'\x1b', b'[', b'2', b'J',
+];
+const BYE: &[u8] = b"\x1b[0m\x1b[2J\x1b[H\x1b[?25h";
+
+struct Client {
+    stream: TcpStream,
+    size: (u16, u16),
+    view: Frame,
+    last: Option<Frame>,
+    input: Vec<u8>,
+    pending: Vec<u8>,
+    closed: bool,
+}
+
+impl Client {
+    fn new(stream: TcpStream) -> Self {
+        Self {
+            stream,
+            size: DEFAULT_SIZE,
+            view: Frame::new(DEFAULT_SIZE.0, DEFAULT_SIZE.1, None),
+            last: None,
+            input: Vec::new(),
+            pending: HELLO.to_vec(),
+            closed: false,
+        }
+    }
+
+    fn read_input(&mut self) {
+        let mut buf = [0u8; 256];
+        loop {
+            match self.stream.read(&mut buf) {
+                Ok(0) => {
+                    self.closed = true;
+                    return;
+                }
+                Ok(n) => self.input.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(_) => {
+                    self.closed = true;
+                    return;
+                }
+            }
+        }
+        self.parse_input();
+    }
+
+    // Consumes complete telnet commands and keys, keeping a trailing partial
+    // command for the next read.
+    fn parse_input(&mut self) {
+        let buf = std::mem::take(&mut self.input);
+        let mut i = 0;
+        while i < buf.len() {
+            match buf[i] {
+                IAC => {
+                    let Some(&cmd) = buf.get(i + 1) else {
+                        break;
+                    };
+                    match cmd {
+                        SB => {
+                            let Some(end) = buf[i..].windows(2).position(|w| w == [IAC, SE]) else {
+                                break;
+                            };
+                            let sub = &buf[i + 2..i + end];
+                            if sub.len() >= 5 && sub[0] == OPT_NAWS {
+                                let w = u16::from_be_bytes([sub[1], sub[2]]);
+                                let h = u16::from_be_bytes([sub[3], sub[4]]);
+                                if w > 0 && h > 0 {
+                                    self.size = (w.min(MAX_SIZE.0), h.min(MAX_SIZE.1));
+                                }
+                            }
+                            i += end + 2;
+                        }
+                        WILL..=254 => {
+                            if i + 2 >= buf.len() {
+                                break;
+                            }
+                            i += 3;
+                        }
+                        _ => i += 2,
+                    }
+                }
+                // Arrow and function keys arrive as escape sequences; skip
+                // them whole so their last byte isn't taken for a key.
+                0x1b => match buf.get(i + 1) {
+                    None => break,
+                    Some(b'[') => match buf[i + 2..].iter().position(|b| (0x40..=0x7e).contains(b))
+                    {
+                        Some(end) => i += end + 3,
+                        None => break,
+                    },
+                    Some(b'O') if i + 2 >= buf.len() => break,
+                    Some(b'O') => i += 3,
+                    Some(_) => i += 1,
+                },
+                b'q' | b'Q' | 0x03 | 0x04 => {
+                    self.pending.extend_from_slice(BYE);
+                    let _ = self.flush();
+                    self.closed = true;
+                    return;
+                }
+                _ => i += 1,
+            }
+        }
+        self.input = buf[i..].to_vec();
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        while !self.pending.is_empty() {
+            match self.stream.write(&self.pending) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(n) => {
+                    self.pending.drain(..n);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    // Queues the part of `frame` that fits this client's window. Skipped while
+    // earlier output is still unsent; `last` keeps the diff correct.
+    fn send(&mut self, frame: &Frame) {
+        if !self.pending.is_empty() {
+            return;
+        }
+        let view = &mut self.view;
+        view.resize(self.size.0, self.size.1, None);
+        view.blit(frame, 0, 0);
+        let cells = changed_cells(self.last.as_ref(), view);
+        if cells.is_empty() {
+            return;
+        }
+        if self
+            .last
+            .as_ref()
+            .is_some_and(|l| l.width != view.width || l.height != view.height)
+        {
+            self.pending.extend_from_slice(b"\x1b[2J");
+        }
+        if queue_cells(&mut self.pending, view, &cells, (0, 0)).is_err() {
+            self.closed = true;
+        }
+        match self.last.as_mut() {
+            Some(last) => last.clone_from(view),
+            None => self.last = Some(view.clone()),
+        }
+    }
+}
+
+pub fn run(mut cloud: Cloud, addr: impl ToSocketAddrs, fps: f64) -> Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    listener.set_nonblocking(true)?;
+    eprintln!("serving on {}", listener.local_addr()?);
+
+    let period = Duration::from_secs_f64(1.0 / fps);
+    let mut clients: Vec<Client> = Vec::new();
+    let mut size = DEFAULT_SIZE;
+    cloud.reset(size.0, size.1);
+    let mut frame = Frame::new(size.0, size.1, cloud.palette.bg);
+
+    while !terminal::interrupted() {
+        let start = Instant::now();
+
+        loop {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    if stream.set_nonblocking(true).is_ok() {
+                        let _ = stream.set_nodelay(true);
+                        clients.push(Client::new(stream));
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(_) => break,
+            }
+        }
+
+        for c in &mut clients {
+            c.read_input();
+        }
+
+        // The shared simulation covers the largest window; smaller clients see
+        // its top-left corner.
+        let want = clients
+            .iter()
+            .filter(|c| !c.closed)
+            .map(|c| c.size)
+            .reduce(|a, b| (a.0.max(b.0), a.1.max(b.1)))
+            .unwrap_or(size);
+        if want != size {
+            size = want;
+            cloud.reset(size.0, size.1);
+            cloud.force_draw_everything();
+            frame.resize(size.0, size.1, cloud.palette.bg);
+        }
+
+        cloud.rain(&mut frame);
+        for c in &mut clients {
+            if c.closed {
+                continue;
+            }
+            c.send(&frame);
+            if c.flush().is_err() {
+                c.closed = true;
+            }
+        }
+        clients.retain(|c| !c.closed);
+
+        std::thread::sleep(period.saturating_sub(start.elapsed()));
+    }
+
+    for c in &mut clients {
+        c.pending.extend_from_slice(BYE);
+        let _ = c.flush();
+    }
+    Ok(())
+}
@@ -0,0 +1,125 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::pixel::{Canvas, PX_PER_COL, ROWS_PER_CELL};
+
+const MAX_COLORS: usize = 256;
+
+// Maps every canvas pixel to a palette register, falling back to a 3-3-2
+// quantization when the frame has more distinct colors than registers.
+fn index_canvas(canvas: &Canvas) -> (Vec<(u8, u8, u8)>, Vec<u8>) {
+    let mut palette: Vec<(u8, u8, u8)> = Vec::new();
+    let mut lookup: HashMap<(u8, u8, u8), u8> = HashMap::new();
+    let mut indexes = Vec::with_capacity(canvas.width * canvas.height);
+
+    for quantize in [false, true] {
+        palette.clear();
+        lookup.clear();
+        indexes.clear();
+        let mut overflow = false;
+        for px in canvas.rgb.chunks_exact(3) {
+            let mut rgb = (px[0], px[1], px[2]);
+            if quantize {
+                rgb = (rgb.0 & 0xe0, rgb.1 & 0xe0, rgb.2 & 0xc0);
+            }
+            let idx = match lookup.get(&rgb) {
+                Some(&i) => i,
+                None => {
+                    if palette.len() == MAX_COLORS {
+                        overflow = true;
+                        break;
+                    }
+                    let i = palette.len() as u8;
+                    palette.push(rgb);
+                    lookup.insert(rgb, i);
+                    i
+                }
+            };
+            indexes.push(idx);
+        }
+        if !overflow {
+            break;
+        }
+    }
+    (palette, indexes)
+}
+
+fn push_run(out: &mut String, ch: u8, count: usize) {
+    let ch = (0x3f + ch) as char;
+    match count {
+        0 => {}
+        1..=3 => (0..count).for_each(|_| out.push(ch)),
+        _ => {
+            let _ = write!(out, "!{}{}", count, ch);
+        }
+    }
+}
+
+// Builds a DCS sixel sequence drawing `canvas` at the cursor, with
+// each canvas pixel scaled to fill its share of a `cell_w` x `cell_h` cell.
+pub fn encode(canvas: &Canvas, cell_w: u16, cell_h: u16) -> String {
+    let sx = (cell_w as usize / PX_PER_COL).max(1);
+    let sy = (cell_h as usize / ROWS_PER_CELL as usize).max(1);
+    let width = canvas.width * sx;
+    let height = canvas.height * sy;
+
+    let (palette, indexes) = index_canvas(canvas);
+
+    let mut out = String::with_capacity(width * height / 4 + palette.len() * 16 + 64);
+    out.push_str("\x1bP0;1;0q");
+    let _ = write!(out, "\"1;1;{};{}", width, height);
+    for (i, (r, g, b)) in palette.iter().enumerate() {
+        let pct = |v: u8| v as u32 * 100 / 255;
+        let _ = write!(out, "#{};2;{};{};{}", i, pct(*r), pct(*g), pct(*b));
+    }
+
+    let mut bits: Vec<Vec<u8>> = vec![Vec::new(); palette.len()];
+    let mut used: Vec<usize> = Vec::new();
+    for band in (0..height).step_by(6) {
+        used.clear();
+        for bit in 0..6 {
+            let y = band + bit;
+            if y >= height {
+                break;
+            }
+            let row = (y / sy) * canvas.width;
+            for x in 0..width {
+                let c = indexes[row + x / sx] as usize;
+                if bits[c].is_empty() {
+                    bits[c].resize(width, 0);
+                    used.push(c);
+                }
+                bits[c][x] |= 1 << bit;
+            }
+        }
+
+        for (n, &c) in used.iter().enumerate() {
+            if n > 0 {
+                out.push('$');
+            }
+            let _ = write!(out, "#{}", c);
+            let row = &bits[c];
+            let mut run = 0;
+            let mut prev = row[0];
+            for &v in row {
+                if v == prev {
+                    run += 1;
+                } else {
+                    push_run(&mut out, prev, run);
+                    prev = v;
+                    run = 1;
+                }
+            }
+            push_run(&mut out, prev, run);
+        }
+        for &c in &used {
+            bits[c].clear();
+        }
+        out.push('-');
+    }
+
+    out.push_str("\x1b\\");
+    out
+}
@@ -0,0 +1,163 @@
+// Copyright (c) 2025 rezk_nightky
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::cell::Cell;
+use crate::clock::Instant;
+use crate::frame::Frame;
+use crate::palette::Palette;
+use crate::scene::Scene;
+
+const FLAKES_PER_COL_SEC: f32 = 0.2;
+// Each landed flake adds half a cell of snow, and a share of every pile
+// melts each second, so the ground holds a thin layer that keeps shifting.
+const SETTLE: f32 = 0.5;
+const MELT_PER_SEC: f32 = 0.08;
+const MAX_PILE_FRACTION: f32 = 0.15;
+
+#[derive(Clone, Copy)]
+struct Flake {
+    x: f32,
+    y: f32,
+    speed: f32,
+    phase: f32,
+    big: bool,
+}
+
+pub struct Snow {
+    flakes: Vec<Flake>,
+    // Settled depth per column, in cells.
+    piles: Vec<f32>,
+    cols: u16,
+    lines: u16,
+    ascii: bool,
+    rng: SmallRng,
+    start: Option<Instant>,
+    last: Option<Instant>,
+    spawn_debt: f32,
+}
+
+impl Snow {
+    pub fn new(ascii: bool) -> Self {
+        Self {
+            flakes: Vec::new(),
+            piles: Vec::new(),
+            cols: 0,
+            lines: 0,
+            ascii,
+            rng: SmallRng::seed_from_u64(0x5eed_5a0f),
+            start: None,
+            last: None,
+            spawn_debt: 0.0,
+        }
+    }
+
+    fn spawn(&mut self, y: f32) {
+        let big = self.rng.random_bool(0.3);
+        self.flakes.push(Flake {
+            x: self.rng.random_range(0.0..self.cols.max(1) as f32),
+            y,
+            speed: if big {
+                self.rng.random_range(3.0..5.0)
+            } else {
+                self.rng.random_range(1.5..3.0)
+            },
+            phase: self.rng.random_range(0.0..std::f32::consts::TAU),
+            big,
+        });
+    }
+
+    fn step(&mut self, dt: f32, t: f32) {
+        let cols = self.cols as f32;
+        let max_pile = (self.lines as f32 * MAX_PILE_FRACTION).max(1.0);
+
+        self.spawn_debt += cols * FLAKES_PER_COL_SEC * dt;
+        while self.spawn_debt >= 1.0 {
+            self.spawn_debt -= 1.0;
+            self.spawn(0.0);
+        }
+
+        let (lines, piles) = (self.lines as f32, &mut self.piles);
+        self.flakes.retain_mut(|f| {
+            f.y += f.speed * dt;
+            f.x = (f.x + (t * 0.8 + f.phase).sin() * 1.2 * dt).rem_euclid(cols);
+            let col = f.x as usize;
+            let ground = lines - piles.get(col).copied().unwrap_or(0.0).floor();
+            if f.y < ground - 1.0 {
+                return true;
+            }
+            if let Some(p) = piles.get_mut(col) {
+                *p = (*p + SETTLE).min(max_pile);
+            }
+            false
+        });
+
+        for p in &mut self.piles {
+            *p -= *p * MELT_PER_SEC * dt;
+        }
+    }
+}
+
+impl Scene for Snow {
+    fn resize(&mut self, cols: u16, lines: u16) {
+        self.cols = cols;
+        self.lines = lines;
+        self.flakes.clear();
+        self.piles.clear();
+        self.piles.resize(cols as usize, 0.0);
+        let n = (cols as f32 * lines as f32 * FLAKES_PER_COL_SEC / 3.0) as usize;
+        for _ in 0..n {
+            let y = self.rng.random_range(0.0..lines.max(1) as f32);
+            self.spawn(y);
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, palette: &Palette, now: Instant) {
+        let dt = self
+            .last
+            .map(|t| now.saturating_duration_since(t).as_secs_f32().min(0.1))
+            .unwrap_or(0.0);
+        self.last = Some(now);
+        let start = *self.start.get_or_insert(now);
+        self.step(dt, now.saturating_duration_since(start).as_secs_f32());
+
+        frame.clear_with_bg(palette.bg);
+        let colors = &palette.colors;
+        let n = colors.len();
+        let bright = colors.last().copied();
+        let soft = n
+            .checked_sub(2)
+            .and_then(|i| colors.get(i))
+            .copied()
+            .or(bright);
+        let ground = colors.get(n / 2).copied();
+
+        let pile_ch = if self.ascii { '#' } else { '\u{2588}' };
+        for (x, &p) in self.piles.iter().enumerate() {
+            for d in 0..p.floor() as u16 {
+                let cell = Cell {
+                    ch: pile_ch,
+                    fg: ground,
+                    bg: palette.bg,
+                    bold: false,
+                };
+                frame.set(x as u16, self.lines.saturating_sub(d + 1), cell);
+            }
+        }
+
+        for f in &self.flakes {
+            let ch = match (f.big, self.ascii) {
+                (true, false) => '\u{2744}',
+                (true, true) | (false, false) => '*',
+                (false, true) => '.',
+            };
+            let cell = Cell {
+                ch,
+                fg: if f.big { bright } else { soft },
+                bg: palette.bg,
+                bold: f.big,
+            };
+            frame.set(f.x as u16, f.y as u16, cell);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright (c) 2025 rezk_nightky
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::cell::Cell;
+use crate::clock::Instant;
+use crate::frame::Frame;
+use crate::palette::Palette;
+use crate::scene::Scene;
+
+const STARS_PER_CELL: f32 = 0.03;
+// Depth covered per second; stars start at depth 1 and pass the viewer at 0.
+const WARP_SPEED: f32 = 0.35;
+const NEAREST: f32 = 0.02;
+
+#[derive(Clone, Copy)]
+struct Star {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+// Stars fly towards the viewer; projecting by 1/depth makes them speed up
+// as they spread out from the centre.
+pub struct Starfield {
+    stars: Vec<Star>,
+    cols: u16,
+    lines: u16,
+    rng: SmallRng,
+    last: Option<Instant>,
+}
+
+impl Starfield {
+    pub fn new() -> Self {
+        Self {
+            stars: Vec::new(),
+            cols: 0,
+            lines: 0,
+            rng: SmallRng::seed_from_u64(0x5eed_57a2),
+            last: None,
+        }
+    }
+
+    fn spawn(&mut self, z: f32) -> Star {
+        Star {
+            x: self.rng.random_range(-1.0..1.0),
+            y: self.rng.random_range(-1.0..1.0),
+            z,
+        }
+    }
+
+    fn project(&self, s: &Star) -> Option<(u16, u16)> {
+        let (cx, cy) = (self.cols as f32 / 2.0, self.lines as f32 / 2.0);
+        let x = cx + s.x / s.z * cx;
+        let y = cy + s.y / s.z * cy;
+        if x < 0.0 || y < 0.0 || x >= self.cols as f32 || y >= self.lines as f32 {
+            return None;
+        }
+        Some((x as u16, y as u16))
+    }
+}
+
+impl Default for Starfield {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene for Starfield {
+    fn resize(&mut self, cols: u16, lines: u16) {
+        self.cols = cols;
+        self.lines = lines;
+        let n = (cols as f32 * lines as f32 * STARS_PER_CELL).round() as usize;
+        self.stars.clear();
+        for _ in 0..n {
+            let z = self.rng.random_range(NEAREST..1.0);
+            let star = self.spawn(z);
+            self.stars.push(star);
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, palette: &Palette, now: Instant) {
+        let dt = self
+            .last
+            .map(|t| now.saturating_duration_since(t).as_secs_f32().min(0.1))
+            .unwrap_or(0.0);
+        self.last = Some(now);
+
+        for i in 0..self.stars.len() {
+            self.stars[i].z -= WARP_SPEED * dt;
+            if self.stars[i].z <= NEAREST || self.project(&self.stars[i]).is_none() {
+                self.stars[i] = self.spawn(1.0);
+            }
+        }
+
+        frame.clear_with_bg(palette.bg);
+        let colors = &palette.colors;
+        for s in &self.stars {
+            let Some((x, y)) = self.project(s) else {
+                continue;
+            };
+            let near = 1.0 - s.z;
+            let ch = match s.z {
+                z if z > 0.6 => '.',
+                z if z > 0.3 => '+',
+                _ => '*',
+            };
+            let fg = match colors.len() {
+                0 => None,
+                n => Some(colors[((near * (n - 1) as f32).round() as usize).min(n - 1)]),
+            };
+            frame.set(
+                x,
+                y,
+                Cell {
+                    ch,
+                    fg,
+                    bg: palette.bg,
+                    bold: s.z < 0.15,
+                },
+            );
+        }
+    }
+}
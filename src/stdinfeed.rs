@@ -0,0 +1,47 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::io::{stdin, BufRead};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+// Reads rows of numbers (separated by spaces or commas) from piped stdin.
+// Values up to 1 are used as they are; once larger ones show up, rows are
+// scaled against the largest value seen so far.
+pub struct StdinFeed {
+    rx: Receiver<Vec<f32>>,
+}
+
+impl StdinFeed {
+    pub fn start() -> Self {
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            let mut max_seen = 1.0f32;
+            for line in stdin().lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                let mut values: Vec<f32> = line
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter_map(|p| p.parse::<f32>().ok())
+                    .filter(|v| v.is_finite())
+                    .map(|v| v.max(0.0))
+                    .collect();
+                if values.is_empty() {
+                    continue;
+                }
+                max_seen = values.iter().copied().fold(max_seen, f32::max);
+                for v in &mut values {
+                    *v /= max_seen;
+                }
+                if tx.send(values).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { rx }
+    }
+
+    pub fn try_recv(&self) -> Option<Vec<f32>> {
+        self.rx.try_recv().ok()
+    }
+}
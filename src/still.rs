@@ -0,0 +1,63 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::env;
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
+
+use crate::clock::ManualClock;
+use crate::cloud::Cloud;
+use crate::frame::Frame;
+
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+// How much rain runs before the frame is taken, so the screen is full.
+const SETTLE: Duration = Duration::from_secs(6);
+
+// The size the shell says the output has (`COLUMNS` and `LINES`), then the
+// window size of a dumb terminal, then 80x24.
+pub fn size() -> (u16, u16) {
+    let var = |name: &str| {
+        env::var(name)
+            .ok()
+            .and_then(|v| v.trim().parse::<u16>().ok())
+            .filter(|&n| n > 0)
+    };
+    let window = std::io::stdout()
+        .is_terminal()
+        .then(|| crossterm::terminal::size().ok())
+        .flatten()
+        .filter(|&(w, h)| w > 0 && h > 0)
+        .unwrap_or(DEFAULT_SIZE);
+    (
+        var("COLUMNS").unwrap_or(window.0),
+        var("LINES").unwrap_or(window.1),
+    )
+}
+
+// For `TERM=dumb` and output to files or pipes: a single frame of the rain
+// as plain text, with no escape sequences to garble logs.
+pub fn render(mut cloud: Cloud, size: (u16, u16), fps: f64) -> String {
+    let clock = ManualClock::new(Instant::now());
+    cloud.set_clock(Box::new(clock.clone()));
+    cloud.reset(size.0, size.1);
+    let mut frame = Frame::new(size.0, size.1, None);
+    let period = Duration::from_secs_f64(1.0 / fps);
+    let mut t = Duration::ZERO;
+    while t < SETTLE {
+        clock.advance(period);
+        t += period;
+        cloud.rain(&mut frame);
+    }
+
+    let mut out = String::new();
+    for line in 0..frame.height {
+        let row: String = (0..frame.width)
+            .filter_map(|col| frame.get(col, line))
+            .filter(|c| !c.is_continuation())
+            .map(|c| c.ch)
+            .collect();
+        out.push_str(row.trim_end());
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,174 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+
+use crate::runtime::ColorScheme;
+
+// `--sync-group`: instances with the same group name share a start time, so
+// periodic changes such as `--shuffle` line up, and tell each other about
+// scheme changes made with the keys. Each instance binds a datagram socket
+// in a per-group directory and sends to every other socket there.
+#[cfg_attr(not(unix), allow(dead_code))]
+pub struct SyncGroup {
+    dir: PathBuf,
+    #[cfg(unix)]
+    socket: UnixDatagram,
+    own: PathBuf,
+    epoch: SystemTime,
+}
+
+fn group_dir(name: &str) -> io::Result<PathBuf> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "group names may only use letters, digits, - and _",
+        ));
+    }
+    let base = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    Ok(base.join(format!("cosmostrix-sync-{}", name)))
+}
+
+fn millis(t: SystemTime) -> u64 {
+    t.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// The group's start time: written by the first member, read by the rest.
+fn group_epoch(dir: &std::path::Path) -> io::Result<SystemTime> {
+    let path = dir.join("epoch");
+    let ms = match fs::read_to_string(&path)
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+    {
+        Some(ms) => ms,
+        None => {
+            let ms = millis(SystemTime::now());
+            fs::write(&path, ms.to_string())?;
+            ms
+        }
+    };
+    Ok(UNIX_EPOCH + Duration::from_millis(ms))
+}
+
+impl SyncGroup {
+    #[cfg(unix)]
+    pub fn join(name: &str) -> io::Result<Self> {
+        let dir = group_dir(name)?;
+        fs::create_dir_all(&dir)?;
+        let own = dir.join(format!("{}.sock", std::process::id()));
+        let _ = fs::remove_file(&own);
+        let socket = UnixDatagram::bind(&own)?;
+        socket.set_nonblocking(true)?;
+        let mut group = Self {
+            epoch: UNIX_EPOCH,
+            dir,
+            socket,
+            own,
+        };
+        // With no other member alive, start the group's clock afresh.
+        if !group.broadcast("hello") {
+            let _ = fs::remove_file(group.dir.join("epoch"));
+        }
+        group.epoch = group_epoch(&group.dir)?;
+        Ok(group)
+    }
+
+    #[cfg(not(unix))]
+    pub fn join(_name: &str) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "sync groups need Unix domain sockets",
+        ))
+    }
+
+    // Which `period` of the group's clock it is now; the same in every member.
+    pub fn period_index(&self, period: Duration) -> u64 {
+        let elapsed = SystemTime::now()
+            .duration_since(self.epoch)
+            .unwrap_or_default();
+        (elapsed.as_millis() / period.as_millis().max(1)) as u64
+    }
+
+    pub fn send_scheme(&self, scheme: ColorScheme) {
+        self.broadcast(&format!("scheme {}", scheme.name()));
+    }
+
+    // A scheme another member switched to since the last call, if any.
+    pub fn recv_scheme(&self) -> Option<ColorScheme> {
+        let mut latest = None;
+        while let Some(msg) = self.recv() {
+            if let Some(name) = msg.strip_prefix("scheme ") {
+                latest = ColorScheme::BUILTIN
+                    .iter()
+                    .copied()
+                    .find(|s| s.name() == name)
+                    .or(latest);
+            }
+        }
+        latest
+    }
+
+    #[cfg(unix)]
+    fn recv(&self) -> Option<String> {
+        let mut buf = [0u8; 256];
+        let n = self.socket.recv(&mut buf).ok()?;
+        Some(String::from_utf8_lossy(&buf[..n]).into_owned())
+    }
+
+    #[cfg(not(unix))]
+    fn recv(&self) -> Option<String> {
+        None
+    }
+
+    // Sends to every other member, clearing out sockets left by members that
+    // are gone. Returns whether anyone got it.
+    #[cfg(unix)]
+    fn broadcast(&self, msg: &str) -> bool {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return false;
+        };
+        let mut sent = false;
+        for path in entries.flatten().map(|e| e.path()) {
+            if path == self.own || path.extension().is_none_or(|e| e != "sock") {
+                continue;
+            }
+            match self.socket.send_to(msg.as_bytes(), &path) {
+                Ok(_) => sent = true,
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::ConnectionRefused | io::ErrorKind::NotFound
+                    ) =>
+                {
+                    let _ = fs::remove_file(&path);
+                }
+                Err(e) => log::debug!("sync: {}: {}", path.display(), e),
+            }
+        }
+        sent
+    }
+
+    #[cfg(not(unix))]
+    fn broadcast(&self, _msg: &str) -> bool {
+        false
+    }
+}
+
+impl Drop for SyncGroup {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.own);
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// One rendered frame: time spent advancing and composing it, time spent
+// writing it to the terminal, and how much was sent.
+pub struct FrameRecord {
+    pub sim: Duration,
+    pub draw: Duration,
+    pub cells: usize,
+    pub bytes: usize,
+}
+
+// `--telemetry`: appends a record per frame, as JSON lines when the file
+// name ends in `.json` or `.jsonl` and as CSV otherwise.
+pub struct Telemetry {
+    out: BufWriter<File>,
+    json: bool,
+    start: Instant,
+    frames: u64,
+    sim: Duration,
+    draw: Duration,
+    worst: Duration,
+    cells: u64,
+    bytes: u64,
+}
+
+impl Telemetry {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let empty = file.metadata()?.len() == 0;
+        let json = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("json") || e.eq_ignore_ascii_case("jsonl"));
+        let mut out = BufWriter::new(file);
+        if empty && !json {
+            writeln!(out, "frame,time_ms,sim_ms,draw_ms,cells,bytes")?;
+        }
+        Ok(Self {
+            out,
+            json,
+            start: Instant::now(),
+            frames: 0,
+            sim: Duration::ZERO,
+            draw: Duration::ZERO,
+            worst: Duration::ZERO,
+            cells: 0,
+            bytes: 0,
+        })
+    }
+
+    pub fn record(&mut self, r: &FrameRecord) -> io::Result<()> {
+        self.frames += 1;
+        self.sim += r.sim;
+        self.draw += r.draw;
+        self.worst = self.worst.max(r.sim + r.draw);
+        self.cells += r.cells as u64;
+        self.bytes += r.bytes as u64;
+
+        let t = self.start.elapsed().as_secs_f64() * 1000.0;
+        let sim = r.sim.as_secs_f64() * 1000.0;
+        let draw = r.draw.as_secs_f64() * 1000.0;
+        if self.json {
+            writeln!(
+                self.out,
+                "{{\"frame\":{},\"time_ms\":{:.3},\"sim_ms\":{:.3},\"draw_ms\":{:.3},\"cells\":{},\"bytes\":{}}}",
+                self.frames, t, sim, draw, r.cells, r.bytes
+            )
+        } else {
+            writeln!(
+                self.out,
+                "{},{:.3},{:.3},{:.3},{},{}",
+                self.frames, t, sim, draw, r.cells, r.bytes
+            )
+        }
+    }
+
+    // Flushes the records and describes the whole run in a few lines.
+    pub fn finish(mut self) -> io::Result<String> {
+        self.out.flush()?;
+        let n = self.frames.max(1);
+        let secs = self.start.elapsed().as_secs_f64();
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0 / n as f64;
+        Ok(format!(
+            "{} frames in {:.1} s ({:.1} fps)\nsim avg {:.2} ms, draw avg {:.2} ms, worst frame {:.2} ms\n{:.0} cells and {:.0} bytes per frame, {} bytes in total",
+            self.frames,
+            secs,
+            self.frames as f64 / secs.max(f64::EPSILON),
+            ms(self.sim),
+            ms(self.draw),
+            self.worst.as_secs_f64() * 1000.0,
+            self.cells as f64 / n as f64,
+            self.bytes as f64 / n as f64,
+            self.bytes
+        ))
+    }
+}
@@ -3,34 +3,257 @@
 use std::io::{stdout, Result, Stdout, Write};
 
 use crossterm::{
-    cursor,
-    event,
-    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
-    terminal,
-    ExecutableCommand, QueueableCommand,
+    cursor, event,
+    style::{
+        Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+    },
+    terminal, ExecutableCommand, QueueableCommand,
 };
 
-use crate::cell::Cell;
-use crate::frame::Frame;
+use crate::frame::{changed_cells, Frame, FramePool};
+use crate::multiplexer;
+use crate::render::Renderer;
+use crate::runtime::{BorderStyle, Margin, Region};
+
+// What the last draw sent to the terminal.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DrawStats {
+    pub cells: usize,
+    pub bytes: usize,
+}
 
 pub struct Terminal {
     stdout: Stdout,
+    buf: Vec<u8>,
+    stats: DrawStats,
     last: Option<Frame>,
+    pool: FramePool,
+    mouse: bool,
+    keyboard: bool,
+    region: Option<Region>,
+    // The size the terminal last reported; the drawing area starts inside it.
+    screen: (u16, u16),
+    margin: Margin,
+    border: Option<BorderStyle>,
+    max_changes: Option<usize>,
+    scan_offset: usize,
+    // Synchronized update (mode 2026) begin and end, passed through to the
+    // outer terminal inside tmux or screen.
+    sync: Option<(String, String)>,
 }
 
+// Whether the kitty keyboard flags are pushed, so every way out pops them.
+static KEYBOARD_PUSHED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+// Whether the window title was saved on the terminal's title stack.
+static TITLE_PUSHED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+// Kitty keyboard protocol: unambiguous keys, repeat and release events, and
+// shifted symbols reported as typed (`+` rather than shift+`=`).
+const KEYBOARD_FLAGS: event::KeyboardEnhancementFlags =
+    event::KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+        .union(event::KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+        .union(event::KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS);
+
 impl Terminal {
     pub fn new() -> Result<Self> {
-        let mut out = stdout();
+        let mut term = Self {
+            stdout: stdout(),
+            buf: Vec::new(),
+            stats: DrawStats::default(),
+            last: None,
+            pool: FramePool::default(),
+            mouse: false,
+            keyboard: terminal::supports_keyboard_enhancement().unwrap_or(false),
+            region: None,
+            screen: (0, 0),
+            margin: Margin::default(),
+            border: None,
+            max_changes: None,
+            scan_offset: 0,
+            sync: vt_available().then(|| {
+                (
+                    multiplexer::passthrough("\x1b[?2026h"),
+                    multiplexer::passthrough("\x1b[?2026l"),
+                )
+            }),
+        };
+        term.enter()?;
+        Ok(term)
+    }
+
+    fn enter(&mut self) -> Result<()> {
         terminal::enable_raw_mode()?;
-        out.execute(terminal::EnterAlternateScreen)?;
-        out.execute(cursor::Hide)?;
-        out.execute(terminal::Clear(terminal::ClearType::All))?;
-        out.flush()?;
-        Ok(Self { stdout: out, last: None })
+        self.stdout.execute(terminal::EnterAlternateScreen)?;
+        self.stdout.execute(cursor::Hide)?;
+        self.stdout
+            .execute(terminal::Clear(terminal::ClearType::All))?;
+        if self.mouse {
+            self.stdout.execute(event::EnableMouseCapture)?;
+        }
+        if self.keyboard {
+            self.stdout
+                .execute(event::PushKeyboardEnhancementFlags(KEYBOARD_FLAGS))?;
+            KEYBOARD_PUSHED.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+        self.stdout.flush()?;
+        #[cfg(windows)]
+        win::fit_buffer_to_window();
+        self.forget_last();
+        Ok(())
+    }
+
+    // Forces a full redraw next time, keeping the old frame's buffer for reuse.
+    fn forget_last(&mut self) {
+        if let Some(f) = self.last.take() {
+            self.pool.give(f);
+        }
+    }
+
+    // Repaints the whole screen on the next draw, for when its contents
+    // can't be trusted (e.g. after the machine slept).
+    #[cfg(all(feature = "logind", target_os = "linux"))]
+    pub fn force_redraw(&mut self) {
+        self.forget_last();
+    }
+
+    // Hands the terminal back to the shell and stops the process, as Ctrl-Z
+    // would outside raw mode; returns once resumed, with the screen re-entered.
+    pub fn suspend(&mut self) -> Result<()> {
+        restore(&mut self.stdout, self.mouse);
+        #[cfg(unix)]
+        unsafe {
+            let prev = libc::signal(libc::SIGTSTP, libc::SIG_DFL);
+            libc::raise(libc::SIGTSTP);
+            libc::signal(libc::SIGTSTP, prev);
+        }
+        RESUMED.store(false, std::sync::atomic::Ordering::Relaxed);
+        self.enter()
+    }
+
+    // Re-enters the screen after an external stop/continue (e.g. SIGSTOP).
+    pub fn resume(&mut self) -> Result<()> {
+        self.enter()
+    }
+
+    // Sets the window title (OSC 0). The first call saves the old title
+    // (XTWINOPS 22), which is put back on exit.
+    pub fn set_title(&mut self, title: &str) -> Result<()> {
+        if !TITLE_PUSHED.swap(true, std::sync::atomic::Ordering::Relaxed) {
+            self.stdout.write_all(b"\x1b[22;0t")?;
+        }
+        self.stdout.execute(terminal::SetTitle(title))?;
+        Ok(())
+    }
+
+    pub fn enable_mouse_capture(&mut self) -> Result<()> {
+        self.stdout.execute(event::EnableMouseCapture)?;
+        self.mouse = true;
+        Ok(())
+    }
+
+    // Confines drawing to `region`; sizes reported afterwards are the
+    // region's, clipped to the terminal, and one that starts past the edge
+    // is drawn at the edge.
+    pub fn set_region(&mut self, region: Option<Region>) {
+        self.region = region;
+        self.forget_last();
+    }
+
+    // Keeps `margin` blank around the drawing area and, with a `border`, a
+    // box just inside the margin.
+    pub fn set_frame_style(&mut self, margin: Margin, border: Option<BorderStyle>) {
+        self.margin = margin;
+        self.border = border;
+        self.forget_last();
+    }
+
+    // Margin plus border, per side.
+    fn inset(&self) -> Margin {
+        let b = self.border.is_some() as u16;
+        Margin {
+            top: self.margin.top.saturating_add(b),
+            right: self.margin.right.saturating_add(b),
+            bottom: self.margin.bottom.saturating_add(b),
+            left: self.margin.left.saturating_add(b),
+        }
+    }
+
+    // Caps how many changed cells one draw sends; the rest are sent on later
+    // frames, starting where the previous draw stopped.
+    pub fn set_max_changes(&mut self, max: Option<usize>) {
+        self.max_changes = max;
+    }
+
+    fn clip(&mut self, (width, height): (u16, u16)) -> (u16, u16) {
+        self.screen = (width, height);
+        let (width, height) = match self.region {
+            Some(r) => (
+                r.width.min(width.saturating_sub(r.x)).max(1),
+                r.height.min(height.saturating_sub(r.y)).max(1),
+            ),
+            None => (width, height),
+        };
+        let m = self.inset();
+        (
+            width.saturating_sub(m.left.saturating_add(m.right)).max(1),
+            height.saturating_sub(m.top.saturating_add(m.bottom)).max(1),
+        )
+    }
+
+    fn origin(&self) -> (u16, u16) {
+        let (x, y) = self.region.map(|r| (r.x, r.y)).unwrap_or((0, 0));
+        let m = self.inset();
+        let (w, h) = self.screen;
+        (
+            x.saturating_add(m.left).min(w.saturating_sub(1)),
+            y.saturating_add(m.top).min(h.saturating_sub(1)),
+        )
     }
 
-    pub fn size(&self) -> Result<(u16, u16)> {
-        terminal::size()
+    // Boxes in a `width` x `height` drawing area at `origin`.
+    fn queue_border(&mut self, (ox, oy): (u16, u16), width: u16, height: u16) -> Result<()> {
+        let Some(style) = self.border else {
+            return Ok(());
+        };
+        let [tl, tr, bl, br, h, v] = style.chars();
+        let (left, top) = (ox.saturating_sub(1), oy.saturating_sub(1));
+        // crossterm counts from 1 on the wire, so the last coordinate is unusable.
+        let (right, bottom) = (
+            ox.saturating_add(width).min(u16::MAX - 1),
+            oy.saturating_add(height).min(u16::MAX - 1),
+        );
+        let edge: String = std::iter::repeat_n(h, width as usize).collect();
+        self.stdout.queue(ResetColor)?;
+        self.stdout.queue(cursor::MoveTo(left, top))?;
+        self.stdout.queue(Print(format!("{}{}{}", tl, edge, tr)))?;
+        for y in oy..bottom {
+            self.stdout.queue(cursor::MoveTo(left, y))?;
+            self.stdout.queue(Print(v))?;
+            self.stdout.queue(cursor::MoveTo(right, y))?;
+            self.stdout.queue(Print(v))?;
+        }
+        self.stdout.queue(cursor::MoveTo(left, bottom))?;
+        self.stdout.queue(Print(format!("{}{}{}", bl, edge, br)))?;
+        Ok(())
+    }
+
+    pub fn size(&mut self) -> Result<(u16, u16)> {
+        Ok(self.clip(terminal::size()?))
+    }
+
+    // Size to use after a resize event. conhost reports the screen buffer
+    // size, and Windows Terminal is off by one, so Windows re-reads the window.
+    pub fn resized(&mut self, width: u16, height: u16) -> Result<(u16, u16)> {
+        self.forget_last();
+        #[cfg(windows)]
+        {
+            let _ = (width, height);
+            win::fit_buffer_to_window();
+            self.size()
+        }
+        #[cfg(not(windows))]
+        Ok(self.clip((width, height)))
     }
 
     pub fn poll_event(timeout: std::time::Duration) -> Result<bool> {
@@ -41,100 +264,473 @@ impl Terminal {
         event::read()
     }
 
-    pub fn draw(&mut self, frame: &Frame) -> Result<()> {
-        let mut cur_fg: Option<Color> = None;
-        let mut cur_bg: Option<Color> = None;
-        let mut cur_bold: bool = false;
+    // Writes a pre-encoded image frame; the next cell draw repaints everything.
+    #[cfg(any(feature = "kitty", feature = "sixel"))]
+    pub fn draw_raw(&mut self, seq: &str) -> Result<()> {
+        let (ox, oy) = self.origin();
+        self.stdout.queue(cursor::MoveTo(ox, oy))?;
+        self.stdout.write_all(seq.as_bytes())?;
+        self.stdout.flush()?;
+        self.stats = DrawStats {
+            cells: 0,
+            bytes: seq.len(),
+        };
+        self.forget_last();
+        Ok(())
+    }
+
+    pub fn last_draw(&self) -> DrawStats {
+        self.stats
+    }
 
+    pub fn draw(&mut self, frame: &Frame) -> Result<()> {
         let needs_full_redraw = self
             .last
             .as_ref()
             .map(|l| l.width != frame.width || l.height != frame.height)
             .unwrap_or(true);
 
-        if needs_full_redraw {
+        if needs_full_redraw && self.region.is_none() {
             self.stdout
                 .queue(terminal::Clear(terminal::ClearType::All))?;
         }
+        let (ox, oy) = self.origin();
+        if needs_full_redraw {
+            self.queue_border((ox, oy), frame.width, frame.height)?;
+        }
 
-        for y in 0..frame.height {
-            for x in 0..frame.width {
-                let idx = y as usize * frame.width as usize + x as usize;
-                let cell = frame.cells[idx];
-                let changed = if needs_full_redraw {
-                    true
-                } else {
-                    self.last
-                        .as_ref()
-                        .and_then(|l| l.cells.get(idx).copied())
-                        .map(|prev| prev != cell)
-                        .unwrap_or(true)
-                };
-
-                if !changed {
-                    continue;
-                }
+        let mut changed = changed_cells(self.last.as_ref(), frame);
+        if let Some(max) = self
+            .max_changes
+            .filter(|&m| !needs_full_redraw && changed.len() > m)
+        {
+            let start = changed.partition_point(|&i| i < self.scan_offset) % changed.len();
+            changed.rotate_left(start);
+            changed.truncate(max);
+            self.scan_offset = changed.last().map(|&i| i + 1).unwrap_or(0);
+            changed.sort_unstable();
+        }
 
-                self.stdout.queue(cursor::MoveTo(x, y))?;
+        self.buf.clear();
+        let sync = self.sync.as_ref().filter(|_| !changed.is_empty());
+        if let Some((begin, _)) = sync {
+            self.buf.extend_from_slice(begin.as_bytes());
+        }
+        queue_cells(&mut self.buf, frame, &changed, (ox, oy))?;
+        if let Some((_, end)) = sync {
+            self.buf.extend_from_slice(end.as_bytes());
+        }
+        self.stdout.write_all(&self.buf)?;
+        self.stdout.flush()?;
+        self.stats = DrawStats {
+            cells: changed.len(),
+            bytes: self.buf.len(),
+        };
 
-                if cell.fg != cur_fg {
-                    if let Some(fg) = cell.fg {
-                        self.stdout.queue(SetForegroundColor(fg))?;
-                    } else {
-                        self.stdout.queue(SetForegroundColor(Color::Reset))?;
+        match self.last.as_mut() {
+            Some(last) if !needs_full_redraw && self.max_changes.is_some() => {
+                for &idx in &changed {
+                    last.cells[idx] = frame.cells[idx];
+                    if frame.cells[idx].width() == 2 && idx + 1 < last.cells.len() {
+                        last.cells[idx + 1] = frame.cells[idx + 1];
                     }
-                    cur_fg = cell.fg;
                 }
+            }
+            Some(last) => last.clone_from(frame),
+            None => self.last = Some(self.pool.copy_of(frame)),
+        }
+        Ok(())
+    }
+}
 
-                if cell.bg != cur_bg {
-                    if let Some(bg) = cell.bg {
-                        self.stdout.queue(SetBackgroundColor(bg))?;
-                    } else {
-                        self.stdout.queue(SetBackgroundColor(Color::Reset))?;
-                    }
-                    cur_bg = cell.bg;
-                }
+impl Renderer for Terminal {
+    fn draw(&mut self, frame: &Frame) -> Result<()> {
+        Terminal::draw(self, frame)
+    }
+}
 
-                if cell.bold != cur_bold {
-                    self.stdout.queue(SetAttribute(if cell.bold {
-                        Attribute::Bold
-                    } else {
-                        Attribute::NormalIntensity
-                    }))?;
-                    cur_bold = cell.bold;
-                }
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        restore(&mut self.stdout, self.mouse);
+    }
+}
 
-                let mut buf = [0u8; 4];
-                let s = cell.ch.encode_utf8(&mut buf);
-                self.stdout.queue(Print(s))?;
+// Writes the given cells of `frame` (indexes into `frame.cells`) with their
+// colors, offset by `origin`. Cursor moves are skipped between adjacent cells.
+pub fn queue_cells<W: Write>(
+    out: &mut W,
+    frame: &Frame,
+    cells: &[usize],
+    origin: (u16, u16),
+) -> Result<()> {
+    let (ox, oy) = origin;
+    let mut cur_fg: Option<Color> = None;
+    let mut cur_bg: Option<Color> = None;
+    let mut cur_bold: bool = false;
+
+    let mut cursor: Option<(u16, u16)> = None;
+    for &idx in cells {
+        let cell = frame.cells[idx];
+        let x = (idx % frame.width as usize) as u16;
+        let y = (idx / frame.width as usize) as u16;
+
+        // Past the last coordinate crossterm can address.
+        let to = |o: u16, v: u16| o.checked_add(v).filter(|&p| p < u16::MAX);
+        let (Some(sx), Some(sy)) = (to(ox, x), to(oy, y)) else {
+            cursor = None;
+            continue;
+        };
+        if cursor != Some((x, y)) {
+            out.queue(cursor::MoveTo(sx, sy))?;
+        }
+
+        if cell.fg != cur_fg {
+            if let Some(fg) = cell.fg {
+                out.queue(SetForegroundColor(fg))?;
+            } else {
+                out.queue(SetForegroundColor(Color::Reset))?;
             }
+            cur_fg = cell.fg;
         }
 
-        self.stdout.queue(SetAttribute(Attribute::Reset))?;
-        self.stdout.queue(ResetColor)?;
-        self.stdout.flush()?;
+        if cell.bg != cur_bg {
+            if let Some(bg) = cell.bg {
+                out.queue(SetBackgroundColor(bg))?;
+            } else {
+                out.queue(SetBackgroundColor(Color::Reset))?;
+            }
+            cur_bg = cell.bg;
+        }
 
-        self.last = Some(frame.clone());
-        Ok(())
+        if cell.bold != cur_bold {
+            out.queue(SetAttribute(if cell.bold {
+                Attribute::Bold
+            } else {
+                Attribute::NormalIntensity
+            }))?;
+            cur_bold = cell.bold;
+        }
+
+        let mut buf = [0u8; 4];
+        let s = cell.ch.encode_utf8(&mut buf);
+        out.queue(Print(s))?;
+        cursor = Some((x + cell.width(), y));
     }
+
+    out.queue(SetAttribute(Attribute::Reset))?;
+    out.queue(ResetColor)?;
+    Ok(())
 }
 
-impl Drop for Terminal {
-    fn drop(&mut self) {
-        let _ = self.stdout.execute(SetAttribute(Attribute::Reset));
-        let _ = self.stdout.execute(ResetColor);
-        let _ = self.stdout.execute(cursor::Show);
-        let _ = self.stdout.execute(terminal::LeaveAlternateScreen);
+fn restore(out: &mut Stdout, mouse: bool) {
+    if mouse {
+        let _ = out.execute(event::DisableMouseCapture);
+    }
+    if KEYBOARD_PUSHED.swap(false, std::sync::atomic::Ordering::Relaxed) {
+        let _ = out.execute(event::PopKeyboardEnhancementFlags);
+    }
+    if TITLE_PUSHED.swap(false, std::sync::atomic::Ordering::Relaxed) {
+        let _ = out.write_all(b"\x1b[23;0t");
+    }
+    let _ = out.execute(SetAttribute(Attribute::Reset));
+    let _ = out.execute(ResetColor);
+    let _ = out.execute(cursor::Show);
+    let _ = out.execute(terminal::LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+    let _ = out.flush();
+}
+
+// Puts the terminal back before the panic message is printed, so it is
+// readable and the shell isn't left in raw mode with a hidden cursor.
+pub fn install_panic_hook() {
+    let default = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore(&mut stdout(), true);
+        default(info);
+    }));
+}
+
+static INTERRUPTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+static SUSPEND: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+static RESUMED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+// Signal handlers only raise flags. On SIGINT/SIGTERM/SIGHUP the main loop
+// exits through the normal path, whose Drop restores the terminal; SIGTSTP
+// and SIGCONT are turned into suspend()/resume() calls.
+#[cfg(unix)]
+pub fn install_signal_handlers() {
+    extern "C" fn on_signal(sig: libc::c_int) {
+        let flag = match sig {
+            libc::SIGTSTP => &SUSPEND,
+            libc::SIGCONT => &RESUMED,
+            _ => &INTERRUPTED,
+        };
+        flag.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+    let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    for sig in [
+        libc::SIGINT,
+        libc::SIGTERM,
+        libc::SIGHUP,
+        libc::SIGTSTP,
+        libc::SIGCONT,
+    ] {
+        unsafe {
+            libc::signal(sig, handler);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn install_signal_handlers() {}
+
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+// Round trip of a device status report, as a proxy for how slowly output
+// reaches the terminal (e.g. over SSH).
+pub fn measure_latency() -> Option<std::time::Duration> {
+    let start = std::time::Instant::now();
+    query_reply("\x1b[5n", std::time::Duration::from_millis(500), |s| {
+        s.ends_with('n')
+    })?;
+    Some(start.elapsed())
+}
+
+pub fn take_suspend_request() -> bool {
+    SUSPEND.swap(false, std::sync::atomic::Ordering::Relaxed)
+}
+
+pub fn take_resumed() -> bool {
+    RESUMED.swap(false, std::sync::atomic::Ordering::Relaxed)
+}
+
+// Output that can't take escape sequences: `TERM=dumb`, or stdout going to
+// a file or pipe. Nothing is queried and the rain isn't animated there.
+pub fn is_dumb() -> bool {
+    use std::io::IsTerminal;
+
+    !std::io::stdout().is_terminal()
+        || std::env::var("TERM").is_ok_and(|t| t.trim().eq_ignore_ascii_case("dumb"))
+}
+
+#[cfg(unix)]
+pub fn query_reply(
+    seq: &str,
+    timeout: std::time::Duration,
+    is_done: impl Fn(&str) -> bool,
+) -> Option<String> {
+    use std::io::IsTerminal;
+    use std::os::unix::io::AsRawFd;
+
+    if is_dumb() || !std::io::stdin().is_terminal() {
+        return None;
+    }
+
+    let was_raw = terminal::is_raw_mode_enabled().unwrap_or(false);
+    if !was_raw {
+        terminal::enable_raw_mode().ok()?;
+    }
+
+    let mut out = stdout();
+    let _ = out.write_all(seq.as_bytes());
+    let _ = out.flush();
+
+    let fd = std::io::stdin().as_raw_fd();
+    let deadline = std::time::Instant::now() + timeout;
+    let mut reply: Vec<u8> = Vec::new();
+    loop {
+        let left = deadline.saturating_duration_since(std::time::Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        let mut pfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ready = unsafe { libc::poll(&mut pfd, 1, left.as_millis().max(1) as libc::c_int) };
+        if ready <= 0 {
+            break;
+        }
+        let mut buf = [0u8; 256];
+        let n = unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+        if n <= 0 {
+            break;
+        }
+        reply.extend_from_slice(&buf[..n as usize]);
+        if is_done(&String::from_utf8_lossy(&reply)) {
+            break;
+        }
+    }
+
+    if !was_raw {
         let _ = terminal::disable_raw_mode();
-        let _ = self.stdout.flush();
     }
+
+    if reply.is_empty() {
+        None
+    } else {
+        Some(String::from_utf8_lossy(&reply).into_owned())
+    }
+}
+
+#[cfg(not(unix))]
+pub fn query_reply(
+    _seq: &str,
+    _timeout: std::time::Duration,
+    _is_done: impl Fn(&str) -> bool,
+) -> Option<String> {
+    None
+}
+
+pub fn osc_reply_done(s: &str) -> bool {
+    s.ends_with('\x07') || s.ends_with("\x1b\\")
+}
+
+// Parses the `rgb:RRRR/GGGG/BBBB` payload of an OSC color reply.
+pub fn parse_osc_rgb(s: &str) -> Option<(u8, u8, u8)> {
+    let spec = &s[s.find("rgb:")? + 4..];
+    let mut parts = spec.split('/').map(|p| {
+        let p: String = p
+            .chars()
+            .take_while(|c| c.is_ascii_hexdigit())
+            .take(4)
+            .collect();
+        let v = u32::from_str_radix(&p, 16).ok()?;
+        let max = (1u32 << (4 * p.len() as u32)) - 1;
+        Some((v * 255 / max.max(1)) as u8)
+    });
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+pub fn query_palette(indexes: &[u8]) -> Vec<Option<(u8, u8, u8)>> {
+    let mut seq = String::new();
+    for i in indexes {
+        seq.push_str(&format!("\x1b]4;{};?\x07", i));
+    }
+    let seq = multiplexer::passthrough(&seq);
+    let want = indexes.len();
+    let done = |s: &str| s.matches('\x07').count() + s.matches("\x1b\\").count() >= want;
+    let reply = query_reply(&seq, std::time::Duration::from_millis(200), done).unwrap_or_default();
+
+    let mut out = vec![None; want];
+    for part in reply.split("\x1b]4;").skip(1) {
+        let Some((idx, rest)) = part.split_once(';') else {
+            continue;
+        };
+        let Ok(idx) = idx.parse::<u8>() else {
+            continue;
+        };
+        if let Some(pos) = indexes.iter().position(|&i| i == idx) {
+            out[pos] = parse_osc_rgb(rest);
+        }
+    }
+    out
+}
+
+pub fn query_background() -> Option<(u8, u8, u8)> {
+    let reply = query_reply(
+        &multiplexer::passthrough("\x1b]11;?\x07"),
+        std::time::Duration::from_millis(100),
+        osc_reply_done,
+    )?;
+    parse_osc_rgb(&reply)
+}
+
+pub fn detect_light_background() -> Option<bool> {
+    if let Ok(v) = std::env::var("COLORFGBG") {
+        if let Some(bg) = v.rsplit(';').next().and_then(|b| b.parse::<u8>().ok()) {
+            return Some(bg == 7 || bg == 15);
+        }
+    }
+
+    let (r, g, b) = query_background()?;
+    let luma = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+    Some(luma > 128.0)
+}
+
+// Whether the console interprets escape sequences. On Windows this enables
+// VT processing once; without it crossterm falls back to the 16-color console API.
+pub fn vt_available() -> bool {
+    #[cfg(windows)]
+    {
+        win::enable_vt()
+    }
+    #[cfg(not(windows))]
+    true
+}
+
+#[cfg(windows)]
+mod win {
+    use std::sync::OnceLock;
+
+    use crossterm_winapi::{ConsoleMode, ScreenBuffer};
+
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+    pub fn enable_vt() -> bool {
+        static VT: OnceLock<bool> = OnceLock::new();
+        *VT.get_or_init(|| {
+            let Ok(console) = ConsoleMode::new() else {
+                return false;
+            };
+            let Ok(mode) = console.mode() else {
+                return false;
+            };
+            if mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0 {
+                return true;
+            }
+            console
+                .set_mode(mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING)
+                .is_ok()
+                && console
+                    .mode()
+                    .is_ok_and(|m| m & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0)
+        })
+    }
+
+    // The legacy alternate screen is a fresh buffer as tall as the main one;
+    // shrink it to the visible window so nothing scrolls out of view.
+    pub fn fit_buffer_to_window() {
+        if enable_vt() {
+            return;
+        }
+        let Ok(buffer) = ScreenBuffer::current() else {
+            return;
+        };
+        let Ok(info) = buffer.info() else {
+            return;
+        };
+        let window = info.terminal_size();
+        let size = info.buffer_size();
+        if size.width != window.width + 1 || size.height != window.height + 1 {
+            let _ = buffer.set_size(window.width + 1, window.height + 1);
+        }
+    }
+}
+
+// Primary device attributes: parameter 4 advertises sixel graphics.
+pub fn supports_sixel() -> bool {
+    let Some(reply) = query_reply("\x1b[c", std::time::Duration::from_millis(200), |s| {
+        s.contains("[?") && s.ends_with('c')
+    }) else {
+        return false;
+    };
+    reply
+        .split("[?")
+        .nth(1)
+        .and_then(|r| r.split('c').next())
+        .is_some_and(|attrs| attrs.split(';').any(|a| a == "4"))
 }
 
-pub fn blank_cell(bg: Option<Color>) -> Cell {
-    Cell {
-        ch: ' ',
-        fg: None,
-        bg,
-        bold: false,
+// Size of one cell in pixels, from the window size the terminal reports.
+#[cfg(feature = "sixel")]
+pub fn cell_pixel_size() -> Option<(u16, u16)> {
+    let ws = terminal::window_size().ok()?;
+    if ws.width == 0 || ws.height == 0 || ws.columns == 0 || ws.rows == 0 {
+        return None;
     }
+    Some((ws.width / ws.columns, ws.height / ws.rows))
 }
@@ -0,0 +1,72 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::io::{stdout, Result, Write};
+use std::path::Path;
+
+use crossterm::queue;
+use crossterm::style::{Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+
+use crate::colorfile::{builtin_names, load_user_colors, user_names};
+use crate::palette::{apply_light, scheme_palette, Palette};
+use crate::runtime::{ColorMode, ColorScheme, UserColors};
+
+const BLOCK: &str = "\u{2588}\u{2588}";
+
+fn palette(
+    scheme: ColorScheme,
+    user: Option<&UserColors>,
+    mode: ColorMode,
+    light: bool,
+    defaultbg: bool,
+) -> Palette {
+    let mut p = scheme_palette(scheme, mode, defaultbg, user);
+    if light {
+        apply_light(&mut p, scheme, mode, defaultbg);
+    }
+    p
+}
+
+fn swatch(out: &mut impl Write, name: &str, p: &Palette) -> Result<()> {
+    queue!(out, Print(format!("{:<14}", name)))?;
+    if let Some(bg) = p.bg {
+        queue!(out, SetBackgroundColor(bg))?;
+    }
+    for &c in &p.colors {
+        queue!(out, SetForegroundColor(c), Print(BLOCK))?;
+    }
+    queue!(out, ResetColor, Print("\n"))
+}
+
+// Prints one strip per built-in scheme, then per color file (user files
+// first), drawn in `mode` so they look as they would in the rain.
+pub fn print(mode: ColorMode, light: bool, defaultbg: bool) -> Result<()> {
+    let mut out = stdout().lock();
+    for scheme in ColorScheme::BUILTIN {
+        swatch(
+            &mut out,
+            scheme.name(),
+            &palette(scheme, None, mode, light, defaultbg),
+        )?;
+    }
+
+    let mut files = user_names();
+    let builtin: Vec<String> = builtin_names()
+        .map(String::from)
+        .filter(|n| !files.contains(n))
+        .collect();
+    files.extend(builtin);
+    if !files.is_empty() {
+        queue!(out, Print("\ncolor files (--colorfile NAME):\n"))?;
+    }
+    for name in files {
+        match load_user_colors(Path::new(&name)) {
+            Ok(uc) => swatch(
+                &mut out,
+                &name,
+                &palette(ColorScheme::User, Some(&uc), mode, light, defaultbg),
+            )?,
+            Err(e) => queue!(out, Print(format!("{:<14}{}\n", name, e)))?,
+        }
+    }
+    out.flush()
+}
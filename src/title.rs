@@ -0,0 +1,56 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use rand::rngs::ThreadRng;
+use rand::seq::IndexedRandom;
+use unicode_width::UnicodeWidthChar;
+
+const LEN: usize = 16;
+const STEP: Duration = Duration::from_millis(150);
+
+// `--title-rain`: a short run of random glyphs scrolling through the window
+// title, one new glyph in from the right every step.
+pub struct TitleRain {
+    chars: Vec<char>,
+    text: VecDeque<char>,
+    last: Instant,
+    rng: ThreadRng,
+}
+
+impl TitleRain {
+    // Wide glyphs are left out, as tab titles are often cut by width.
+    pub fn new(chars: &[char]) -> Self {
+        let mut chars: Vec<char> = chars
+            .iter()
+            .copied()
+            .filter(|c| c.width() == Some(1))
+            .collect();
+        if chars.is_empty() {
+            chars = ('!'..='~').collect();
+        }
+        let mut rng = rand::rng();
+        let text = (0..LEN)
+            .map(|_| *chars.choose(&mut rng).unwrap_or(&' '))
+            .collect();
+        Self {
+            chars,
+            text,
+            last: Instant::now(),
+            rng,
+        }
+    }
+
+    // The new title when it is time to move on.
+    pub fn tick(&mut self) -> Option<String> {
+        if self.last.elapsed() < STEP {
+            return None;
+        }
+        self.last = Instant::now();
+        self.text.pop_front();
+        self.text
+            .push_back(*self.chars.choose(&mut self.rng).unwrap_or(&' '));
+        Some(self.text.iter().collect())
+    }
+}
@@ -0,0 +1,449 @@
+// Copyright (c) 2025 rezk_nightky
+
+use crate::charset::{charset_from_str, parse_user_hex_chars, Charset};
+use crate::config::{Args, Command};
+use crate::error::CosmostrixError;
+use crate::listener::parse_listen_addr;
+use crate::panes::parse_grid;
+use crate::runtime::ColorScheme;
+use crate::scene::SceneKind;
+use crate::{
+    logger, parse_auto_theme, parse_border, parse_collision, parse_color_override,
+    parse_color_scheme, parse_density_profile, parse_glitch_mode, parse_margin, parse_metrics_mode,
+    parse_region, parse_shade_curve, parse_size, parse_span, parse_typing_mode, parse_wave_start,
+};
+
+const COLOR_MODES: [(u16, &str); 5] = [(0, "0"), (8, "8"), (16, "16"), (256, "256"), (32, "32")];
+
+const SHELLS: [&str; 3] = ["bash", "zsh", "fish"];
+
+// Longest span, in seconds, a timing option may ask for: about 30 years, far
+// inside what a `Duration` holds.
+const MAX_SECS: f32 = 1e9;
+
+const BACKENDS: &[&str] = &[
+    "auto",
+    "cell",
+    #[cfg(feature = "kitty")]
+    "kitty",
+    #[cfg(feature = "sixel")]
+    "sixel",
+];
+
+// The names an option accepts, for suggestions and shell completion. Empty
+// for options that take free-form values.
+pub fn choices(option: &str) -> Vec<&'static str> {
+    match option {
+        "color" | "pane-colors" => ColorScheme::BUILTIN
+            .iter()
+            .map(|s| s.name())
+            .chain(["user", "terminal"])
+            .collect(),
+        "charset" | "pane-charsets" => {
+            let names = Charset::NAMED
+                .iter()
+                .chain(&Charset::ALIASES)
+                .map(|&(n, _)| n);
+            std::iter::once("auto").chain(names).collect()
+        }
+        "backend" => BACKENDS.to_vec(),
+        "scene" => SceneKind::ALL.iter().map(|k| k.name()).collect(),
+        "scene-input" => vec!["random", "stdin"],
+        "low-bandwidth" => vec!["on", "off", "auto"],
+        "shade-curve" => vec!["linear", "quadratic", "exp"],
+        "glitch-mode" => vec!["global", "cell"],
+        "collision" => vec!["pass", "merge", "queue"],
+        "density-profile" => vec!["uniform", "center", "edges", "gradient-left"],
+        "border" => vec!["single", "double", "none"],
+        "log-level" => vec!["off", "error", "warn", "info", "debug", "trace"],
+        "wave-start" => vec!["left", "center"],
+        "typing" => vec!["random", "cursor"],
+        "metrics" => vec!["cpu", "mem", "net"],
+        "colormode" => COLOR_MODES.iter().map(|&(_, n)| n).collect(),
+        "shadingmode" | "bold" => vec!["0", "1", "2"],
+        "shell" => SHELLS.to_vec(),
+        _ => Vec::new(),
+    }
+}
+
+// Checks every option after the command line and config file are merged,
+// so all the mistakes are reported in one go instead of one per run. Only
+// looks at the values: nothing here touches the terminal or the network.
+pub fn check(args: &Args) -> Result<(), CosmostrixError> {
+    let mut errors = Vec::new();
+    let mut push = |option: &'static str, reason: String| {
+        errors.push(CosmostrixError::invalid(option, reason))
+    };
+
+    if let Some(m) = args
+        .colormode
+        .filter(|&m| COLOR_MODES.iter().all(|&(v, _)| v != m))
+    {
+        // 24-bit color is asked for with 32.
+        let near = if m == 24 {
+            32
+        } else {
+            COLOR_MODES
+                .iter()
+                .map(|&(v, _)| v)
+                .min_by_key(|v| v.abs_diff(m))
+                .unwrap_or(256)
+        };
+        push(
+            "colormode",
+            format!(
+                "{} is not valid (expected 0, 8, 16, 256 or 32); did you mean {}?",
+                m, near
+            ),
+        );
+    }
+    if args.shading_mode > 2 {
+        push(
+            "shadingmode",
+            format!("expected 0, 1 or 2, got: {}", args.shading_mode),
+        );
+    }
+    if args.head_len == 0 {
+        push("head-len", "expected at least 1".to_string());
+    }
+    if args.bold > 2 {
+        push("bold", format!("expected 0, 1 or 2, got: {}", args.bold));
+    }
+
+    if let Err(e) = parse_color_scheme(&args.color) {
+        push("color", hint(e, &args.color, "color"));
+    }
+    for s in list(&args.pane_colors) {
+        if let Err(e) = parse_color_scheme(s) {
+            push("pane-colors", hint(e, s, "color"));
+        }
+    }
+    if let Err(e) = charset_from_str(&args.charset, true) {
+        push("charset", hint(e.to_string(), &args.charset, "charset"));
+    }
+    for s in list(&args.pane_charsets) {
+        if let Err(e) = charset_from_str(s, true) {
+            push("pane-charsets", hint(e.to_string(), s, "charset"));
+        }
+    }
+    if let Some(spec) = &args.chars {
+        match parse_user_hex_chars(spec) {
+            Ok(list) if list.len() % 2 != 0 => push(
+                "chars",
+                "odd number of unicode chars given (must be even)".to_string(),
+            ),
+            Ok(_) => {}
+            Err(CosmostrixError::InvalidChars(reason)) => push("chars", reason),
+            Err(e) => push("chars", e.to_string()),
+        }
+    }
+
+    let backend = args.backend.trim().to_ascii_lowercase();
+    if !BACKENDS.contains(&backend.as_str()) {
+        push(
+            "backend",
+            hint(
+                format!("unsupported backend: {}", args.backend),
+                &backend,
+                "backend",
+            ),
+        );
+    } else if backend == "cell" && args.bg_image.is_some() {
+        push("bg-image", "needs --backend kitty or sixel".to_string());
+    }
+    if SceneKind::from_name(&args.scene).is_none() {
+        push(
+            "scene",
+            hint(
+                format!("unknown scene: {}", args.scene),
+                &args.scene,
+                "scene",
+            ),
+        );
+    }
+    let input = args.scene_input.trim().to_ascii_lowercase();
+    if input != "random" && input != "stdin" {
+        push(
+            "scene-input",
+            hint(
+                format!("expected random or stdin, got: {}", input),
+                &input,
+                "scene-input",
+            ),
+        );
+    }
+    let low = args.low_bandwidth.trim().to_ascii_lowercase();
+    if !["on", "true", "1", "off", "false", "0", "auto"].contains(&low.as_str()) {
+        push(
+            "low-bandwidth",
+            hint(
+                format!("expected on, off or auto, got: {}", low),
+                &low,
+                "low-bandwidth",
+            ),
+        );
+    }
+
+    if let Err(e) = parse_shade_curve(&args.shade_curve) {
+        push("shade-curve", hint(e, &args.shade_curve, "shade-curve"));
+    }
+    if let Err(e) = parse_glitch_mode(&args.glitch_mode) {
+        push("glitch-mode", hint(e, &args.glitch_mode, "glitch-mode"));
+    }
+    if let Err(e) = parse_collision(&args.collision) {
+        push("collision", hint(e, &args.collision, "collision"));
+    }
+    if let Err(e) = parse_density_profile(&args.density_profile) {
+        push(
+            "density-profile",
+            hint(e, &args.density_profile, "density-profile"),
+        );
+    }
+    if let Err(e) = parse_border(&args.border) {
+        push("border", hint(e, &args.border, "border"));
+    }
+    if let Err(e) = logger::parse_level(&args.log_level) {
+        push("log-level", hint(e, &args.log_level, "log-level"));
+    }
+    if let Some(Err(e)) = args.wave_start.as_deref().map(parse_wave_start) {
+        push(
+            "wave-start",
+            hint(e, args.wave_start.as_deref().unwrap_or(""), "wave-start"),
+        );
+    }
+    if let Some(Err(e)) = args.typing.as_deref().map(parse_typing_mode) {
+        push(
+            "typing",
+            hint(e, args.typing.as_deref().unwrap_or(""), "typing"),
+        );
+    }
+    if let Some(Err(e)) = args.metrics.as_deref().map(parse_metrics_mode) {
+        push(
+            "metrics",
+            hint(e, args.metrics.as_deref().unwrap_or(""), "metrics"),
+        );
+    }
+
+    if let Some(Err(e)) = args.auto_theme.as_deref().map(parse_auto_theme) {
+        push("auto-theme", e);
+    }
+    if let Some(Err(e)) = args.head_color.as_deref().map(parse_color_override) {
+        push("head-color", e);
+    }
+    if let Some(Err(e)) = args.tail_color.as_deref().map(parse_color_override) {
+        push("tail-color", e);
+    }
+    if let Some(Err(e)) = args.region.as_deref().map(parse_region) {
+        push("region", e);
+    }
+    if let Some(Err(e)) = args.margin.as_deref().map(parse_margin) {
+        push("margin", e);
+    }
+    if let Some(Err(e)) = args.panes.as_deref().map(parse_grid) {
+        push("panes", e);
+    }
+    if let Some(Err(e)) = args.listen.as_deref().map(parse_listen_addr) {
+        push("listen", e);
+    }
+    if let Some(Err(e)) = args.sleep_after.as_deref().map(parse_span) {
+        push("sleep-after", e);
+    }
+
+    if !args.speed_jitter.is_finite() {
+        push(
+            "speed-jitter",
+            format!("expected a percentage, got: {}", args.speed_jitter),
+        );
+    }
+    if !(args.time_scale.is_finite() && args.time_scale > 0.0) {
+        push(
+            "time-scale",
+            format!("expected a positive number, got: {}", args.time_scale),
+        );
+    }
+    if let Some(secs) = args
+        .duration
+        .filter(|s| !(s.is_finite() && *s > 0.0 && *s <= MAX_SECS))
+    {
+        push(
+            "duration",
+            format!(
+                "expected a positive number of seconds up to {}, got: {}",
+                MAX_SECS, secs
+            ),
+        );
+    }
+    if let Some(secs) = args
+        .hue_cycle
+        .filter(|s| !(s.is_finite() && *s > 0.0 && *s <= MAX_SECS))
+    {
+        push(
+            "hue-cycle",
+            format!(
+                "expected a positive number of seconds up to {}, got: {}",
+                MAX_SECS, secs
+            ),
+        );
+    }
+    if let Some(secs) = args
+        .reshade
+        .filter(|s| !(s.is_finite() && *s > 0.0 && *s <= MAX_SECS))
+    {
+        push(
+            "reshade",
+            format!(
+                "expected a positive number of seconds up to {}, got: {}",
+                MAX_SECS, secs
+            ),
+        );
+    }
+    if !(args.marquee_speed.is_finite() && args.marquee_speed >= 0.0) {
+        push(
+            "marquee-speed",
+            format!(
+                "expected a non-negative number of columns per second, got: {}",
+                args.marquee_speed
+            ),
+        );
+    }
+    if let Some(rate) = args.spawn_rate.filter(|r| !(r.is_finite() && *r >= 0.0)) {
+        push(
+            "spawn-rate",
+            format!(
+                "expected a non-negative number of droplets per second, got: {}",
+                rate
+            ),
+        );
+    }
+    if let Some(secs) = args
+        .shuffle
+        .filter(|s| !(s.is_finite() && *s > 0.0 && *s <= MAX_SECS))
+    {
+        push(
+            "shuffle",
+            format!(
+                "expected a positive number of seconds up to {}, got: {}",
+                MAX_SECS, secs
+            ),
+        );
+    }
+    if !(args.message_delay.is_finite() && (0.0..=MAX_SECS).contains(&args.message_delay)) {
+        push(
+            "message-delay",
+            format!(
+                "expected 0 to {} seconds, got: {}",
+                MAX_SECS, args.message_delay
+            ),
+        );
+    }
+    if let Some(secs) = args
+        .message_hold
+        .filter(|s| !(s.is_finite() && (0.0..=MAX_SECS).contains(s)))
+    {
+        push(
+            "message-hold",
+            format!("expected 0 to {} seconds, got: {}", MAX_SECS, secs),
+        );
+    }
+    if let Some(secs) = args
+        .demo
+        .filter(|s| !(s.is_finite() && *s > 0.0 && *s <= MAX_SECS))
+    {
+        push(
+            "demo",
+            format!(
+                "expected a positive number of seconds up to {}, got: {}",
+                MAX_SECS, secs
+            ),
+        );
+    }
+    if let Some(minutes) = args.idle.filter(|m| !(m.is_finite() && *m > 0.0)) {
+        push(
+            "idle",
+            format!("expected a positive number of minutes, got: {}", minutes),
+        );
+    } else if args.idle.is_some_and(|m| m * 60.0 > MAX_SECS) {
+        push("idle", "too long".to_string());
+    }
+
+    match &args.command {
+        Some(Command::Bench { frames, size }) => {
+            if *frames == 0 {
+                push("frames", "expected at least one frame".to_string());
+            }
+            if let Err(e) = parse_size(size) {
+                push("size", e);
+            }
+        }
+        Some(Command::Record { size, .. }) => {
+            if let Err(e) = parse_size(size) {
+                push("size", e);
+            }
+        }
+        _ => {}
+    }
+
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.remove(0)),
+        _ => Err(CosmostrixError::Several(errors)),
+    }
+}
+
+fn list(s: &Option<String>) -> impl Iterator<Item = &str> {
+    s.as_deref()
+        .into_iter()
+        .flat_map(|s| s.split(','))
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+}
+
+// Adds the closest of the option's choices to `reason`, when one is close enough to be a
+// likely typo or `value` is the start of it.
+pub fn hint(reason: String, value: &str, option: &str) -> String {
+    let value = value.trim().to_ascii_lowercase();
+    let best = choices(option)
+        .iter()
+        .map(|&n| {
+            (
+                if n.starts_with(&value) && !value.is_empty() {
+                    1
+                } else {
+                    distance(&value, n)
+                },
+                n,
+            )
+        })
+        .filter(|&(d, n)| d > 0 && d <= (n.len() / 3).max(1))
+        .min_by_key(|&(d, _)| d);
+    match best {
+        Some((_, name)) => format!("{}; did you mean {}?", reason, name),
+        None => reason,
+    }
+}
+
+// Edit distance between two short names, counting a swap of neighbouring
+// letters as one edit.
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, v) in d[0].iter_mut().enumerate() {
+        *v = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j - 1] + cost)
+                .min(d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
@@ -0,0 +1,127 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::fmt::Write;
+
+use wasm_bindgen::prelude::*;
+
+use crate::cell::{Cell, Color};
+use crate::charset::{build_chars, Charset};
+use crate::clock;
+use crate::cloud::Cloud;
+use crate::frame::Frame;
+use crate::palette::color_to_rgb;
+use crate::runtime::{BoldMode, ColorMode, ColorScheme, ShadingMode};
+
+// Drives the rain for a browser terminal such as xterm.js: each `tick` returns
+// the ANSI escapes that turn the previous frame into the current one.
+#[wasm_bindgen]
+pub struct Rain {
+    cloud: Cloud,
+    frame: Frame,
+    last: Option<Frame>,
+}
+
+#[wasm_bindgen]
+impl Rain {
+    #[wasm_bindgen(constructor)]
+    pub fn new(cols: u16, rows: u16, now_ms: f64) -> Rain {
+        clock::set_now_ms(now_ms);
+        let mut cloud = Cloud::new(
+            ColorMode::TrueColor,
+            false,
+            ShadingMode::Random,
+            BoldMode::Random,
+            false,
+            false,
+            ColorScheme::Green,
+            None,
+        );
+        cloud.init_chars(build_chars(Charset::DEFAULT, &[], true));
+        cloud.reset(cols, rows);
+        let frame = Frame::new(cols, rows, cloud.palette.bg);
+        Rain {
+            cloud,
+            frame,
+            last: None,
+        }
+    }
+
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.cloud.reset(cols, rows);
+        self.frame.resize(cols, rows, self.cloud.palette.bg);
+        self.last = None;
+        self.cloud.force_draw_everything();
+    }
+
+    // Index into the built-in schemes, in `--color` list order.
+    pub fn set_color_scheme(&mut self, index: usize) {
+        if let Some(&scheme) = ColorScheme::BUILTIN.get(index) {
+            self.cloud.set_color_scheme(scheme);
+        }
+    }
+
+    pub fn set_chars_per_sec(&mut self, cps: f32) {
+        self.cloud.set_chars_per_sec(cps.clamp(0.001, 1_000_000.0));
+    }
+
+    pub fn set_droplet_density(&mut self, density: f32) {
+        self.cloud.set_droplet_density(density.clamp(0.01, 5.0));
+    }
+
+    pub fn tick(&mut self, now_ms: f64) -> String {
+        clock::set_now_ms(now_ms);
+        self.cloud.rain(&mut self.frame);
+        let out = diff(self.last.as_ref(), &self.frame);
+        match self.last.as_mut() {
+            Some(last) => last.clone_from(&self.frame),
+            None => self.last = Some(self.frame.clone()),
+        }
+        out
+    }
+}
+
+fn push_sgr(out: &mut String, cell: &Cell) {
+    out.push_str("\x1b[0");
+    if cell.bold {
+        out.push_str(";1");
+    }
+    if let Some((r, g, b)) = cell.fg.and_then(color_to_rgb) {
+        let _ = write!(out, ";38;2;{};{};{}", r, g, b);
+    }
+    if let Some((r, g, b)) = cell.bg.and_then(color_to_rgb) {
+        let _ = write!(out, ";48;2;{};{};{}", r, g, b);
+    }
+    out.push('m');
+}
+
+fn diff(last: Option<&Frame>, frame: &Frame) -> String {
+    let full = last.is_none_or(|l| l.width != frame.width || l.height != frame.height);
+    let mut out = String::new();
+    if full {
+        let bg = frame
+            .cells
+            .first()
+            .and_then(|c| c.bg)
+            .unwrap_or(Color::Black);
+        if let Some((r, g, b)) = color_to_rgb(bg) {
+            let _ = write!(out, "\x1b[48;2;{};{};{}m", r, g, b);
+        }
+        out.push_str("\x1b[2J");
+    }
+
+    let mut prev_style: Option<(Option<Color>, Option<Color>, bool)> = None;
+    let mut cursor: Option<(u16, u16)> = None;
+    for (x, y, cell) in frame.diff(last) {
+        if cursor != Some((x, y)) {
+            let _ = write!(out, "\x1b[{};{}H", y + 1, x + 1);
+        }
+        let style = (cell.fg, cell.bg, cell.bold);
+        if prev_style != Some(style) {
+            push_sgr(&mut out, &cell);
+            prev_style = Some(style);
+        }
+        out.push(cell.ch);
+        cursor = Some((x + cell.width(), y));
+    }
+    out
+}
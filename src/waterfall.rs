@@ -0,0 +1,149 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::time::Duration;
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::cell::Cell;
+use crate::clock::Instant;
+use crate::frame::Frame;
+use crate::palette::Palette;
+use crate::scene::Scene;
+
+const ROW_PERIOD: Duration = Duration::from_millis(50);
+const PEAKS: usize = 4;
+// Values below this are left blank so the background shows through.
+const FLOOR: f32 = 0.08;
+const ASCII_RAMP: [char; 4] = ['.', ':', '+', '#'];
+const SHADE_RAMP: [char; 4] = ['\u{2591}', '\u{2592}', '\u{2593}', '\u{2588}'];
+
+#[derive(Clone, Copy)]
+struct Peak {
+    center: f32,
+    width: f32,
+    height: f32,
+}
+
+// A spectrogram-style heatmap: every new row enters at the top and older
+// rows scroll down. Rows come from `feed` when something supplies data,
+// otherwise from drifting random peaks over noise.
+pub struct Waterfall {
+    // Ring of rows, `top` being the newest.
+    rows: Vec<f32>,
+    top: usize,
+    cols: u16,
+    lines: u16,
+    ascii: bool,
+    fed: bool,
+    peaks: [Peak; PEAKS],
+    rng: SmallRng,
+    last: Option<Instant>,
+}
+
+impl Waterfall {
+    pub fn new(ascii: bool) -> Self {
+        let mut rng = SmallRng::seed_from_u64(0x5eed_fa11);
+        let peaks = std::array::from_fn(|_| Peak {
+            center: rng.random_range(0.0..1.0),
+            width: rng.random_range(0.01..0.06),
+            height: rng.random_range(0.3..1.0),
+        });
+        Self {
+            rows: Vec::new(),
+            top: 0,
+            cols: 0,
+            lines: 0,
+            ascii,
+            fed: false,
+            peaks,
+            rng,
+            last: None,
+        }
+    }
+
+    fn push_row(&mut self) -> &mut [f32] {
+        let w = self.cols as usize;
+        let h = self.lines.max(1) as usize;
+        self.top = (self.top + h - 1) % h;
+        let start = self.top * w;
+        &mut self.rows[start..start + w]
+    }
+
+    fn random_row(&mut self) {
+        for p in &mut self.peaks {
+            p.center = (p.center + self.rng.random_range(-0.01..0.01)).rem_euclid(1.0);
+            p.height = (p.height + self.rng.random_range(-0.05..0.05)).clamp(0.2, 1.0);
+        }
+        let w = self.cols as usize;
+        let peaks = self.peaks;
+        let noise: Vec<f32> = (0..w).map(|_| self.rng.random_range(0.0..0.25)).collect();
+        let row = self.push_row();
+        for (x, v) in row.iter_mut().enumerate() {
+            let t = x as f32 / w.max(1) as f32;
+            let signal: f32 = peaks
+                .iter()
+                .map(|p| p.height * (-((t - p.center) / p.width).powi(2)).exp())
+                .sum();
+            *v = (signal + noise[x]).min(1.0);
+        }
+    }
+}
+
+impl Scene for Waterfall {
+    fn resize(&mut self, cols: u16, lines: u16) {
+        self.cols = cols;
+        self.lines = lines;
+        self.rows.clear();
+        self.rows.resize(cols as usize * lines as usize, 0.0);
+        self.top = 0;
+    }
+
+    // Starts a row from external values in 0..=1, stretched to the width.
+    // Once anything is fed, random rows stop.
+    fn feed(&mut self, values: &[f32]) {
+        self.fed = true;
+        if values.is_empty() || self.rows.is_empty() {
+            return;
+        }
+        let n = values.len();
+        let row = self.push_row();
+        let w = row.len();
+        for (x, v) in row.iter_mut().enumerate() {
+            let pos = x as f32 * (n - 1) as f32 / (w.max(2) - 1) as f32;
+            let i = pos as usize;
+            let frac = pos - i as f32;
+            let next = values[(i + 1).min(n - 1)];
+            *v = (values[i] * (1.0 - frac) + next * frac).clamp(0.0, 1.0);
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, palette: &Palette, now: Instant) {
+        let last = *self.last.get_or_insert(now);
+        if !self.fed && !self.rows.is_empty() && now.saturating_duration_since(last) >= ROW_PERIOD {
+            self.random_row();
+            self.last = Some(now);
+        }
+
+        frame.clear_with_bg(palette.bg);
+        let colors = &palette.colors;
+        let ramp = if self.ascii { ASCII_RAMP } else { SHADE_RAMP };
+        let (w, h) = (self.cols as usize, self.lines as usize);
+        for y in 0..h {
+            let row = (self.top + y) % h;
+            for x in 0..w {
+                let v = self.rows[row * w + x];
+                if v < FLOOR || colors.is_empty() {
+                    continue;
+                }
+                let n = colors.len();
+                let cell = Cell {
+                    ch: ramp[((v * ramp.len() as f32) as usize).min(ramp.len() - 1)],
+                    fg: Some(colors[((v * (n - 1) as f32).round() as usize).min(n - 1)]),
+                    bg: palette.bg,
+                    bold: v > 0.9,
+                };
+                frame.set(x as u16, y as u16, cell);
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright (c) 2025 rezk_nightky
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+use std::time::Duration;
+
+const HOST: &str = "wttr.in";
+const REFRESH: Duration = Duration::from_secs(15 * 60);
+const TIMEOUT: Duration = Duration::from_secs(10);
+// The reply is a one-line condition; anything much longer is not what we asked for.
+const MAX_RESPONSE: u64 = 16 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Weather {
+    Rain,
+    Fog,
+    Clear,
+    Other,
+}
+
+pub struct WeatherFeed {
+    rx: Receiver<Weather>,
+}
+
+impl WeatherFeed {
+    pub fn start(city: &str) -> Self {
+        let path = format!("/{}?format=%C", encode_city(city));
+        let (tx, rx) = channel();
+        thread::spawn(move || loop {
+            if let Ok(cond) = fetch(&path) {
+                if tx.send(classify(&cond)).is_err() {
+                    return;
+                }
+            }
+            thread::sleep(REFRESH);
+        });
+        Self { rx }
+    }
+
+    pub fn try_recv(&self) -> Option<Weather> {
+        self.rx.try_iter().last()
+    }
+}
+
+fn encode_city(city: &str) -> String {
+    let mut out = String::new();
+    for b in city.trim().bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' => out.push(b as char),
+            b' ' => out.push('+'),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+fn fetch(path: &str) -> Result<String> {
+    let mut stream = TcpStream::connect((HOST, 80))?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    // wttr.in picks HTML or text by client; ask for text outright.
+    write!(
+        stream,
+        "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: cosmostrix/{}\r\nAccept: text/plain\r\nConnection: close\r\n\r\n",
+        path,
+        HOST,
+        env!("CARGO_PKG_VERSION")
+    )?;
+
+    let mut resp = String::new();
+    stream.take(MAX_RESPONSE).read_to_string(&mut resp)?;
+    let body = resp
+        .split_once("\r\n\r\n")
+        .map(|(_, b)| b)
+        .unwrap_or("")
+        .trim();
+    if body.starts_with('<') {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "wttr.in sent HTML instead of text",
+        ));
+    }
+    Ok(body.lines().next().unwrap_or("").to_string())
+}
+
+fn classify(cond: &str) -> Weather {
+    let cond = cond.to_ascii_lowercase();
+    if ["rain", "drizzle", "shower", "thunder"]
+        .iter()
+        .any(|k| cond.contains(k))
+    {
+        Weather::Rain
+    } else if ["fog", "mist", "haze"].iter().any(|k| cond.contains(k)) {
+        Weather::Fog
+    } else if ["clear", "sunny"].iter().any(|k| cond.contains(k)) {
+        Weather::Clear
+    } else {
+        Weather::Other
+    }
+}
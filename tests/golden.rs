@@ -0,0 +1,621 @@
+// Copyright (c) 2025 rezk_nightky
+
+// Seeded end-to-end runs of the rain, snapshotted at fixed ticks and compared
+// with the files in tests/golden/. Run with COSMOSTRIX_BLESS=1 to rewrite them
+// after an intended change.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use cosmostrix::cell::{Cell, Color};
+use cosmostrix::charset::{build_chars, Charset, Codepage};
+use cosmostrix::clock::{Clock, ManualClock, ScaledClock};
+use cosmostrix::cloud::Cloud;
+use cosmostrix::droplet::Droplet;
+use cosmostrix::effect::{Backdrop, Marquee, Message};
+use cosmostrix::frame::{Blend, Frame};
+use cosmostrix::palette::{color_to_rgb, HueCycle};
+use cosmostrix::render::{Renderer, TestBackend};
+use cosmostrix::runtime::{BoldMode, Collision, ColorMode, ColorScheme, ShadingMode};
+
+// One simulation step per tick.
+const TICK: Duration = Duration::from_micros(16_667);
+
+struct Harness {
+    cloud: Cloud,
+    frame: Frame,
+    backend: TestBackend,
+    clock: ManualClock,
+    tick: u32,
+}
+
+impl Harness {
+    fn new(cols: u16, lines: u16, shading: ShadingMode) -> Self {
+        let mut cloud = Cloud::new(
+            ColorMode::Color256,
+            false,
+            shading,
+            BoldMode::Random,
+            false,
+            false,
+            ColorScheme::Green,
+            None,
+        );
+        cloud.set_seed(42);
+        cloud.init_chars(build_chars(Charset::DEFAULT, &[], true));
+        let clock = ManualClock::new(Instant::now());
+        cloud.set_clock(Box::new(clock.clone()));
+        cloud.reset(cols, lines);
+        let frame = Frame::new(cols, lines, cloud.palette.bg);
+        Self {
+            cloud,
+            frame,
+            backend: TestBackend::default(),
+            clock,
+            tick: 0,
+        }
+    }
+
+    // Rains tick by tick up to `tick`, then records the frame.
+    fn run_to(&mut self, tick: u32) {
+        while self.tick < tick {
+            self.tick += 1;
+            self.clock.advance(TICK);
+            self.cloud.rain(&mut self.frame);
+        }
+        self.backend.draw(&self.frame).unwrap();
+    }
+
+    fn snapshots(mut self, ticks: &[u32]) -> String {
+        for &t in ticks {
+            self.run_to(t);
+        }
+        ticks
+            .iter()
+            .zip(&self.backend.frames)
+            .map(|(t, f)| format!("--- tick {} ---\n{}", t, f))
+            .collect()
+    }
+}
+
+fn check(name: &str, actual: &str) {
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "golden",
+        &format!("{}.txt", name),
+    ]
+    .iter()
+    .collect();
+    if std::env::var_os("COSMOSTRIX_BLESS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected =
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    assert!(
+        expected == actual,
+        "{} differs from the golden file; rerun with COSMOSTRIX_BLESS=1 if the change is intended\n{}",
+        name,
+        actual
+    );
+}
+
+#[test]
+fn droplet_motion() {
+    let h = Harness::new(24, 10, ShadingMode::Random);
+    check("droplet_motion", &h.snapshots(&[10, 40, 120]));
+}
+
+#[test]
+fn distance_shading() {
+    let h = Harness::new(24, 10, ShadingMode::DistanceFromHead);
+    check("distance_shading", &h.snapshots(&[60, 120]));
+}
+
+#[test]
+fn comet_heads() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.cloud.set_head_len(4);
+    check("comet_heads", &h.snapshots(&[40, 80]));
+}
+
+// A longer head only brightens the cells just behind each head; the rest of
+// the rain is what a one-cell head draws. Glitches are off, since they swap
+// glyphs in cells only the longer head redraws.
+#[test]
+fn comet_heads_brighten_behind_the_head() {
+    let mut plain = Harness::new(24, 10, ShadingMode::Random);
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    plain.cloud.glitchy = false;
+    h.cloud.glitchy = false;
+    h.cloud.set_head_len(4);
+    let colors = h.cloud.palette.colors.clone();
+    let shade = |c: &Cell| colors.iter().position(|&p| Some(p) == c.fg);
+    let mut brightened = 0;
+    for tick in 1..=120 {
+        plain.run_to(tick);
+        h.run_to(tick);
+        let live: Vec<&Droplet> = h.cloud.droplets().iter().filter(|d| d.is_alive).collect();
+        for d in live
+            .iter()
+            .filter(|d| d.is_head_crawling && d.head_cur_line < 10)
+        {
+            let head = h.frame.get(d.bound_col, d.head_cur_line).unwrap();
+            assert_eq!(
+                shade(head),
+                Some(colors.len() - 1),
+                "a head isn't the brightest at tick {}",
+                tick
+            );
+        }
+        for (i, (p, c)) in plain.frame.cells.iter().zip(&h.frame.cells).enumerate() {
+            assert_eq!(p.ch, c.ch);
+            if p == c {
+                continue;
+            }
+            let (x, y) = ((i % 24) as u16, (i / 24) as u16);
+            let behind_head = live
+                .iter()
+                .any(|d| d.bound_col == x && y < d.head_cur_line && d.head_cur_line - y < 4);
+            assert!(
+                behind_head,
+                "cell {},{} changed away from a head at tick {}",
+                x, y, tick
+            );
+            assert!(
+                shade(c) >= shade(p),
+                "cell {},{} got dimmer at tick {}",
+                x,
+                y,
+                tick
+            );
+            brightened += 1;
+        }
+    }
+    assert!(brightened > 0);
+}
+
+#[test]
+fn tail_ghost() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.cloud.set_tail_ghost(3);
+    check("tail_ghost", &h.snapshots(&[60, 120]));
+}
+
+// The cells just ahead of each erasing tail take the dimmest color.
+#[test]
+fn tail_ghost_dims_ahead_of_the_tail() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.cloud.set_tail_ghost(3);
+    let dimmest = h.cloud.palette.colors.first().copied();
+    let mut ghosts = 0;
+    for tick in 1..=120 {
+        h.run_to(tick);
+        let live: Vec<&Droplet> = h.cloud.droplets().iter().filter(|d| d.is_alive).collect();
+        for d in &live {
+            let Some(tp) = d.tail_put_line else {
+                continue;
+            };
+            // Droplets sharing a column can draw over each other.
+            if live.iter().filter(|o| o.bound_col == d.bound_col).count() > 1 {
+                continue;
+            }
+            for y in (tp + 1..=tp + 3).filter(|&y| y < d.head_cur_line && y < 10) {
+                let c = h.frame.get(d.bound_col, y).unwrap();
+                assert!(
+                    c.fg == dimmest && !c.bold,
+                    "cell {},{} isn't a ghost at tick {}",
+                    d.bound_col,
+                    y,
+                    tick
+                );
+                ghosts += 1;
+            }
+        }
+    }
+    assert!(ghosts > 0);
+}
+
+#[test]
+fn collisions() {
+    for (name, collision) in [
+        ("collision_merge", Collision::Merge),
+        ("collision_queue", Collision::Queue),
+    ] {
+        let mut h = Harness::new(24, 16, ShadingMode::Random);
+        h.cloud.set_speed_jitter(0.9);
+        h.cloud.set_collision(collision);
+        check(name, &h.snapshots(&[300, 600, 900, 1200]));
+
+        let mut h = Harness::new(24, 16, ShadingMode::Random);
+        h.cloud.set_speed_jitter(0.9);
+        h.cloud.set_collision(collision);
+        let mut heads = Vec::new();
+        for tick in 1..=1200 {
+            h.run_to(tick);
+            heads.clear();
+            heads.extend(
+                h.cloud
+                    .droplets()
+                    .iter()
+                    .filter(|d| d.is_alive)
+                    .map(|d| (d.bound_col, d.head_put_line)),
+            );
+            heads.sort_unstable();
+            assert!(
+                heads.windows(2).all(|w| w[0] != w[1]),
+                "{}: two heads share a cell at tick {}",
+                name,
+                tick
+            );
+        }
+    }
+}
+
+#[test]
+fn stable_glyphs() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.cloud.set_stable_glyphs(true);
+    h.cloud.set_glitch_pct(1.0);
+    check("stable_glyphs", &h.snapshots(&[40, 80]));
+}
+
+// A cell shows the same glyph for as long as droplets keep it lit.
+#[test]
+fn stable_glyphs_hold_still() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.cloud.set_stable_glyphs(true);
+    h.cloud.set_glitch_pct(1.0);
+    h.run_to(1);
+    for tick in 2..=240 {
+        let last = h.frame.clone();
+        h.run_to(tick);
+        for (a, b) in last.cells.iter().zip(&h.frame.cells) {
+            assert!(
+                a.ch == ' ' || b.ch == ' ' || a.ch == b.ch,
+                "glyph changed at tick {}",
+                tick
+            );
+        }
+    }
+}
+
+#[test]
+fn glitch_drift() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.cloud.set_glitch_drift(4);
+    check("glitch_drift", &h.snapshots(&[60, 120, 240]));
+}
+
+// Drifting moves glitched cells around without changing how many there are.
+#[test]
+fn glitch_drift_keeps_the_count() {
+    let glitched = |drift: u16| {
+        let mut h = Harness::new(24, 10, ShadingMode::Random);
+        h.cloud.set_glitch_drift(drift);
+        let cells = |c: &Cloud| {
+            let all = (0..24).flat_map(|x| (0..10).map(move |y| (x, y)));
+            all.filter(|&(x, y)| c.is_glitched(y, x))
+                .collect::<Vec<_>>()
+        };
+        let before = cells(&h.cloud);
+        h.run_to(240);
+        (before, cells(&h.cloud))
+    };
+    let (before, after) = glitched(0);
+    assert_eq!(before, after);
+    let (before, after) = glitched(4);
+    assert!(!before.is_empty());
+    assert_eq!(before.len(), after.len());
+    assert_ne!(before, after);
+}
+
+#[test]
+fn after_image_decay() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.cloud.set_decay(Some(Duration::from_millis(600)));
+    check("after_image_decay", &h.snapshots(&[60, 90, 120]));
+}
+
+// Decay only leaves glyphs where the plain rain has gone blank, and those
+// only get dimmer until they go.
+#[test]
+fn after_image_only_fades() {
+    let mut plain = Harness::new(24, 10, ShadingMode::Random);
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.cloud.set_decay(Some(Duration::from_millis(600)));
+    let brightness = |c: &Cell| {
+        c.fg.and_then(color_to_rgb)
+            .map_or(0, |(r, g, b)| r as u32 + g as u32 + b as u32)
+    };
+    let mut left = 0;
+    let mut last = h.frame.clone();
+    for tick in 1..=240 {
+        plain.run_to(tick);
+        h.run_to(tick);
+        for (i, (p, c)) in plain.frame.cells.iter().zip(&h.frame.cells).enumerate() {
+            if p.ch != ' ' {
+                assert_eq!(p, c, "decay changed a lit cell at tick {}", tick);
+            } else if c.ch != ' ' {
+                left += 1;
+                assert!(!c.bold, "a leftover glyph is bold at tick {}", tick);
+                let before = &last.cells[i];
+                if before.ch == c.ch {
+                    assert!(
+                        brightness(c) <= brightness(before),
+                        "a leftover glyph brightened at tick {}",
+                        tick
+                    );
+                }
+            }
+        }
+        last = h.frame.clone();
+    }
+    assert!(left > 0);
+}
+
+#[test]
+fn reshade() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.cloud.set_reshade(Some(Duration::from_millis(500)));
+    check("reshade", &h.snapshots(&[60, 90, 120]));
+}
+
+// With glitches off, the heads and tails are all that move a lit cell from
+// one shade to another, unless the shading is drifting between the middle
+// shades.
+#[test]
+fn reshade_recolors_lit_cells() {
+    let recolored = |reshade: Option<Duration>| {
+        let mut h = Harness::new(24, 10, ShadingMode::Random);
+        h.cloud.glitchy = false;
+        h.cloud.set_reshade(reshade);
+        let (dimmest, brightest) = (
+            h.cloud.palette.colors.first().copied(),
+            h.cloud.palette.colors.last().copied(),
+        );
+        let middle = |c: &Cell| c.ch != ' ' && c.fg != dimmest && c.fg != brightest;
+        let mut n = 0;
+        h.run_to(1);
+        for tick in 2..=240 {
+            let last = h.frame.clone();
+            h.run_to(tick);
+            n += last
+                .cells
+                .iter()
+                .zip(&h.frame.cells)
+                .filter(|(a, b)| middle(a) && middle(b) && a.ch == b.ch && a.fg != b.fg)
+                .count();
+        }
+        n
+    };
+    assert_eq!(recolored(None), 0);
+    assert!(recolored(Some(Duration::from_millis(500))) > 0);
+}
+
+#[test]
+fn heat_map_tints_where_heads_passed() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.cloud.toggle_heat_map();
+    h.run_to(60);
+    let bg = h.cloud.palette.bg;
+    assert!(h.frame.cells.iter().any(|c| c.bg != bg));
+    h.cloud.toggle_heat_map();
+    h.run_to(61);
+    assert!(h.frame.cells.iter().all(|c| c.bg == bg));
+}
+
+#[test]
+fn reserved_columns_stay_dry() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.run_to(60);
+    h.cloud.reserve_columns(0..12);
+    h.run_to(120);
+    let dry =
+        |f: &Frame| (0..10).all(|y| (0..12).all(|x| f.get(x, y).is_some_and(|c| c.ch == ' ')));
+    assert!(dry(&h.frame));
+    // What the host drew there goes with the next frame after a release.
+    let panel = Cell {
+        ch: '\u{2588}',
+        fg: None,
+        bg: None,
+        bold: false,
+    };
+    for x in 0..12 {
+        h.frame.set(x, 0, panel);
+    }
+    h.cloud.release_columns(0..12);
+    h.run_to(121);
+    assert!((0..12).all(|x| h.frame.get(x, 0).is_some_and(|c| *c != panel)));
+    h.run_to(300);
+    assert!(!dry(&h.frame));
+}
+
+#[test]
+fn composite_blend_rules() {
+    let cell = |ch: char, fg: u8| Cell {
+        ch,
+        fg: Some(Color::AnsiValue(fg)),
+        bg: None,
+        bold: true,
+    };
+    let mut under = Frame::new(3, 1, None);
+    for x in 0..3 {
+        under.set(x, 0, cell('u', 1));
+    }
+    let mut over = Frame::new(2, 1, None);
+    over.set(0, 0, cell('o', 2));
+
+    let mut f = under.clone();
+    f.composite(&over, 1, 0, Blend::Overwrite);
+    assert_eq!(f.cells.iter().map(|c| c.ch).collect::<String>(), "uo ");
+
+    let mut f = under.clone();
+    f.composite(&over, 1, 0, Blend::SkipBlank);
+    assert_eq!(f.cells.iter().map(|c| c.ch).collect::<String>(), "uou");
+    assert_eq!(f.cells[2], cell('u', 1));
+
+    let mut f = under.clone();
+    f.composite(&over, 1, 0, Blend::DimUnder(Some(Color::AnsiValue(3))));
+    assert_eq!(f.cells.iter().map(|c| c.ch).collect::<String>(), "uou");
+    assert_eq!(f.cells[0], cell('u', 1));
+    assert_eq!(f.cells[2].fg, Some(Color::AnsiValue(3)));
+    assert!(!f.cells[2].bold);
+}
+
+#[test]
+fn frame_diff_yields_changed_cells() {
+    let glyph = |ch: char| Cell {
+        ch,
+        fg: Some(Color::AnsiValue(2)),
+        bg: None,
+        bold: false,
+    };
+    let last = Frame::new(4, 2, None);
+    let mut frame = last.clone();
+    frame.set(1, 0, glyph('a'));
+    frame.set(2, 1, glyph('\u{30a2}'));
+
+    let changed: Vec<(u16, u16, char)> = frame
+        .diff(Some(&last))
+        .map(|(x, y, c)| (x, y, c.ch))
+        .collect();
+    assert_eq!(changed, [(1, 0, 'a'), (2, 1, '\u{30a2}')]);
+    assert_eq!(frame.diff(Some(&frame)).count(), 0);
+    // No previous frame, or one of another size: every cell but the
+    // continuation of the wide glyph.
+    assert_eq!(frame.diff(None).count(), 7);
+    assert_eq!(frame.diff(Some(&Frame::new(3, 2, None))).count(), 7);
+}
+
+#[test]
+fn hue_cycle_turns_the_colors() {
+    let mut a = Harness::new(24, 10, ShadingMode::Random);
+    let mut b = Harness::new(24, 10, ShadingMode::Random);
+    b.cloud
+        .set_palette_source(Some(Box::new(HueCycle::new(2.0, ColorMode::Color256))));
+    a.run_to(30);
+    b.run_to(30);
+    let glyphs = |f: &Frame| f.cells.iter().map(|c| c.ch).collect::<String>();
+    assert_eq!(glyphs(&a.frame), glyphs(&b.frame));
+    assert_ne!(a.backend.frames, b.backend.frames);
+}
+
+#[test]
+fn message_reveal() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.cloud.add_effect(Box::new(Message::new("wake up")));
+    check("message_reveal", &h.snapshots(&[30, 90, 240]));
+}
+
+#[test]
+fn message_timing() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    let mut message = Message::new("wake up");
+    message.set_timing(Duration::from_secs(1), Some(Duration::from_secs(1)));
+    h.cloud.add_effect(Box::new(message));
+    check("message_timing", &h.snapshots(&[50, 240, 480]));
+}
+
+#[test]
+fn message_scatter() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    let mut message = Message::from_list(vec!["wake up".to_string(), "knock".to_string()]);
+    message.set_timing(Duration::ZERO, Some(Duration::from_secs(1)));
+    message.set_scatter(true);
+    h.cloud.add_effect(Box::new(message));
+    check("message_scatter", &h.snapshots(&[120, 360, 600]));
+}
+
+#[test]
+fn marquee() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.cloud
+        .add_effect(Box::new(Marquee::new("there is no spoon", Some(2), 12.0)));
+    check("marquee", &h.snapshots(&[30, 90, 180]));
+}
+
+#[test]
+fn backdrop() {
+    let mut h = Harness::new(24, 10, ShadingMode::Random);
+    h.cloud.add_effect(Box::new(Backdrop::new(
+        "\x1b[32m/\\_/\\\x1b[0m\n( o.o )\n > ^ <\n",
+    )));
+    check("backdrop", &h.snapshots(&[1, 60, 150]));
+}
+
+#[test]
+fn same_seed_same_frames() {
+    let a = Harness::new(30, 12, ShadingMode::Random).snapshots(&[50, 100]);
+    let b = Harness::new(30, 12, ShadingMode::Random).snapshots(&[50, 100]);
+    assert_eq!(a, b);
+}
+
+#[test]
+fn threads_draw_the_same_frames() {
+    let mut h = Harness::new(40, 12, ShadingMode::Random);
+    h.cloud.set_threads(4);
+    let serial = Harness::new(40, 12, ShadingMode::Random).snapshots(&[30, 90]);
+    assert_eq!(h.snapshots(&[30, 90]), serial);
+}
+
+#[test]
+fn step_then_draw_matches_rain_at() {
+    let mut a = Harness::new(30, 12, ShadingMode::Random);
+    a.run_to(90);
+    let mut b = Harness::new(30, 12, ShadingMode::Random);
+    for _ in 0..90 {
+        b.cloud.step(TICK);
+        b.cloud.draw(&mut b.frame);
+    }
+    b.backend.draw(&b.frame).unwrap();
+    assert_eq!(a.backend.frames, b.backend.frames);
+}
+
+#[test]
+fn pause_leaves_no_gap() {
+    let mut a = Harness::new(30, 12, ShadingMode::Random);
+    a.run_to(60);
+    a.cloud.toggle_pause();
+    a.clock.advance(Duration::from_secs(5));
+    a.cloud.toggle_pause();
+    a.run_to(120);
+    let mut b = Harness::new(30, 12, ShadingMode::Random);
+    b.run_to(60);
+    b.run_to(120);
+    assert_eq!(a.backend.frames, b.backend.frames);
+}
+
+#[test]
+fn scaled_clock_runs_at_its_factor() {
+    let manual = ManualClock::new(Instant::now());
+    let scaled = ScaledClock::new(Box::new(manual.clone()), 0.5);
+    let t0 = scaled.now();
+    manual.advance(Duration::from_secs(2));
+    assert_eq!(scaled.now() - t0, Duration::from_secs(1));
+    scaled.set_factor(2.0);
+    manual.advance(Duration::from_secs(1));
+    assert_eq!(scaled.now() - t0, Duration::from_secs(3));
+}
+
+#[test]
+fn codepage_fits_the_charset() {
+    assert_eq!(Codepage::from_locale("ru_RU.KOI8-R"), Some(Codepage::Koi8R));
+    assert_eq!(
+        Codepage::from_locale("el_GR.ISO-8859-7@euro"),
+        Some(Codepage::Iso8859_7)
+    );
+    assert_eq!(Codepage::from_locale("en_US.UTF-8"), None);
+    assert_eq!(Codepage::from_locale("C"), None);
+
+    let cyrillic = build_chars(Charset::CYRILLIC, &[], false);
+    assert_eq!(Codepage::Koi8R.fit(cyrillic.clone()), cyrillic);
+    let latin = Codepage::Latin.fit(cyrillic);
+    assert!(latin.iter().all(|c| c.is_ascii()));
+    assert!(latin.contains(&'Z') && latin.contains(&'z'));
+    let katakana = build_chars(Charset::KATAKANA, &[], false);
+    assert!(Codepage::Iso8859_5.fit(katakana.clone()).is_empty());
+    assert_eq!(Codepage::EucJp.fit(katakana.clone()), katakana);
+}